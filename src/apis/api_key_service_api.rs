@@ -4,10 +4,11 @@
  * For consistency with the rest of this codebase, this file matches the output of the terrible openapi generator.
  */
 
+use chrono::{DateTime, Utc};
 use hex;
 use reqwest;
 
-use crate::{apis::ResponseContent, propelauth::auth::AUTH_HOSTNAME_HEADER};
+use crate::apis::ResponseContent;
 
 use super::{configuration, Error};
 
@@ -30,6 +31,19 @@ pub struct CreateApiKeyParams {
     pub org_id: Option<String>,
 }
 
+impl CreateApiKeyParams {
+    /// `None` if the key won't expire, or if `expires_at_seconds` is out of range
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at_seconds
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+    }
+
+    /// `None` creates a key that never expires
+    pub fn set_expires_at(&mut self, expires_at: Option<DateTime<Utc>>) {
+        self.expires_at_seconds = expires_at.map(|expires_at| expires_at.timestamp());
+    }
+}
+
 /// struct for passing parameters to the method [`update_api_key`]
 #[derive(Clone, Debug, Default, Serialize)]
 pub struct UpdateApiKeyParams {
@@ -38,6 +52,21 @@ pub struct UpdateApiKeyParams {
     pub set_to_never_expire: Option<bool>,
 }
 
+impl UpdateApiKeyParams {
+    /// `None` unless a new expiration is set, or if `expires_at_seconds` is out of range
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at_seconds
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+    }
+
+    /// Changes when the key expires, where `None` makes it never expire. Leave it unset to keep
+    /// the current expiration.
+    pub fn set_expires_at(&mut self, expires_at: Option<DateTime<Utc>>) {
+        self.expires_at_seconds = expires_at.map(|expires_at| expires_at.timestamp());
+        self.set_to_never_expire = expires_at.is_none().then_some(true);
+    }
+}
+
 /// struct for passing parameters to the method [`validate_api_key`]
 #[derive(Clone, Debug, Default, Serialize)]
 pub struct ValidateApiKeyParams {
@@ -108,16 +137,7 @@ pub async fn fetch_current_api_keys(
         req_builder = req_builder.query(&[("page_number", page_number)]);
     }
 
-    if let Some(ref user_agent) = configuration.user_agent {
-        req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
-    }
-    if let Some(ref bearer_token) = configuration.bearer_access_token {
-        req_builder = req_builder.bearer_auth(bearer_token.to_owned());
-    }
-    req_builder = req_builder.header(AUTH_HOSTNAME_HEADER, configuration.auth_hostname.to_owned());
-
-    let req = req_builder.build()?;
-    let resp = client.execute(req).await?;
+    let resp = configuration.execute(req_builder).await?;
 
     let status = resp.status();
     let content = resp.text().await?;
@@ -164,16 +184,7 @@ pub async fn fetch_archived_api_keys(
         req_builder = req_builder.query(&[("page_number", page_number)]);
     }
 
-    if let Some(ref user_agent) = configuration.user_agent {
-        req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
-    }
-    if let Some(ref bearer_token) = configuration.bearer_access_token {
-        req_builder = req_builder.bearer_auth(bearer_token.to_owned());
-    }
-    req_builder = req_builder.header(AUTH_HOSTNAME_HEADER, configuration.auth_hostname.to_owned());
-
-    let req = req_builder.build()?;
-    let resp = client.execute(req).await?;
+    let resp = configuration.execute(req_builder).await?;
 
     let status = resp.status();
     let content = resp.text().await?;
@@ -205,18 +216,9 @@ pub async fn fetch_api_key(
         "{}/api/backend/v1/end_user_api_keys/{}",
         configuration.base_path, api_key_id
     );
-    let mut req_builder = client.request(reqwest::Method::GET, uri.as_str());
+    let req_builder = client.request(reqwest::Method::GET, uri.as_str());
 
-    if let Some(ref user_agent) = configuration.user_agent {
-        req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
-    }
-    if let Some(ref bearer_token) = configuration.bearer_access_token {
-        req_builder = req_builder.bearer_auth(bearer_token.to_owned());
-    }
-    req_builder = req_builder.header(AUTH_HOSTNAME_HEADER, configuration.auth_hostname.to_owned());
-
-    let req = req_builder.build()?;
-    let resp = client.execute(req).await?;
+    let resp = configuration.execute(req_builder).await?;
 
     let status = resp.status();
     let content = resp.text().await?;
@@ -246,18 +248,9 @@ pub async fn create_api_key(
     );
     let mut req_builder = client.request(reqwest::Method::POST, uri.as_str());
 
-    if let Some(ref user_agent) = configuration.user_agent {
-        req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
-    }
-    if let Some(ref bearer_token) = configuration.bearer_access_token {
-        req_builder = req_builder.bearer_auth(bearer_token.to_owned());
-    }
-    req_builder = req_builder.header(AUTH_HOSTNAME_HEADER, configuration.auth_hostname.to_owned());
-
     req_builder = req_builder.json(&params);
 
-    let req = req_builder.build()?;
-    let resp = client.execute(req).await?;
+    let resp = configuration.execute(req_builder).await?;
 
     let status = resp.status();
     let content = resp.text().await?;
@@ -292,18 +285,9 @@ pub async fn update_api_key(
     );
     let mut req_builder = client.request(reqwest::Method::PATCH, uri.as_str());
 
-    if let Some(ref user_agent) = configuration.user_agent {
-        req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
-    }
-    if let Some(ref bearer_token) = configuration.bearer_access_token {
-        req_builder = req_builder.bearer_auth(bearer_token.to_owned());
-    }
-    req_builder = req_builder.header(AUTH_HOSTNAME_HEADER, configuration.auth_hostname.to_owned());
-
     req_builder = req_builder.json(&params);
 
-    let req = req_builder.build()?;
-    let resp = client.execute(req).await?;
+    let resp = configuration.execute(req_builder).await?;
 
     let status = resp.status();
     let content = resp.text().await?;
@@ -335,18 +319,9 @@ pub async fn delete_api_key(
         "{}/api/backend/v1/end_user_api_keys/{}",
         configuration.base_path, api_key_id
     );
-    let mut req_builder = client.request(reqwest::Method::DELETE, uri.as_str());
-
-    if let Some(ref user_agent) = configuration.user_agent {
-        req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
-    }
-    if let Some(ref bearer_token) = configuration.bearer_access_token {
-        req_builder = req_builder.bearer_auth(bearer_token.to_owned());
-    }
-    req_builder = req_builder.header(AUTH_HOSTNAME_HEADER, configuration.auth_hostname.to_owned());
+    let req_builder = client.request(reqwest::Method::DELETE, uri.as_str());
 
-    let req = req_builder.build()?;
-    let resp = client.execute(req).await?;
+    let resp = configuration.execute(req_builder).await?;
 
     let status = resp.status();
     let content = resp.text().await?;
@@ -376,18 +351,9 @@ pub async fn validate_api_key(
     );
     let mut req_builder = client.request(reqwest::Method::POST, uri.as_str());
 
-    if let Some(ref user_agent) = configuration.user_agent {
-        req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
-    }
-    if let Some(ref bearer_token) = configuration.bearer_access_token {
-        req_builder = req_builder.bearer_auth(bearer_token.to_owned());
-    }
-    req_builder = req_builder.header(AUTH_HOSTNAME_HEADER, configuration.auth_hostname.to_owned());
-
     req_builder = req_builder.json(&params);
 
-    let req = req_builder.build()?;
-    let resp = client.execute(req).await?;
+    let resp = configuration.execute(req_builder).await?;
 
     let status = resp.status();
     let content = resp.text().await?;
@@ -404,3 +370,46 @@ pub async fn validate_api_key(
         Err(Error::ResponseError(error))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::{CreateApiKeyParams, UpdateApiKeyParams};
+
+    #[test]
+    fn expirations_round_trip_through_seconds() {
+        let expires_at = DateTime::from_timestamp(4_102_444_800, 0).unwrap();
+
+        let mut create = CreateApiKeyParams::default();
+        create.set_expires_at(Some(expires_at));
+        assert_eq!(Some(4_102_444_800), create.expires_at_seconds);
+        assert_eq!(Some(expires_at), create.expires_at());
+        create.set_expires_at(None);
+        assert_eq!(None, create.expires_at_seconds);
+        assert_eq!(None, create.expires_at());
+
+        let mut update = UpdateApiKeyParams::default();
+        update.set_expires_at(Some(expires_at));
+        assert_eq!(Some(4_102_444_800), update.expires_at_seconds);
+        assert_eq!(None, update.set_to_never_expire);
+        assert_eq!(Some(expires_at), update.expires_at());
+        update.set_expires_at(None);
+        assert_eq!(None, update.expires_at_seconds);
+        assert_eq!(Some(true), update.set_to_never_expire);
+    }
+
+    #[test]
+    fn out_of_range_expirations_are_none() {
+        let create = CreateApiKeyParams {
+            expires_at_seconds: Some(i64::MIN),
+            ..Default::default()
+        };
+        assert_eq!(None, create.expires_at());
+        let update = UpdateApiKeyParams {
+            expires_at_seconds: Some(i64::MAX),
+            ..Default::default()
+        };
+        assert_eq!(None, update.expires_at());
+    }
+}
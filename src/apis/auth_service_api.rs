@@ -11,7 +11,7 @@
 use reqwest;
 
 use super::{configuration, Error};
-use crate::{apis::ResponseContent, propelauth::auth::AUTH_HOSTNAME_HEADER};
+use crate::apis::ResponseContent;
 
 /// struct for typed errors of method [`token_verification_metadata`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,23 +34,12 @@ pub async fn token_verification_metadata(
         "{}/api/backend/v1/token_verification_metadata",
         local_var_configuration.base_path
     );
-    let mut local_var_req_builder =
+    let local_var_req_builder =
         local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
 
-    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
-        local_var_req_builder =
-            local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
-    }
-    if let Some(ref local_var_token) = local_var_configuration.bearer_access_token {
-        local_var_req_builder = local_var_req_builder.bearer_auth(local_var_token.to_owned());
-    };
-    local_var_req_builder = local_var_req_builder.header(
-        AUTH_HOSTNAME_HEADER,
-        local_var_configuration.auth_hostname.to_owned(),
-    );
-
-    let local_var_req = local_var_req_builder.build()?;
-    let local_var_resp = local_var_client.execute(local_var_req).await?;
+    let local_var_resp = local_var_configuration
+        .execute(local_var_req_builder)
+        .await?;
 
     let local_var_status = local_var_resp.status();
     let local_var_content = local_var_resp.text().await?;
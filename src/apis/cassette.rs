@@ -0,0 +1,415 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use reqwest::{Request, Response};
+use serde_json::Value;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// JSON fields whose values are credentials, and are never written to a cassette
+const REDACTED_FIELDS: [&str; 3] = ["api_key_token", "api_key", "access_token"];
+
+/// Whether a [`Cassette`] is saving real responses or serving saved ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Requests are sent to the backend API and every request/response pair is saved
+    Record,
+    /// No requests are sent. Responses come from the cassette, and requests that weren't
+    /// recorded fail with an [`io::ErrorKind::NotFound`] error
+    Replay,
+}
+
+/// Record/replay fixtures for backend API calls, so tests can run without reaching PropelAuth.
+///
+/// Set it with [`Configuration::set_cassette`](super::configuration::Configuration::set_cassette)
+/// or [`PropelAuth::with_cassette`](crate::propelauth::auth::PropelAuth::with_cassette).
+/// In [`CassetteMode::Record`] every request made through the `apis` layer is saved to a JSON
+/// file, rewritten after each call. The bearer token is never written and the values of
+/// `api_key_token`, `api_key` and `access_token` fields are replaced with `[REDACTED]`.
+///
+/// In [`CassetteMode::Replay`] requests are matched on method, path, query and JSON body
+/// (after the same redaction). Each recorded interaction is served once, in the order it was
+/// recorded, so repeating a request returns the responses that were seen when recording.
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Mutex<CassetteState>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    #[serde(skip)]
+    used: Vec<bool>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<RecordedBody>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    body: RecordedBody,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedBody {
+    Json(Value),
+    Text(String),
+}
+
+impl Cassette {
+    /// A cassette which records to `path`, replacing anything already saved there
+    pub fn record<P: AsRef<Path>>(path: P) -> Cassette {
+        Cassette {
+            path: path.as_ref().to_path_buf(),
+            mode: CassetteMode::Record,
+            state: Mutex::new(CassetteState::default()),
+        }
+    }
+
+    /// A cassette which replays the interactions previously recorded to `path`
+    pub fn replay<P: AsRef<Path>>(path: P) -> io::Result<Cassette> {
+        let contents = fs::read_to_string(path.as_ref())?;
+        let mut state: CassetteState = serde_json::from_str(&contents)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        state.used = vec![false; state.interactions.len()];
+
+        Ok(Cassette {
+            path: path.as_ref().to_path_buf(),
+            mode: CassetteMode::Replay,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Whether this cassette is recording or replaying
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// The file interactions are saved to or loaded from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// True once every recorded interaction has been replayed
+    pub fn is_exhausted(&self) -> bool {
+        self.state().used.iter().all(|used| *used)
+    }
+
+    pub(crate) fn replay_request(
+        &self,
+        request: &Request,
+        base_path: &str,
+        bearer_token: Option<&str>,
+    ) -> io::Result<Response> {
+        let recorded_request = RecordedRequest::from_request(request, base_path, bearer_token);
+
+        let mut state = self.state();
+        let CassetteState { interactions, used } = &mut *state;
+        let index = interactions
+            .iter()
+            .zip(used.iter())
+            .position(|(interaction, used)| !used && interaction.request == recorded_request)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "No unused interaction in {} matches {} {}",
+                        self.path.display(),
+                        recorded_request.method,
+                        recorded_request.path
+                    ),
+                )
+            })?;
+        used[index] = true;
+        interactions[index].response.to_response()
+    }
+
+    pub(crate) async fn record_response(
+        &self,
+        request: &RecordedRequest,
+        response: Response,
+        bearer_token: Option<&str>,
+    ) -> Result<Response, RecordError> {
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let bytes = response.bytes().await.map_err(RecordError::Reqwest)?;
+
+        let recorded_response = RecordedResponse {
+            status: status.as_u16(),
+            content_type,
+            body: RecordedBody::from_bytes(&bytes, bearer_token),
+        };
+        {
+            let mut state = self.state();
+            state.interactions.push(Interaction {
+                request: request.clone(),
+                response: recorded_response.clone(),
+            });
+            state.used.push(true);
+            let contents = serde_json::to_string_pretty(&*state)
+                .map_err(|e| RecordError::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+            fs::write(&self.path, contents).map_err(RecordError::Io)?;
+        }
+
+        // The caller still needs the real, unredacted response
+        let mut http_response = http::Response::new(bytes);
+        *http_response.status_mut() = status;
+        if let Some(content_type) = recorded_response.content_type {
+            if let Ok(value) = content_type.parse() {
+                http_response
+                    .headers_mut()
+                    .insert(reqwest::header::CONTENT_TYPE, value);
+            }
+        }
+        Ok(Response::from(http_response))
+    }
+
+    fn state(&self) -> MutexGuard<'_, CassetteState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl fmt::Debug for Cassette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cassette")
+            .field("path", &self.path)
+            .field("mode", &self.mode)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(crate) enum RecordError {
+    Reqwest(reqwest::Error),
+    Io(io::Error),
+}
+
+impl RecordedRequest {
+    pub(crate) fn from_request(
+        request: &Request,
+        base_path: &str,
+        bearer_token: Option<&str>,
+    ) -> RecordedRequest {
+        let url = request.url().as_str();
+        let path = url
+            .strip_prefix(base_path.trim_end_matches('/'))
+            .unwrap_or(url);
+
+        RecordedRequest {
+            method: request.method().to_string(),
+            path: redact_text(path, bearer_token),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .filter(|bytes| !bytes.is_empty())
+                .map(|bytes| RecordedBody::from_bytes(bytes, bearer_token)),
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn to_response(&self) -> io::Result<Response> {
+        let body = match &self.body {
+            RecordedBody::Json(value) => value.to_string(),
+            RecordedBody::Text(text) => text.clone(),
+        };
+        let mut builder = http::Response::builder().status(self.status);
+        if let Some(content_type) = &self.content_type {
+            builder = builder.header(reqwest::header::CONTENT_TYPE, content_type.as_str());
+        }
+        builder
+            .body(body)
+            .map(Response::from)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl RecordedBody {
+    fn from_bytes(bytes: &[u8], bearer_token: Option<&str>) -> RecordedBody {
+        match serde_json::from_slice::<Value>(bytes) {
+            Ok(mut value) => {
+                redact_value(&mut value, bearer_token);
+                RecordedBody::Json(value)
+            }
+            Err(_) => {
+                RecordedBody::Text(redact_text(&String::from_utf8_lossy(bytes), bearer_token))
+            }
+        }
+    }
+}
+
+fn redact_value(value: &mut Value, bearer_token: Option<&str>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if REDACTED_FIELDS.contains(&key.as_str()) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value, bearer_token);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                redact_value(value, bearer_token);
+            }
+        }
+        Value::String(s) => *s = redact_text(s, bearer_token),
+        _ => {}
+    }
+}
+
+fn redact_text(text: &str, bearer_token: Option<&str>) -> String {
+    match bearer_token {
+        Some(token) if !token.is_empty() => text.replace(token, REDACTED),
+        _ => text.to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use std::fs;
+
+    use uuid::Uuid;
+
+    use crate::apis::api_key_service_api::{CreateApiKeyParams, ValidateApiKeyParams};
+    use crate::apis::cassette::Cassette;
+    use crate::apis::user_service_api::FetchUserByIdParams;
+    use crate::models::{CreateUserRequest, UserMetadata};
+    use crate::propelauth::auth::PropelAuth;
+    use crate::propelauth::errors::ErrorsWithNotFound;
+    use crate::propelauth::options::AuthOptionsWithTokenVerification;
+    use crate::testing::FakeBackend;
+
+    async fn fetch_user(
+        auth: &PropelAuth,
+        user_id: &str,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        auth.user()
+            .fetch_user_by_id(FetchUserByIdParams {
+                user_id: user_id.to_string(),
+                include_orgs: None,
+            })
+            .await
+    }
+
+    #[test]
+    fn recorded_interactions_replay_without_the_backend() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.json", Uuid::new_v4()));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth().with_cassette(Cassette::record(&path));
+        let (user_id, api_key_token) = runtime.block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            assert!(fetch_user(&auth, &user_id).await.is_ok());
+
+            let api_key_token = auth
+                .api_key()
+                .create_api_key(CreateApiKeyParams {
+                    user_id: Some(user_id.clone()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .api_key_token;
+            assert!(auth
+                .api_key()
+                .validate_api_key(ValidateApiKeyParams {
+                    api_key_token: api_key_token.clone(),
+                })
+                .await
+                .is_ok());
+            (user_id, api_key_token)
+        });
+        let backend_api_key = fake.api_key().to_string();
+        drop(fake);
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains(&user_id));
+        assert!(!contents.contains(&backend_api_key));
+        assert!(!contents.contains(&api_key_token));
+
+        // Nothing is listening at the real backend URL in tests, so every response must be replayed
+        let auth = PropelAuth::init(AuthOptionsWithTokenVerification {
+            auth_url: "https://auth.example.com".to_string(),
+            api_key: "replayed".to_string(),
+            manual_token_verification_metadata: Default::default(),
+        })
+        .unwrap()
+        .with_cassette(Cassette::replay(&path).unwrap());
+        runtime.block_on(async {
+            auth.user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            assert_eq!(
+                "user@example.com",
+                fetch_user(&auth, &user_id).await.unwrap().email
+            );
+            let api_key_token = auth
+                .api_key()
+                .create_api_key(CreateApiKeyParams {
+                    user_id: Some(user_id.clone()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .api_key_token;
+            assert_eq!("[REDACTED]", api_key_token);
+
+            // Credentials are redacted before matching, so any well-formed key replays
+            assert!(auth
+                .api_key()
+                .validate_api_key(ValidateApiKeyParams {
+                    api_key_token: "deadbeef".to_string(),
+                })
+                .await
+                .is_ok());
+
+            // Each interaction is only served once, and nothing else was recorded
+            assert_eq!(
+                Err(ErrorsWithNotFound::UnexpectedException),
+                fetch_user(&auth, &user_id).await
+            );
+        });
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -8,8 +8,18 @@
  * Generated by: https://openapi-generator.tech
  */
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use reqwest;
 
+use super::cassette::{Cassette, CassetteMode, RecordError, RecordedRequest};
+use super::interceptor::Interceptor;
+use super::Error;
+use crate::propelauth::auth::AUTH_HOSTNAME_HEADER;
+use crate::propelauth::cache::{CacheBackend, ResponseCache};
+use crate::propelauth::coalesce::RequestCoalescer;
+
 #[derive(Debug, Clone)]
 pub struct Configuration {
     pub base_path: String,
@@ -20,6 +30,11 @@ pub struct Configuration {
     pub oauth_access_token: Option<String>,
     pub bearer_access_token: Option<String>,
     pub api_key: Option<ApiKey>,
+    pub interceptors: Vec<Arc<dyn Interceptor>>,
+    pub cassette: Option<Arc<Cassette>>,
+    pub request_coalescer: Option<Arc<RequestCoalescer>>,
+    pub cache: Option<ResponseCache>,
+    pub strict_password_hashes: bool,
     // TODO: take an oauth2 token source, similar to the go one
 }
 
@@ -42,6 +57,82 @@ impl Default for Configuration {
             oauth_access_token: None,
             bearer_access_token: None,
             api_key: None,
+            interceptors: Vec::new(),
+            cassette: None,
+            request_coalescer: None,
+            cache: None,
+            strict_password_hashes: false,
+        }
+    }
+}
+
+impl Configuration {
+    /// Registers an interceptor which will run on every backend API request
+    pub fn add_interceptor<I: Interceptor + 'static>(&mut self, interceptor: I) {
+        self.interceptors.push(Arc::new(interceptor));
+    }
+
+    /// Records every backend API request to, or replays them from, the given cassette
+    pub fn set_cassette(&mut self, cassette: Cassette) {
+        self.cassette = Some(Arc::new(cassette));
+    }
+
+    /// Shares in-flight idempotent reads between concurrent identical callers
+    pub fn enable_request_coalescing(&mut self) {
+        self.request_coalescer = Some(Arc::new(RequestCoalescer::new()));
+    }
+
+    /// Serves user and org lookups from `backend` for up to `ttl` after they are fetched
+    pub fn set_cache<B: CacheBackend + 'static>(&mut self, backend: B, ttl: Duration) {
+        self.cache = Some(ResponseCache {
+            backend: Arc::new(backend),
+            ttl,
+        });
+    }
+
+    /// The single request pipeline that every backend API call goes through. Applies the
+    /// default headers, runs the registered interceptors and sends the request (or replays it
+    /// from the cassette).
+    pub(crate) async fn execute<T>(
+        &self,
+        req_builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, Error<T>> {
+        let mut req_builder = req_builder;
+        if let Some(ref user_agent) = self.user_agent {
+            req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
+        }
+        if let Some(ref bearer_token) = self.bearer_access_token {
+            req_builder = req_builder.bearer_auth(bearer_token);
+        }
+        req_builder = req_builder.header(AUTH_HOSTNAME_HEADER, self.auth_hostname.to_owned());
+
+        let mut req = req_builder.build()?;
+        for interceptor in &self.interceptors {
+            interceptor.before_send(&mut req);
+        }
+
+        let bearer_token = self.bearer_access_token.as_deref();
+        let resp = match self.cassette.as_deref() {
+            Some(cassette) if cassette.mode() == CassetteMode::Replay => {
+                cassette.replay_request(&req, &self.base_path, bearer_token)?
+            }
+            Some(cassette) => {
+                let recorded_request =
+                    RecordedRequest::from_request(&req, &self.base_path, bearer_token);
+                let resp = self.client.execute(req).await?;
+                cassette
+                    .record_response(&recorded_request, resp, bearer_token)
+                    .await
+                    .map_err(|e| match e {
+                        RecordError::Reqwest(e) => Error::Reqwest(e),
+                        RecordError::Io(e) => Error::Io(e),
+                    })?
+            }
+            None => self.client.execute(req).await?,
+        };
+        for interceptor in &self.interceptors {
+            interceptor.after_response(&resp);
         }
+        Ok(resp)
     }
 }
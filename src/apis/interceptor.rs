@@ -0,0 +1,111 @@
+use std::fmt;
+
+use reqwest::{Request, Response};
+
+/// Hooks into every request made to the PropelAuth backend API.
+///
+/// Interceptors are registered on [`Configuration`](super::configuration::Configuration)
+/// (or through [`PropelAuth::with_interceptor`](crate::propelauth::auth::PropelAuth::with_interceptor))
+/// and run in the order they were added. This is useful for propagating correlation IDs,
+/// adding custom headers, or auditing calls without forking the crate.
+pub trait Interceptor: Send + Sync {
+    /// Called right before the request is sent, after the default headers
+    /// (user agent, bearer auth and `X-Propelauth-url`) have been applied.
+    fn before_send(&self, _request: &mut Request) {}
+
+    /// Called once the response headers have been received, before the body is read.
+    fn after_response(&self, _response: &Response) {}
+}
+
+impl fmt::Debug for dyn Interceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Interceptor")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use reqwest::header::HeaderValue;
+    use reqwest::{Request, Response};
+
+    use crate::apis::configuration::Configuration;
+    use crate::apis::interceptor::Interceptor;
+    use crate::apis::user_service_api::{fetch_user_by_id, FetchUserByIdParams};
+
+    struct CorrelationId {
+        statuses: Arc<Mutex<Vec<u16>>>,
+    }
+
+    impl Interceptor for CorrelationId {
+        fn before_send(&self, request: &mut Request) {
+            request
+                .headers_mut()
+                .insert("x-correlation-id", HeaderValue::from_static("abc123"));
+        }
+
+        fn after_response(&self, response: &Response) {
+            self.statuses
+                .lock()
+                .unwrap()
+                .push(response.status().as_u16());
+        }
+    }
+
+    #[test]
+    fn hooks_see_the_request_and_the_response() {
+        // Answers a single request with a 404 and hands back the request's header lines
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_path = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut headers = vec![];
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                headers.push(line.trim().to_ascii_lowercase());
+            }
+            reader
+                .get_mut()
+                .write_all(
+                    b"HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                )
+                .unwrap();
+            headers
+        });
+
+        let statuses = Arc::new(Mutex::new(vec![]));
+        let mut configuration = Configuration {
+            base_path,
+            ..Default::default()
+        };
+        configuration.add_interceptor(CorrelationId {
+            statuses: statuses.clone(),
+        });
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let result = runtime.block_on(fetch_user_by_id(
+            &configuration,
+            FetchUserByIdParams {
+                user_id: "e4b4a1e4-2c7b-4a62-9a83-3f3bdb0f1bd6".to_string(),
+                include_orgs: None,
+            },
+        ));
+        assert!(result.is_err());
+
+        let headers = server.join().unwrap();
+        assert!(headers.contains(&"x-correlation-id: abc123".to_string()));
+        assert_eq!(vec![404], *statuses.lock().unwrap());
+    }
+}
@@ -0,0 +1,945 @@
+//! A synchronous client for applications that don't run inside an async runtime.
+//!
+//! Enable the `blocking` feature to use it. It mirrors the async [`PropelAuth`](crate::propelauth::auth::PropelAuth)
+//! and shares the same models and error types:
+//!
+//! ```rust
+//! let auth = propelauth::blocking::PropelAuth::fetch_and_init(AuthOptions {
+//!     auth_url: "REPLACE_ME".to_string(),
+//!     api_key: "REPLACE_ME".to_string(),
+//! }).expect("Unable to initialize authentication");
+//!
+//! let user = auth.user().fetch_user_by_email(FetchUserByEmailParams {
+//!     email: "user@customer.com".to_string(),
+//!     include_orgs: Some(true),
+//! });
+//! ```
+//!
+//! Unlike what the name might suggest, this isn't built on `reqwest::blocking`. Every endpoint
+//! is written once, against the async `reqwest::Client`, and a second copy for
+//! `reqwest::blocking` would also need its own interceptor, cassette, cache and coalescing
+//! pipeline. Instead each client owns a single-worker Tokio runtime and blocks on the async
+//! services, which is also what `reqwest::blocking` does internally, so everything behaves
+//! exactly as it does for the async client.
+//!
+//! The same restriction as `reqwest::blocking` applies: `fetch_and_init` and the service methods
+//! panic when called from within an async runtime (e.g. inside `#[tokio::main]`), since blocking
+//! there would stall the runtime's worker. Use the async client there instead. Creating and
+//! dropping a client is fine anywhere.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::runtime::Runtime;
+
+use crate::apis::access_token_service_api::{CreateAccessTokenParams, CreateAccessTokenV2Params};
+use crate::apis::api_key_service_api::{
+    ApiKeyError, ApiKeyQueryParams, CreateApiKeyParams, UpdateApiKeyParams, ValidateApiKeyParams,
+};
+use crate::apis::interceptor::Interceptor;
+use crate::apis::org_service_api::{
+    AllowOrgToEnableSamlParams, CreateSamlConnectionLinkParams, DisallowSamlParams,
+    FetchOrgsByQueryParams, FetchPendingInvitesParams, FetchUsersInOrgParams,
+};
+use crate::apis::user_service_api::{
+    FetchUserByEmailParams, FetchUserByIdParams, FetchUserByUsernameParams,
+    FetchUsersByEmailsParams, FetchUsersByIdsParams, FetchUsersByQueryParams,
+    FetchUsersByUsernamesParams, UpdateUserEmailParams, UpdateUserMetadataParams,
+    UpdateUserPasswordParams,
+};
+use crate::models::validate_api_key_response::{
+    ValidateOrgApiKeyResponse, ValidatePersonalApiKeyResponse,
+};
+use crate::models::{
+    AddUserToOrgRequest, ChangeUserRoleInOrgRequest, CreateAccessTokenResponse,
+    CreateApiKeyResponse, CreateMagicLinkRequest, CreateOrgRequest, CreateOrgResponse,
+    CreateSamlConnectionLinkResponse, CreateUserRequest, CreatedUserResponse, FetchApiKeyResponse,
+    FetchApiKeysPagedResponse, FetchOrgResponse, FetchOrgsResponse, FetchSamlSpMetadataResponse,
+    InviteUserToOrgRequest, MagicLink, MigrateUserPasswordRequest, MigrateUserRequest,
+    RemoveUserFromOrgRequest, RevokePendingOrgInviteRequest, UpdateOrgRequest, UserMetadata,
+    UserPagedResponse, ValidateApiKeyResponse,
+};
+use crate::propelauth::errors::{
+    BatchFetchError, ClearPasswordError, CreateAccessTokenError, CreateMagicLinkError,
+    CreateOrgError, CreateUserError, ErrorsWithNotFound, FetchByQueryError, FetchOrgsByQueryError,
+    FetchUsersInOrgError, InitializationError, InviteUserToOrgError, MigrateUserError,
+    MigrateUserPasswordError, OrgMissingOrRoleError, ResendEmailConfirmationError,
+    SubscribeOrgToRoleMappingError, UpdateOrgError, UpdatePasswordError, UpdateUserEmailError,
+    UpdateUserMetadataError,
+};
+use crate::propelauth::ids::{ApiKeyId, OrgId, UserId};
+use crate::propelauth::options::{AuthOptions, AuthOptionsWithTokenVerification};
+use crate::propelauth::token::TokenService;
+
+/// The blocking entrypoint of this library.
+/// All authentication, authorization and API requests starts from this struct
+#[derive(Debug, Clone)]
+pub struct PropelAuth {
+    inner: crate::propelauth::auth::PropelAuth,
+    runtime: Arc<BlockingRuntime>,
+}
+
+impl PropelAuth {
+    /// Initializes the PropelAuth library without making any external requests. This contrasts
+    /// with `fetch_and_init` which will fetch the metadata needed to validate access tokens
+    pub fn init(opts: AuthOptionsWithTokenVerification) -> Result<PropelAuth, InitializationError> {
+        let inner = crate::propelauth::auth::PropelAuth::init(opts)?;
+        Ok(PropelAuth {
+            inner,
+            runtime: Arc::new(BlockingRuntime::new()?),
+        })
+    }
+
+    /// Initializes the PropelAuth library by making a single external request. This contrasts
+    /// with `init` where you manually specify the metadata needed to validate access tokens
+    pub fn fetch_and_init(opts: AuthOptions) -> Result<PropelAuth, InitializationError> {
+        let runtime = BlockingRuntime::new()?;
+        let inner = runtime.block_on(crate::propelauth::auth::PropelAuth::fetch_and_init(opts))?;
+        Ok(PropelAuth {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Registers an interceptor which will run on every backend API request
+    /// made through this instance, e.g. to add correlation IDs or audit calls
+    pub fn with_interceptor<I: Interceptor + 'static>(self, interceptor: I) -> PropelAuth {
+        PropelAuth {
+            inner: self.inner.with_interceptor(interceptor),
+            runtime: self.runtime,
+        }
+    }
+
+    /// API requests related to users
+    pub fn user(&self) -> UserService<'_> {
+        UserService {
+            inner: self.inner.user(),
+            runtime: &self.runtime,
+        }
+    }
+
+    /// API requests related to organizations
+    pub fn org(&self) -> OrgService<'_> {
+        OrgService {
+            inner: self.inner.org(),
+            runtime: &self.runtime,
+        }
+    }
+
+    /// API requests related to end user API keys
+    pub fn api_key(&self) -> ApiKeyService<'_> {
+        ApiKeyService {
+            inner: self.inner.api_key(),
+            runtime: &self.runtime,
+        }
+    }
+
+    /// Verify access tokens from your frontend. Token verification never blocks on the network,
+    /// so this is the same service the async client uses.
+    pub fn verify(&self) -> TokenService<'_> {
+        self.inner.verify()
+    }
+
+    /// API requests related to access tokens.
+    pub fn access_token(&self) -> AccessTokenService<'_> {
+        AccessTokenService {
+            inner: self.inner.access_token(),
+            runtime: &self.runtime,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BlockingRuntime {
+    // Only taken on drop
+    runtime: Option<Runtime>,
+}
+
+impl BlockingRuntime {
+    fn new() -> Result<BlockingRuntime, InitializationError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("propelauth-blocking")
+            .enable_all()
+            .build()
+            .map_err(|_| InitializationError::UnexpectedException)?;
+        Ok(BlockingRuntime {
+            runtime: Some(runtime),
+        })
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            panic!(
+                "propelauth::blocking can't be used from within an async runtime, use the async \
+                 PropelAuth client instead"
+            );
+        }
+        self.runtime
+            .as_ref()
+            .expect("runtime is only taken on drop")
+            .block_on(future)
+    }
+}
+
+impl Drop for BlockingRuntime {
+    fn drop(&mut self) {
+        // Dropping a runtime waits for its worker, which panics inside an async context.
+        // Nothing can be in flight at this point, so there's nothing to wait for.
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Blocking counterpart of [`crate::propelauth::user::UserService`]
+pub struct UserService<'a> {
+    inner: crate::propelauth::user::UserService<'a>,
+    runtime: &'a BlockingRuntime,
+}
+
+impl UserService<'_> {
+    pub fn fetch_user_by_email(
+        &self,
+        params: FetchUserByEmailParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.fetch_user_by_email(params))
+    }
+
+    pub fn fetch_user_by_id(
+        &self,
+        params: FetchUserByIdParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        self.runtime.block_on(self.inner.fetch_user_by_id(params))
+    }
+
+    pub fn fetch_user_by_username(
+        &self,
+        params: FetchUserByUsernameParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.fetch_user_by_username(params))
+    }
+
+    pub fn fetch_users_by_ids(
+        &self,
+        params: FetchUsersByIdsParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
+        self.runtime.block_on(self.inner.fetch_users_by_ids(params))
+    }
+
+    pub fn fetch_users_by_emails(
+        &self,
+        params: FetchUsersByEmailsParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
+        self.runtime
+            .block_on(self.inner.fetch_users_by_emails(params))
+    }
+
+    pub fn fetch_users_by_usernames(
+        &self,
+        params: FetchUsersByUsernamesParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
+        self.runtime
+            .block_on(self.inner.fetch_users_by_usernames(params))
+    }
+
+    pub fn fetch_users_by_query(
+        &self,
+        params: FetchUsersByQueryParams,
+    ) -> Result<UserPagedResponse, FetchByQueryError> {
+        self.runtime
+            .block_on(self.inner.fetch_users_by_query(params))
+    }
+
+    pub fn create_user(
+        &self,
+        create_user_request: CreateUserRequest,
+    ) -> Result<CreatedUserResponse, CreateUserError> {
+        self.runtime
+            .block_on(self.inner.create_user(create_user_request))
+    }
+
+    #[deprecated(note = "use `delete_user_by_id`, which takes an `UserId`")]
+    pub fn delete_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.delete_user_by_raw_id(user_id))
+    }
+
+    pub fn delete_user_by_id(&self, user_id: UserId) -> Result<(), ErrorsWithNotFound> {
+        self.runtime.block_on(self.inner.delete_user_by_id(user_id))
+    }
+
+    #[deprecated(note = "use `disable_user_by_id`, which takes an `UserId`")]
+    pub fn disable_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.disable_user_by_raw_id(user_id))
+    }
+
+    pub fn disable_user_by_id(&self, user_id: UserId) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.disable_user_by_id(user_id))
+    }
+
+    #[deprecated(note = "use `logout_all_user_sessions_by_id`, which takes an `UserId`")]
+    pub fn logout_all_user_sessions(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.logout_all_user_sessions_by_raw_id(user_id))
+    }
+
+    pub fn logout_all_user_sessions_by_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.logout_all_user_sessions_by_id(user_id))
+    }
+
+    #[deprecated(note = "use `enable_user_by_id`, which takes an `UserId`")]
+    pub fn enable_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.enable_user_by_raw_id(user_id))
+    }
+
+    pub fn enable_user_by_id(&self, user_id: UserId) -> Result<(), ErrorsWithNotFound> {
+        self.runtime.block_on(self.inner.enable_user_by_id(user_id))
+    }
+
+    pub fn update_user_metadata(
+        &self,
+        params: UpdateUserMetadataParams,
+    ) -> Result<(), UpdateUserMetadataError> {
+        self.runtime
+            .block_on(self.inner.update_user_metadata(params))
+    }
+
+    pub fn update_user_email(
+        &self,
+        params: UpdateUserEmailParams,
+    ) -> Result<(), UpdateUserEmailError> {
+        self.runtime.block_on(self.inner.update_user_email(params))
+    }
+
+    pub fn update_user_password(
+        &self,
+        params: UpdateUserPasswordParams,
+    ) -> Result<(), UpdatePasswordError> {
+        self.runtime
+            .block_on(self.inner.update_user_password(params))
+    }
+
+    #[deprecated(note = "use `clear_user_password_by_id`, which takes an `UserId`")]
+    pub fn clear_user_password(&self, user_id: String) -> Result<(), ClearPasswordError> {
+        self.runtime
+            .block_on(self.inner.clear_user_password_by_raw_id(user_id))
+    }
+
+    pub fn clear_user_password_by_id(&self, user_id: UserId) -> Result<(), ClearPasswordError> {
+        self.runtime
+            .block_on(self.inner.clear_user_password_by_id(user_id))
+    }
+
+    #[deprecated(note = "use `disable_user_2fa_by_id`, which takes an `UserId`")]
+    pub fn disable_user_2fa(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.disable_user_2fa_by_raw_id(user_id))
+    }
+
+    pub fn disable_user_2fa_by_id(&self, user_id: UserId) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.disable_user_2fa_by_id(user_id))
+    }
+
+    #[deprecated(note = "use `resend_email_confirmation_by_id`, which takes an `UserId`")]
+    pub fn resend_email_confirmation(
+        &self,
+        user_id: String,
+    ) -> Result<(), ResendEmailConfirmationError> {
+        self.runtime
+            .block_on(self.inner.resend_email_confirmation_by_raw_id(user_id))
+    }
+
+    pub fn resend_email_confirmation_by_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<(), ResendEmailConfirmationError> {
+        self.runtime
+            .block_on(self.inner.resend_email_confirmation_by_id(user_id))
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn migrate_user(
+        &self,
+        migrate_user_request: MigrateUserRequest,
+    ) -> Result<CreatedUserResponse, MigrateUserError> {
+        self.runtime
+            .block_on(self.inner.migrate_user(migrate_user_request))
+    }
+
+    pub fn migrate_user_password(
+        &self,
+        migrate_user_password_request: MigrateUserPasswordRequest,
+    ) -> Result<(), MigrateUserPasswordError> {
+        self.runtime.block_on(
+            self.inner
+                .migrate_user_password(migrate_user_password_request),
+        )
+    }
+
+    pub fn create_magic_link(
+        &self,
+        create_magic_link_request: CreateMagicLinkRequest,
+    ) -> Result<MagicLink, CreateMagicLinkError> {
+        self.runtime
+            .block_on(self.inner.create_magic_link(create_magic_link_request))
+    }
+
+    #[deprecated(note = "use `enable_user_can_create_orgs_by_id`, which takes an `UserId`")]
+    pub fn enable_user_can_create_orgs(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.enable_user_can_create_orgs_by_raw_id(user_id))
+    }
+
+    pub fn enable_user_can_create_orgs_by_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.enable_user_can_create_orgs_by_id(user_id))
+    }
+
+    #[deprecated(note = "use `disable_user_can_create_orgs_by_id`, which takes an `UserId`")]
+    pub fn disable_user_can_create_orgs(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.disable_user_can_create_orgs_by_raw_id(user_id))
+    }
+
+    pub fn disable_user_can_create_orgs_by_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.disable_user_can_create_orgs_by_id(user_id))
+    }
+}
+
+/// Blocking counterpart of [`crate::propelauth::org::OrgService`]
+pub struct OrgService<'a> {
+    inner: crate::propelauth::org::OrgService<'a>,
+    runtime: &'a BlockingRuntime,
+}
+
+impl OrgService<'_> {
+    #[deprecated(note = "use `fetch_org_by_id`, which takes an `OrgId`")]
+    pub fn fetch_org(&self, org_id: String) -> Result<FetchOrgResponse, ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.fetch_org_by_raw_id(org_id))
+    }
+
+    pub fn fetch_org_by_id(&self, org_id: OrgId) -> Result<FetchOrgResponse, ErrorsWithNotFound> {
+        self.runtime.block_on(self.inner.fetch_org_by_id(org_id))
+    }
+
+    pub fn fetch_orgs_by_query(
+        &self,
+        params: FetchOrgsByQueryParams,
+    ) -> Result<FetchOrgsResponse, FetchOrgsByQueryError> {
+        self.runtime
+            .block_on(self.inner.fetch_orgs_by_query(params))
+    }
+
+    pub fn fetch_users_in_org(
+        &self,
+        params: FetchUsersInOrgParams,
+    ) -> Result<UserPagedResponse, FetchUsersInOrgError> {
+        self.runtime.block_on(self.inner.fetch_users_in_org(params))
+    }
+
+    pub fn fetch_custom_role_mappings(
+        &self,
+    ) -> Result<crate::models::FetchCustomRoleMappingsResponse, ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.fetch_custom_role_mappings())
+    }
+
+    pub fn fetch_pending_invites(
+        &self,
+        params: FetchPendingInvitesParams,
+    ) -> Result<crate::models::FetchPendingInvitesResponse, ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.fetch_pending_invites(params))
+    }
+
+    pub fn add_user_to_org(
+        &self,
+        add_user_to_org_request: AddUserToOrgRequest,
+    ) -> Result<(), OrgMissingOrRoleError> {
+        self.runtime
+            .block_on(self.inner.add_user_to_org(add_user_to_org_request))
+    }
+
+    pub fn change_user_role_in_org(
+        &self,
+        change_user_role_in_org_request: ChangeUserRoleInOrgRequest,
+    ) -> Result<(), OrgMissingOrRoleError> {
+        self.runtime.block_on(
+            self.inner
+                .change_user_role_in_org(change_user_role_in_org_request),
+        )
+    }
+
+    pub fn remove_user_from_org(
+        &self,
+        remove_user_from_org_request: RemoveUserFromOrgRequest,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.runtime.block_on(
+            self.inner
+                .remove_user_from_org(remove_user_from_org_request),
+        )
+    }
+
+    pub fn create_org(
+        &self,
+        create_org_request: CreateOrgRequest,
+    ) -> Result<CreateOrgResponse, CreateOrgError> {
+        self.runtime
+            .block_on(self.inner.create_org(create_org_request))
+    }
+
+    #[deprecated(note = "use `update_org_by_id`, which takes an `OrgId`")]
+    pub fn update_org(
+        &self,
+        org_id: String,
+        update_org_request: UpdateOrgRequest,
+    ) -> Result<(), UpdateOrgError> {
+        self.runtime
+            .block_on(self.inner.update_org_by_raw_id(org_id, update_org_request))
+    }
+
+    pub fn update_org_by_id(
+        &self,
+        org_id: OrgId,
+        update_org_request: UpdateOrgRequest,
+    ) -> Result<(), UpdateOrgError> {
+        self.runtime
+            .block_on(self.inner.update_org_by_id(org_id, update_org_request))
+    }
+
+    pub fn subscribe_org_to_role_mapping(
+        &self,
+        org_id: String,
+        custom_role_mapping_name: String,
+    ) -> Result<(), SubscribeOrgToRoleMappingError> {
+        self.runtime.block_on(
+            self.inner
+                .subscribe_org_to_role_mapping(org_id, custom_role_mapping_name),
+        )
+    }
+
+    pub fn allow_org_to_enable_saml(
+        &self,
+        params: AllowOrgToEnableSamlParams,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.allow_org_to_enable_saml(params))
+    }
+
+    pub fn disallow_saml(&self, params: DisallowSamlParams) -> Result<(), ErrorsWithNotFound> {
+        self.runtime.block_on(self.inner.disallow_saml(params))
+    }
+
+    pub fn create_saml_connection_link(
+        &self,
+        params: CreateSamlConnectionLinkParams,
+    ) -> Result<CreateSamlConnectionLinkResponse, ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.create_saml_connection_link(params))
+    }
+
+    pub fn fetch_saml_sp_metadata(
+        &self,
+        org_id: String,
+    ) -> Result<FetchSamlSpMetadataResponse, ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.fetch_saml_sp_metadata(org_id))
+    }
+
+    pub fn set_saml_idp_metadata(
+        &self,
+        request: crate::models::SetSamlIdpMetadataRequest,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.set_saml_idp_metadata(request))
+    }
+
+    #[deprecated(note = "use `saml_go_live_by_id`, which takes an `OrgId`")]
+    pub fn saml_go_live(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.saml_go_live_by_raw_id(org_id))
+    }
+
+    pub fn saml_go_live_by_id(&self, org_id: OrgId) -> Result<(), ErrorsWithNotFound> {
+        self.runtime.block_on(self.inner.saml_go_live_by_id(org_id))
+    }
+
+    #[deprecated(note = "use `delete_saml_connection_by_id`, which takes an `OrgId`")]
+    pub fn delete_saml_connection(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.delete_saml_connection_by_raw_id(org_id))
+    }
+
+    pub fn delete_saml_connection_by_id(&self, org_id: OrgId) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.delete_saml_connection_by_id(org_id))
+    }
+
+    #[deprecated(note = "use `delete_org_by_id`, which takes an `OrgId`")]
+    pub fn delete_org(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.runtime
+            .block_on(self.inner.delete_org_by_raw_id(org_id))
+    }
+
+    pub fn delete_org_by_id(&self, org_id: OrgId) -> Result<(), ErrorsWithNotFound> {
+        self.runtime.block_on(self.inner.delete_org_by_id(org_id))
+    }
+
+    pub fn invite_user_to_org(
+        &self,
+        invite_user_to_org_request: InviteUserToOrgRequest,
+    ) -> Result<(), InviteUserToOrgError> {
+        self.runtime
+            .block_on(self.inner.invite_user_to_org(invite_user_to_org_request))
+    }
+
+    pub fn revoke_pending_org_invite(
+        &self,
+        revoke_pending_org_invite_request: RevokePendingOrgInviteRequest,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.runtime.block_on(
+            self.inner
+                .revoke_pending_org_invite(revoke_pending_org_invite_request),
+        )
+    }
+}
+
+/// Blocking counterpart of [`crate::propelauth::api_key::ApiKeyService`]
+pub struct ApiKeyService<'a> {
+    inner: crate::propelauth::api_key::ApiKeyService<'a>,
+    runtime: &'a BlockingRuntime,
+}
+
+impl ApiKeyService<'_> {
+    pub fn fetch_current_api_keys(
+        &self,
+        params: ApiKeyQueryParams,
+    ) -> Result<FetchApiKeysPagedResponse, ApiKeyError> {
+        self.runtime
+            .block_on(self.inner.fetch_current_api_keys(params))
+    }
+
+    pub fn fetch_archived_api_keys(
+        &self,
+        params: ApiKeyQueryParams,
+    ) -> Result<FetchApiKeysPagedResponse, ApiKeyError> {
+        self.runtime
+            .block_on(self.inner.fetch_archived_api_keys(params))
+    }
+
+    #[deprecated(note = "use `fetch_api_key_by_id`, which takes an `ApiKeyId`")]
+    pub fn fetch_api_key(&self, api_key_id: String) -> Result<FetchApiKeyResponse, ApiKeyError> {
+        self.runtime
+            .block_on(self.inner.fetch_api_key_by_raw_id(api_key_id))
+    }
+
+    pub fn fetch_api_key_by_id(
+        &self,
+        api_key_id: ApiKeyId,
+    ) -> Result<FetchApiKeyResponse, ApiKeyError> {
+        self.runtime
+            .block_on(self.inner.fetch_api_key_by_id(api_key_id))
+    }
+
+    pub fn create_api_key(
+        &self,
+        params: CreateApiKeyParams,
+    ) -> Result<CreateApiKeyResponse, ApiKeyError> {
+        self.runtime.block_on(self.inner.create_api_key(params))
+    }
+
+    #[deprecated(note = "use `update_api_key_by_id`, which takes an `ApiKeyId`")]
+    pub fn update_api_key(
+        &self,
+        api_key_id: String,
+        params: UpdateApiKeyParams,
+    ) -> Result<(), ApiKeyError> {
+        self.runtime
+            .block_on(self.inner.update_api_key_by_raw_id(api_key_id, params))
+    }
+
+    pub fn update_api_key_by_id(
+        &self,
+        api_key_id: ApiKeyId,
+        params: UpdateApiKeyParams,
+    ) -> Result<(), ApiKeyError> {
+        self.runtime
+            .block_on(self.inner.update_api_key_by_id(api_key_id, params))
+    }
+
+    #[deprecated(note = "use `delete_api_key_by_id`, which takes an `ApiKeyId`")]
+    pub fn delete_api_key(&self, api_key_id: String) -> Result<(), ApiKeyError> {
+        self.runtime
+            .block_on(self.inner.delete_api_key_by_raw_id(api_key_id))
+    }
+
+    pub fn delete_api_key_by_id(&self, api_key_id: ApiKeyId) -> Result<(), ApiKeyError> {
+        self.runtime
+            .block_on(self.inner.delete_api_key_by_id(api_key_id))
+    }
+
+    pub fn validate_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidateApiKeyResponse, ApiKeyError> {
+        self.runtime.block_on(self.inner.validate_api_key(params))
+    }
+
+    pub fn validate_personal_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidatePersonalApiKeyResponse, ApiKeyError> {
+        self.runtime
+            .block_on(self.inner.validate_personal_api_key(params))
+    }
+
+    pub fn validate_org_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidateOrgApiKeyResponse, ApiKeyError> {
+        self.runtime
+            .block_on(self.inner.validate_org_api_key(params))
+    }
+}
+
+/// Blocking counterpart of [`crate::propelauth::access_token::AccessTokenService`]
+pub struct AccessTokenService<'a> {
+    inner: crate::propelauth::access_token::AccessTokenService<'a>,
+    runtime: &'a BlockingRuntime,
+}
+
+impl AccessTokenService<'_> {
+    pub fn create_access_token(
+        &self,
+        params: CreateAccessTokenParams,
+    ) -> Result<CreateAccessTokenResponse, CreateAccessTokenError> {
+        self.runtime
+            .block_on(self.inner.create_access_token(params))
+    }
+
+    pub fn create_access_token_v2(
+        &self,
+        params: CreateAccessTokenV2Params,
+    ) -> Result<CreateAccessTokenResponse, CreateAccessTokenError> {
+        self.runtime
+            .block_on(self.inner.create_access_token_v2(params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::apis::interceptor::Interceptor;
+    use crate::apis::user_service_api::FetchUserByIdParams;
+    use crate::blocking::{BlockingRuntime, PropelAuth};
+    use crate::models::AuthTokenVerificationMetadata;
+    use crate::propelauth::errors::ErrorsWithNotFound;
+
+    struct CountingInterceptor(Arc<AtomicUsize>);
+
+    impl Interceptor for CountingInterceptor {
+        fn before_send(&self, request: &mut reqwest::Request) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            request
+                .headers_mut()
+                .insert("x-correlation-id", "abc".parse().unwrap());
+        }
+    }
+
+    fn serve_once(status_line: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).unwrap();
+            let response = format!(
+                "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status_line,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    fn auth_with_base_path(base_path: String) -> PropelAuth {
+        let mut inner = crate::propelauth::auth::PropelAuth::init(
+            crate::propelauth::options::AuthOptionsWithTokenVerification {
+                auth_url: "https://auth.example.com".to_string(),
+                api_key: "api_key".to_string(),
+                manual_token_verification_metadata: AuthTokenVerificationMetadata {
+                    public_key_pem: String::new(),
+                },
+            },
+        )
+        .unwrap();
+        inner.config.base_path = base_path;
+        PropelAuth {
+            inner,
+            runtime: Arc::new(BlockingRuntime::new().unwrap()),
+        }
+    }
+
+    #[test]
+    fn blocking_calls_go_through_the_pipeline() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let auth = auth_with_base_path(serve_once("404 Not Found", "{}"))
+            .with_interceptor(CountingInterceptor(count.clone()));
+
+        let result = auth.user().fetch_user_by_id(FetchUserByIdParams {
+            user_id: "bf7b3bc0-739d-45a2-ba60-60655249a5b0".to_string(),
+            include_orgs: None,
+        });
+
+        assert_eq!(result.err(), Some(ErrorsWithNotFound::NotFound));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't be used from within an async runtime")]
+    fn panics_inside_an_async_runtime() {
+        let auth = auth_with_base_path("http://127.0.0.1:1".to_string());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let _ = auth.user().fetch_user_by_id(FetchUserByIdParams {
+                user_id: "bf7b3bc0-739d-45a2-ba60-60655249a5b0".to_string(),
+                include_orgs: None,
+            });
+        });
+    }
+
+    #[test]
+    fn can_be_dropped_inside_an_async_runtime() {
+        let auth = auth_with_base_path("http://127.0.0.1:1".to_string());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async move { drop(auth) });
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mirrors_the_async_services() {
+        use crate::apis::access_token_service_api::CreateAccessTokenV2Params;
+        use crate::apis::api_key_service_api::{CreateApiKeyParams, ValidateApiKeyParams};
+        use crate::models::{
+            AddUserToOrgRequest, CreateAccessTokenV2Request, CreateOrgRequest, CreateUserRequest,
+        };
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = PropelAuth {
+            inner: fake.auth(),
+            runtime: Arc::new(BlockingRuntime::new().unwrap()),
+        };
+
+        let user_id = auth
+            .user()
+            .create_user(CreateUserRequest {
+                email: "user@example.com".to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+            .user_id;
+        let org_id = auth
+            .org()
+            .create_org(CreateOrgRequest {
+                name: "Acme".to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+            .org_id;
+        auth.org()
+            .add_user_to_org(AddUserToOrgRequest {
+                user_id: user_id.clone(),
+                org_id: org_id.clone(),
+                role: "Owner".to_string(),
+                additional_roles: vec![],
+            })
+            .unwrap();
+
+        let user = auth
+            .user()
+            .fetch_user_by_id(FetchUserByIdParams {
+                user_id: user_id.clone(),
+                include_orgs: Some(true),
+            })
+            .unwrap();
+        assert_eq!("user@example.com", user.email);
+        assert!(user.org_id_to_org_info.unwrap().contains_key(&org_id));
+
+        let api_key = auth
+            .api_key()
+            .create_api_key(CreateApiKeyParams {
+                user_id: Some(user_id.clone()),
+                ..Default::default()
+            })
+            .unwrap();
+        let validated = auth
+            .api_key()
+            .validate_api_key(ValidateApiKeyParams {
+                api_key_token: api_key.api_key_token,
+            })
+            .unwrap();
+        assert_eq!(Some(user_id.clone()), validated.user_id);
+
+        let access_token = auth
+            .access_token()
+            .create_access_token_v2(CreateAccessTokenV2Params {
+                create_access_token_request: CreateAccessTokenV2Request {
+                    user_id: user_id.clone(),
+                    duration_in_minutes: 10,
+                    active_org_id: Some(org_id),
+                },
+            })
+            .unwrap()
+            .access_token;
+        let verified = auth
+            .verify()
+            .validate_authorization_header(&format!("Bearer {}", access_token))
+            .unwrap();
+        assert_eq!(user_id, verified.user_id);
+
+        auth.user()
+            .delete_user_by_id(user_id.parse().unwrap())
+            .unwrap();
+        assert_eq!(
+            Err(ErrorsWithNotFound::NotFound),
+            auth.user().fetch_user_by_id(FetchUserByIdParams {
+                user_id,
+                include_orgs: None,
+            })
+        );
+    }
+}
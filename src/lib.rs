@@ -163,11 +163,13 @@
 #[macro_use]
 extern crate serde_derive;
 
+#[cfg(feature = "__reqwest")]
 extern crate reqwest;
 extern crate serde;
 extern crate serde_json;
 extern crate url;
 
+#[cfg(feature = "__reqwest")]
 pub mod apis;
 pub mod models;
 pub mod propelauth;
@@ -183,3 +185,9 @@ pub mod axum08;
 
 #[cfg(feature = "actix4")]
 pub mod actix;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+#[cfg(feature = "testing")]
+pub mod testing;
@@ -12,6 +12,8 @@
 
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct AuthTokenVerificationMetadata {
     #[serde(rename = "public_key_pem")]
     pub public_key_pem: String,
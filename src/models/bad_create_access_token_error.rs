@@ -1,5 +1,7 @@
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct BadCreateAccessTokenError {
     #[serde(rename = "active_org_id", skip_serializing_if = "Option::is_none")]
     pub active_org_id: Option<Vec<String>>,
@@ -12,6 +12,8 @@
 
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct BadFetchUsersByEmailsQuery {
     #[serde(rename = "query", skip_serializing_if = "Option::is_none")]
     pub query: Option<Vec<String>>,
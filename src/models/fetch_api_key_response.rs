@@ -1,7 +1,11 @@
+use chrono::{DateTime, Utc};
+
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct FetchApiKeyResponse {
     pub api_key_id: String,
-    pub created_at: i32,
+    pub created_at: i64,
     pub expires_at_seconds: Option<i64>,
     pub metadata: Option<serde_json::Value>,
     pub user_id: Option<String>,
@@ -9,7 +13,62 @@ pub struct FetchApiKeyResponse {
 }
 
 impl FetchApiKeyResponse {
-    pub fn new(api_key_id: String, created_at: i32, expires_at_seconds: Option<i64>, metadata: Option<serde_json::Value>, user_id: Option<String>, org_id: Option<String>) -> Self {
+    pub fn new(api_key_id: String, created_at: i64, expires_at_seconds: Option<i64>, metadata: Option<serde_json::Value>, user_id: Option<String>, org_id: Option<String>) -> Self {
         Self { api_key_id, created_at, expires_at_seconds, metadata, user_id, org_id }
     }
+
+    /// `None` if the timestamp is out of the range `chrono` can represent
+    pub fn created_at_utc(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.created_at, 0)
+    }
+
+    /// `None` if the key never expires, or if its expiration is out of range
+    pub fn expires_at_utc(&self) -> Option<DateTime<Utc>> {
+        self.expires_at_seconds
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::FetchApiKeyResponse;
+
+    #[test]
+    fn reads_timestamps_past_2038() {
+        let api_key: FetchApiKeyResponse = serde_json::from_value(json!({
+            "api_key_id": "e4b4a1e42c7b4a629a833f3bdb0f1bd6",
+            "created_at": 4_102_444_800_i64,
+            "expires_at_seconds": 4_133_980_800_i64,
+        }))
+        .unwrap();
+        assert_eq!(4_102_444_800, api_key.created_at);
+        assert_eq!(
+            Some("2100-01-01T00:00:00+00:00".to_string()),
+            api_key
+                .created_at_utc()
+                .map(|created_at| created_at.to_rfc3339())
+        );
+        assert_eq!(
+            Some("2101-01-01T00:00:00+00:00".to_string()),
+            api_key
+                .expires_at_utc()
+                .map(|expires_at| expires_at.to_rfc3339())
+        );
+    }
+
+    #[test]
+    fn out_of_range_timestamps_are_none() {
+        let api_key = FetchApiKeyResponse {
+            created_at: i64::MAX,
+            expires_at_seconds: Some(i64::MIN),
+            ..Default::default()
+        };
+        assert_eq!(None, api_key.created_at_utc());
+        assert_eq!(None, api_key.expires_at_utc());
+
+        let api_key = FetchApiKeyResponse::default();
+        assert_eq!(None, api_key.expires_at_utc());
+    }
 }
@@ -1,4 +1,6 @@
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct FetchApiKeysPagedResponse {
     #[serde(rename = "api_keys")]
     pub api_keys: Vec<crate::models::FetchApiKeyResponse>,
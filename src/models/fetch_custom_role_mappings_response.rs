@@ -1,10 +1,14 @@
-#[derive(Deserialize, Debug)]
+#[derive(Clone, Deserialize, Debug)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct FetchCustomRoleMappingsResponse {
     #[serde(rename = "custom_role_mappings", default)]
     pub custom_role_mappings: Vec<CustomRoleMappingResponse>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Clone, Deserialize, Debug)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct CustomRoleMappingResponse {
     #[serde(rename = "custom_role_mapping_name")]
     pub custom_role_mapping_name: String,
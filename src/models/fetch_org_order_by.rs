@@ -9,8 +9,9 @@
  */
 
 
-/// 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum FetchOrgOrderBy {
     #[serde(rename = "CREATED_AT_ASC")]
     CreatedAtAsc,
@@ -21,6 +22,7 @@ pub enum FetchOrgOrderBy {
 
 }
 
+#[allow(clippy::to_string_trait_impl)]
 impl ToString for FetchOrgOrderBy {
     fn to_string(&self) -> String {
         match self {
@@ -31,6 +33,7 @@ impl ToString for FetchOrgOrderBy {
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for FetchOrgOrderBy {
     fn default() -> FetchOrgOrderBy {
         Self::CreatedAtAsc
@@ -13,6 +13,8 @@ use std::collections::HashMap;
 use serde_json::Value;
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct OrgMetadata {
     #[serde(flatten)]
     pub metadata: HashMap<String, Value>,
@@ -25,6 +27,8 @@ impl OrgMetadata {
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct FetchOrgResponse {
     pub org_id: String,
     pub name: String,
@@ -48,6 +52,7 @@ pub struct FetchOrgResponse {
 }
 
 impl FetchOrgResponse {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         org_id: String,
         name: String,
@@ -80,6 +85,8 @@ impl FetchOrgResponse {
 
 // A Simple org response is used for fetching multiple orgs until that API returns a full org object
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct FetchOrgBasicResponse {
     pub org_id: String,
     pub name: String,
@@ -95,6 +102,7 @@ pub struct FetchOrgBasicResponse {
 }
 
 impl crate::models::FetchOrgBasicResponse {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         org_id: String,
         name: String,
@@ -12,6 +12,8 @@
 
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct FetchOrgsResponse {
     #[serde(rename = "orgs")]
     pub orgs: Vec<crate::models::FetchOrgBasicResponse>,
@@ -1,4 +1,8 @@
-#[derive(Deserialize, Debug)]
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Deserialize, Debug)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct FetchPendingInvitesResponse {
     #[serde(rename = "total_invites")]
     pub total_invites: i64,
@@ -12,7 +16,9 @@ pub struct FetchPendingInvitesResponse {
     pub invites: Vec<PendingInviteResponse>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Clone, Deserialize, Debug)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct PendingInviteResponse {
     #[serde(rename = "invitee_email")]
     pub invitee_email: String,
@@ -33,3 +39,15 @@ pub struct PendingInviteResponse {
     #[serde(rename = "inviter_user_id", default)]
     pub inviter_user_id: Option<String>,
 }
+
+impl PendingInviteResponse {
+    /// `None` if the timestamp is out of the range `chrono` can represent
+    pub fn created_at_utc(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.created_at, 0)
+    }
+
+    /// `None` if the timestamp is out of the range `chrono` can represent
+    pub fn expires_at_utc(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.expires_at, 0)
+    }
+}
@@ -1,4 +1,6 @@
 #[derive(Clone, Debug, PartialEq, Default, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct FetchSamlSpMetadataResponse {
     #[serde(rename = "entity_id")]
     pub entity_id: String,
@@ -9,8 +9,9 @@
  */
 
 
-/// 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum FetchUsersOrderBy {
     #[serde(rename = "CREATED_AT_ASC")]
     CreatedAtAsc,
@@ -27,6 +28,7 @@ pub enum FetchUsersOrderBy {
 
 }
 
+#[allow(clippy::to_string_trait_impl)]
 impl ToString for FetchUsersOrderBy {
     fn to_string(&self) -> String {
         match self {
@@ -40,6 +42,7 @@ impl ToString for FetchUsersOrderBy {
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for FetchUsersOrderBy {
     fn default() -> FetchUsersOrderBy {
         Self::CreatedAtAsc
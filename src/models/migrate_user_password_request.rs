@@ -9,6 +9,8 @@
  */
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct MigrateUserPasswordRequest {
     #[serde(rename = "user_id")]
     pub user_id: String,
@@ -12,6 +12,8 @@ use serde_json::Value;
 use std::collections::HashMap;
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct MigrateUserRequest {
     #[serde(rename = "email")]
     pub email: String,
@@ -9,6 +9,8 @@
  */
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct RevokePendingOrgInviteRequest {
     #[serde(rename = "org_id")]
     pub org_id: String,
@@ -1,4 +1,6 @@
 #[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct SetSamlIdpMetadataRequest {
     #[serde(rename = "org_id")]
     pub org_id: String,
@@ -13,6 +15,8 @@ pub struct SetSamlIdpMetadataRequest {
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum SamlIdpProvider {
     Google,
     Rippling,
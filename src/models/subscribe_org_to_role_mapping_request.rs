@@ -1,4 +1,6 @@
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct SubscribeOrgToRoleMappingRequest {
     #[serde(rename = "custom_role_mapping_name")]
     pub custom_role_mapping_name: String,
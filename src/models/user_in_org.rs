@@ -14,6 +14,8 @@ use std::collections::HashMap;
 use crate::propelauth::token_models::OrgRoleStructure;
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct UserInOrg {
     #[serde(rename = "org_id")]
     pub org_id: String,
@@ -10,9 +10,12 @@
 
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
 use serde_json::Value;
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct UserMetadata {
     #[serde(rename = "user_id")]
     pub user_id: String,
@@ -59,6 +62,7 @@ pub struct UserMetadata {
 }
 
 impl UserMetadata {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         user_id: String,
         email: String,
@@ -92,4 +96,14 @@ impl UserMetadata {
             additional_roles_in_org: None,
         }
     }
+
+    /// `None` if the timestamp is out of the range `chrono` can represent
+    pub fn created_at_utc(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.created_at, 0)
+    }
+
+    /// `None` if the timestamp is out of the range `chrono` can represent
+    pub fn last_active_at_utc(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.last_active_at, 0)
+    }
 }
@@ -6,6 +6,8 @@ use crate::models::{UserInOrg, UserMetadata};
 pub type OrgRole = String;
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ValidateApiKeyResponse {
     pub metadata: Option<serde_json::Value>,
     pub user: Option<UserMetadata>,
@@ -16,12 +18,16 @@ pub struct ValidateApiKeyResponse {
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ValidatePersonalApiKeyResponse {
     pub metadata: Option<serde_json::Value>,
     pub user: UserMetadata,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ValidateOrgApiKeyResponse {
     pub metadata: Option<serde_json::Value>,
     pub user: Option<UserMetadata>,
@@ -30,11 +36,17 @@ pub struct ValidateOrgApiKeyResponse {
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+// Renamed so it doesn't collide with the `OrgMetadata` in `FetchOrgResponse`
+#[cfg_attr(feature = "schemars", schemars(rename = "ApiKeyOrgMetadata"))]
+#[cfg_attr(feature = "utoipa", schema(as = ApiKeyOrgMetadata))]
 pub struct OrgMetadata {
     pub org_id: Uuid,
     pub org_name: String,
     pub can_setup_saml: bool,
     pub max_users: Option<i32>,
+    #[cfg_attr(feature = "utoipa", schema(value_type = Object))]
     pub metadata: MetadataHashOfValue,
 }
 
@@ -18,7 +18,7 @@ impl AccessTokenService<'_> {
             return Err(CreateAccessTokenError::NotFound);
         }
 
-        crate::apis::access_token_service_api::create_access_token(&self.config, params)
+        crate::apis::access_token_service_api::create_access_token(self.config, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -48,7 +48,7 @@ impl AccessTokenService<'_> {
             return Err(CreateAccessTokenError::NotFound);
         }
 
-        crate::apis::access_token_service_api::create_access_token_v2(&self.config, params)
+        crate::apis::access_token_service_api::create_access_token_v2(self.config, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -9,90 +9,182 @@ use crate::models::validate_api_key_response::{
 use crate::models::{
     CreateApiKeyResponse, FetchApiKeyResponse, FetchApiKeysPagedResponse, ValidateApiKeyResponse,
 };
+use crate::propelauth::coalesce::{coalesced, coalescing_key};
 use crate::propelauth::helpers::map_autogenerated_error;
+use crate::propelauth::ids::ApiKeyId;
+use crate::propelauth::pagination::{paginate, Page, PaginationOptions};
+use futures_util::stream::Stream;
 
 pub struct ApiKeyService<'a> {
     pub(crate) config: &'a Configuration,
 }
 
-impl ApiKeyService<'_> {
+impl<'a> ApiKeyService<'a> {
     pub async fn fetch_current_api_keys(
         &self,
         params: ApiKeyQueryParams,
     ) -> Result<FetchApiKeysPagedResponse, ApiKeyError> {
-        crate::apis::api_key_service_api::fetch_current_api_keys(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ApiKeyError::UnexpectedExceptionWithSDK,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (
-                            _,
-                            Some(crate::apis::api_key_service_api::ApiKeyError::BadRequest(
-                                bad_request,
-                            )),
-                        ) => ApiKeyError::BadRequest(bad_request),
-                        (401, _) => ApiKeyError::InvalidIntegrationAPIKey,
-                        (429, _) => ApiKeyError::PropelAuthRateLimit,
-                        (404, _) => ApiKeyError::NotFound,
-                        _ => ApiKeyError::UnexpectedExceptionWithSDK,
-                    },
-                )
-            })
+        let key = coalescing_key(self.config, "fetch_current_api_keys", &params);
+        coalesced(self.config, key, async move {
+            crate::apis::api_key_service_api::fetch_current_api_keys(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ApiKeyError::UnexpectedExceptionWithSDK,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (
+                                _,
+                                Some(crate::apis::api_key_service_api::ApiKeyError::BadRequest(
+                                    bad_request,
+                                )),
+                            ) => ApiKeyError::BadRequest(bad_request),
+                            (401, _) => ApiKeyError::InvalidIntegrationAPIKey,
+                            (429, _) => ApiKeyError::PropelAuthRateLimit,
+                            (404, _) => ApiKeyError::NotFound,
+                            _ => ApiKeyError::UnexpectedExceptionWithSDK,
+                        },
+                    )
+                })
+        })
+        .await
+    }
+
+    /// Lazily walks every page of [`fetch_current_api_keys`](Self::fetch_current_api_keys), one API key at a time
+    pub fn fetch_current_api_keys_stream(
+        &self,
+        params: ApiKeyQueryParams,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<FetchApiKeyResponse, ApiKeyError>> + 'a {
+        let config = self.config;
+        paginate(
+            params.page_number.unwrap_or(0),
+            options.page_size.or(params.page_size),
+            options.prefetch,
+            move |page_number, page_size| {
+                let params = ApiKeyQueryParams {
+                    page_number: Some(page_number),
+                    page_size,
+                    ..params.clone()
+                };
+                async move {
+                    let response = ApiKeyService { config }
+                        .fetch_current_api_keys(params)
+                        .await?;
+                    Ok(Page {
+                        items: response.api_keys,
+                        has_more_results: response.has_more_results,
+                    })
+                }
+            },
+        )
     }
 
     pub async fn fetch_archived_api_keys(
         &self,
         params: ApiKeyQueryParams,
     ) -> Result<FetchApiKeysPagedResponse, ApiKeyError> {
-        crate::apis::api_key_service_api::fetch_archived_api_keys(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ApiKeyError::UnexpectedExceptionWithSDK,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (
-                            _,
-                            Some(crate::apis::api_key_service_api::ApiKeyError::BadRequest(
-                                bad_request,
-                            )),
-                        ) => ApiKeyError::BadRequest(bad_request),
-                        (401, _) => ApiKeyError::InvalidIntegrationAPIKey,
-                        (429, _) => ApiKeyError::PropelAuthRateLimit,
-                        (404, _) => ApiKeyError::NotFound,
-                        _ => ApiKeyError::UnknownError,
-                    },
-                )
-            })
+        let key = coalescing_key(self.config, "fetch_archived_api_keys", &params);
+        coalesced(self.config, key, async move {
+            crate::apis::api_key_service_api::fetch_archived_api_keys(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ApiKeyError::UnexpectedExceptionWithSDK,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (
+                                _,
+                                Some(crate::apis::api_key_service_api::ApiKeyError::BadRequest(
+                                    bad_request,
+                                )),
+                            ) => ApiKeyError::BadRequest(bad_request),
+                            (401, _) => ApiKeyError::InvalidIntegrationAPIKey,
+                            (429, _) => ApiKeyError::PropelAuthRateLimit,
+                            (404, _) => ApiKeyError::NotFound,
+                            _ => ApiKeyError::UnknownError,
+                        },
+                    )
+                })
+        })
+        .await
     }
 
+    /// Lazily walks every page of [`fetch_archived_api_keys`](Self::fetch_archived_api_keys), one API key at a time
+    pub fn fetch_archived_api_keys_stream(
+        &self,
+        params: ApiKeyQueryParams,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<FetchApiKeyResponse, ApiKeyError>> + 'a {
+        let config = self.config;
+        paginate(
+            params.page_number.unwrap_or(0),
+            options.page_size.or(params.page_size),
+            options.prefetch,
+            move |page_number, page_size| {
+                let params = ApiKeyQueryParams {
+                    page_number: Some(page_number),
+                    page_size,
+                    ..params.clone()
+                };
+                async move {
+                    let response = ApiKeyService { config }
+                        .fetch_archived_api_keys(params)
+                        .await?;
+                    Ok(Page {
+                        items: response.api_keys,
+                        has_more_results: response.has_more_results,
+                    })
+                }
+            },
+        )
+    }
+
+    #[deprecated(note = "use `fetch_api_key_by_id`, which takes an `ApiKeyId`")]
     pub async fn fetch_api_key(
         &self,
         api_key_id: String,
     ) -> Result<FetchApiKeyResponse, ApiKeyError> {
-        crate::apis::api_key_service_api::fetch_api_key(&self.config, api_key_id)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ApiKeyError::UnexpectedExceptionWithSDK,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ApiKeyError::InvalidIntegrationAPIKey,
-                        429 => ApiKeyError::PropelAuthRateLimit,
-                        404 => ApiKeyError::NotFound,
-                        _ => ApiKeyError::UnknownError,
-                    },
-                )
-            })
+        self.fetch_api_key_by_raw_id(api_key_id).await
+    }
+
+    pub async fn fetch_api_key_by_id(
+        &self,
+        api_key_id: ApiKeyId,
+    ) -> Result<FetchApiKeyResponse, ApiKeyError> {
+        self.fetch_api_key_by_raw_id(api_key_id.to_string()).await
+    }
+
+    /// Sends `api_key_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn fetch_api_key_by_raw_id(
+        &self,
+        api_key_id: String,
+    ) -> Result<FetchApiKeyResponse, ApiKeyError> {
+        let key = coalescing_key(self.config, "fetch_api_key", &api_key_id);
+        coalesced(self.config, key, async move {
+            crate::apis::api_key_service_api::fetch_api_key(self.config, api_key_id)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ApiKeyError::UnexpectedExceptionWithSDK,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ApiKeyError::InvalidIntegrationAPIKey,
+                            429 => ApiKeyError::PropelAuthRateLimit,
+                            404 => ApiKeyError::NotFound,
+                            _ => ApiKeyError::UnknownError,
+                        },
+                    )
+                })
+        })
+        .await
     }
 
     pub async fn create_api_key(
         &self,
         params: CreateApiKeyParams,
     ) -> Result<CreateApiKeyResponse, ApiKeyError> {
-        crate::apis::api_key_service_api::create_api_key(&self.config, params)
+        crate::apis::api_key_service_api::create_api_key(self.config, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -108,12 +200,31 @@ impl ApiKeyService<'_> {
             })
     }
 
+    #[deprecated(note = "use `update_api_key_by_id`, which takes an `ApiKeyId`")]
     pub async fn update_api_key(
         &self,
         api_key_id: String,
         params: UpdateApiKeyParams,
     ) -> Result<(), ApiKeyError> {
-        crate::apis::api_key_service_api::update_api_key(&self.config, api_key_id, params)
+        self.update_api_key_by_raw_id(api_key_id, params).await
+    }
+
+    pub async fn update_api_key_by_id(
+        &self,
+        api_key_id: ApiKeyId,
+        params: UpdateApiKeyParams,
+    ) -> Result<(), ApiKeyError> {
+        self.update_api_key_by_raw_id(api_key_id.to_string(), params)
+            .await
+    }
+
+    /// Sends `api_key_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn update_api_key_by_raw_id(
+        &self,
+        api_key_id: String,
+        params: UpdateApiKeyParams,
+    ) -> Result<(), ApiKeyError> {
+        crate::apis::api_key_service_api::update_api_key(self.config, api_key_id, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -131,8 +242,21 @@ impl ApiKeyService<'_> {
         Ok(())
     }
 
+    #[deprecated(note = "use `delete_api_key_by_id`, which takes an `ApiKeyId`")]
     pub async fn delete_api_key(&self, api_key_id: String) -> Result<(), ApiKeyError> {
-        crate::apis::api_key_service_api::delete_api_key(&self.config, api_key_id)
+        self.delete_api_key_by_raw_id(api_key_id).await
+    }
+
+    pub async fn delete_api_key_by_id(&self, api_key_id: ApiKeyId) -> Result<(), ApiKeyError> {
+        self.delete_api_key_by_raw_id(api_key_id.to_string()).await
+    }
+
+    /// Sends `api_key_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn delete_api_key_by_raw_id(
+        &self,
+        api_key_id: String,
+    ) -> Result<(), ApiKeyError> {
+        crate::apis::api_key_service_api::delete_api_key(self.config, api_key_id)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -154,41 +278,45 @@ impl ApiKeyService<'_> {
         &self,
         params: ValidateApiKeyParams,
     ) -> Result<ValidateApiKeyResponse, ApiKeyError> {
-        if hex::decode(&params.api_key_token).is_err() {
-            return Err(ApiKeyError::InvalidAPIKey {
-                message: "Invalid API key format.".to_string(),
-            });
-        }
+        let key = coalescing_key(self.config, "validate_api_key", &params);
+        coalesced(self.config, key, async move {
+            if hex::decode(&params.api_key_token).is_err() {
+                return Err(ApiKeyError::InvalidAPIKey {
+                    message: "Invalid API key format.".to_string(),
+                });
+            }
 
-        crate::apis::api_key_service_api::validate_api_key(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ApiKeyError::UnexpectedExceptionWithSDK,
-                    |status_code, error_response_body| match error_response_body {
-                        Some(ApiKeyValidationErrorResponse::InvalidEndUserApiKey {
-                            api_key_token,
-                        }) => ApiKeyError::InvalidAPIKey {
-                            message: api_key_token,
-                        },
-                        Some(ApiKeyValidationErrorResponse::EndUserApiKeyRateLimited {
-                            wait_seconds,
-                            user_facing_error,
-                            ..
-                        }) => ApiKeyError::RateLimited {
-                            wait_seconds,
-                            user_facing_error,
+            crate::apis::api_key_service_api::validate_api_key(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ApiKeyError::UnexpectedExceptionWithSDK,
+                        |status_code, error_response_body| match error_response_body {
+                            Some(ApiKeyValidationErrorResponse::InvalidEndUserApiKey {
+                                api_key_token,
+                            }) => ApiKeyError::InvalidAPIKey {
+                                message: api_key_token,
+                            },
+                            Some(ApiKeyValidationErrorResponse::EndUserApiKeyRateLimited {
+                                wait_seconds,
+                                user_facing_error,
+                                ..
+                            }) => ApiKeyError::RateLimited {
+                                wait_seconds,
+                                user_facing_error,
+                            },
+                            None => match status_code.as_u16() {
+                                401 => ApiKeyError::InvalidIntegrationAPIKey,
+                                429 => ApiKeyError::PropelAuthRateLimit,
+                                404 => ApiKeyError::NotFound,
+                                _ => ApiKeyError::UnknownError,
+                            },
                         },
-                        None => match status_code.as_u16() {
-                            401 => ApiKeyError::InvalidIntegrationAPIKey,
-                            429 => ApiKeyError::PropelAuthRateLimit,
-                            404 => ApiKeyError::NotFound,
-                            _ => ApiKeyError::UnknownError,
-                        },
-                    },
-                )
-            })
+                    )
+                })
+        })
+        .await
     }
 
     pub async fn validate_personal_api_key(
@@ -223,3 +351,55 @@ impl ApiKeyService<'_> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "testing")]
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_methods_send_the_id_as_given() {
+        use std::sync::{Arc, Mutex};
+
+        use reqwest::Request;
+
+        use crate::apis::api_key_service_api::UpdateApiKeyParams;
+        use crate::apis::interceptor::Interceptor;
+        use crate::testing::FakeBackend;
+
+        struct RecordPaths(Arc<Mutex<Vec<String>>>);
+        impl Interceptor for RecordPaths {
+            fn before_send(&self, request: &mut Request) {
+                let path = request.url().path().to_string();
+                self.0.lock().unwrap().push(path);
+            }
+        }
+
+        let fake = FakeBackend::start();
+        let paths = Arc::new(Mutex::new(vec![]));
+        let auth = fake.auth().with_interceptor(RecordPaths(paths.clone()));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let api_keys = auth.api_key();
+            let short_id = "abc123".to_string();
+            let upper_case_id = "E4B4A1E42C7B4A629A833F3BDB0F1BD6".to_string();
+            let _ = api_keys.fetch_api_key(short_id).await;
+            let _ = api_keys
+                .update_api_key(upper_case_id.clone(), UpdateApiKeyParams::default())
+                .await;
+            let _ = api_keys.delete_api_key(upper_case_id).await;
+        });
+
+        assert_eq!(
+            vec![
+                "/api/backend/v1/end_user_api_keys/abc123",
+                "/api/backend/v1/end_user_api_keys/E4B4A1E42C7B4A629A833F3BDB0F1BD6",
+                "/api/backend/v1/end_user_api_keys/E4B4A1E42C7B4A629A833F3BDB0F1BD6",
+            ],
+            *paths.lock().unwrap()
+        );
+    }
+}
@@ -1,32 +1,77 @@
+#[cfg(feature = "__reqwest")]
+use std::time::Duration;
+
 use url::Url;
 
+#[cfg(feature = "__reqwest")]
 use crate::apis::auth_service_api::token_verification_metadata;
+#[cfg(feature = "__reqwest")]
+use crate::apis::cassette::Cassette;
+#[cfg(feature = "__reqwest")]
 use crate::apis::configuration::Configuration;
+#[cfg(feature = "__reqwest")]
+use crate::apis::interceptor::Interceptor;
 use crate::models::AuthTokenVerificationMetadata;
+#[cfg(feature = "__reqwest")]
 use crate::propelauth::access_token::AccessTokenService;
+#[cfg(feature = "__reqwest")]
 use crate::propelauth::api_key::ApiKeyService;
+#[cfg(feature = "__reqwest")]
+use crate::propelauth::cache::{CacheBackend, CacheOptions, InMemoryCache};
+#[cfg(feature = "__reqwest")]
+use crate::propelauth::client::PropelAuthClient;
 use crate::propelauth::errors::InitializationError;
+#[cfg(feature = "__reqwest")]
 use crate::propelauth::helpers::map_autogenerated_error;
+use crate::propelauth::options::VerifyOnlyAuthOptions;
+#[cfg(feature = "__reqwest")]
 use crate::propelauth::options::{AuthOptions, AuthOptionsWithTokenVerification};
+#[cfg(feature = "__reqwest")]
 use crate::propelauth::org::OrgService;
 use crate::propelauth::token::TokenService;
+#[cfg(feature = "__reqwest")]
 use crate::propelauth::user::UserService;
 
+#[cfg(feature = "__reqwest")]
 static BACKEND_API_BASE_URL: &str = "https://propelauth-api.com";
+#[cfg(feature = "__reqwest")]
 pub(crate) static AUTH_HOSTNAME_HEADER: &str = "X-Propelauth-url";
 
 /// The main entrypoint of this library.
 /// All authentication, authorization and API requests starts from this struct
 #[derive(Debug, Clone)]
 pub struct PropelAuth {
-    config: Configuration,
+    #[cfg(feature = "__reqwest")]
+    pub(crate) config: Configuration,
     token_verification_metadata: AuthTokenVerificationMetadata,
     issuer: String,
 }
 
 impl PropelAuth {
+    /// Initializes the PropelAuth library for verifying access tokens only. No API key is
+    /// needed and no external requests are made, which makes this the only initializer
+    /// available when the crate is built without an HTTP client (`default-features = false`).
+    pub fn init_verify_only(
+        opts: VerifyOnlyAuthOptions,
+    ) -> Result<PropelAuth, InitializationError> {
+        let auth_hostname = validate_auth_url_extract_hostname(&opts.auth_url)?;
+        let issuer = "https://".to_string() + &auth_hostname;
+
+        Ok(PropelAuth {
+            #[cfg(feature = "__reqwest")]
+            config: Configuration {
+                base_path: BACKEND_API_BASE_URL.to_string(),
+                auth_hostname,
+                ..Default::default()
+            },
+            token_verification_metadata: opts.token_verification_metadata,
+            issuer,
+        })
+    }
+
     /// Initializes the PropelAuth library without making any external requests. This contrasts
     /// with `fetch_and_init` which will fetch the metadata needed to validate access tokens
+    #[cfg(feature = "__reqwest")]
     pub fn init(opts: AuthOptionsWithTokenVerification) -> Result<PropelAuth, InitializationError> {
         let auth_hostname = validate_auth_url_extract_hostname(&opts.auth_url)?;
         let issuer = "https://".to_string() + &auth_hostname;
@@ -47,6 +92,7 @@ impl PropelAuth {
 
     /// Initializes the PropelAuth library by making a single external request. This contrasts
     /// with `init` where you manually specify the metadata needed to validate access tokens
+    #[cfg(feature = "__reqwest")]
     pub async fn fetch_and_init(opts: AuthOptions) -> Result<PropelAuth, InitializationError> {
         let auth_hostname = validate_auth_url_extract_hostname(&opts.auth_url)?;
         let issuer = "https://".to_string() + &auth_hostname;
@@ -79,29 +125,90 @@ impl PropelAuth {
         })
     }
 
+    /// Registers an interceptor which will run on every backend API request
+    /// made through this instance, e.g. to add correlation IDs or audit calls
+    #[cfg(feature = "__reqwest")]
+    pub fn with_interceptor<I: Interceptor + 'static>(mut self, interceptor: I) -> PropelAuth {
+        self.config.add_interceptor(interceptor);
+        self
+    }
+
+    /// Records all backend API requests made through this instance to a cassette, or replays
+    /// them from one, so tests can run without reaching PropelAuth
+    #[cfg(feature = "__reqwest")]
+    pub fn with_cassette(mut self, cassette: Cassette) -> PropelAuth {
+        self.config.set_cassette(cassette);
+        self
+    }
+
+    /// Makes concurrent identical read requests (e.g. `fetch_user_by_id` or `validate_api_key`
+    /// with the same parameters) share a single in-flight backend call and its result. Clones
+    /// of this instance, and clients created from it, share the same in-flight calls.
+    #[cfg(feature = "__reqwest")]
+    pub fn with_request_coalescing(mut self) -> PropelAuth {
+        self.config.enable_request_coalescing();
+        self
+    }
+
+    /// Validates password hashes locally with
+    /// [`validate_password_hash`](crate::propelauth::password_hash::validate_password_hash)
+    /// before `migrate_user` and `migrate_user_password` send them, failing with a `BadRequest`
+    /// for the password hash field if it's unrecognized or malformed
+    #[cfg(feature = "__reqwest")]
+    pub fn with_strict_password_hashes(mut self) -> PropelAuth {
+        self.config.strict_password_hashes = true;
+        self
+    }
+
+    /// Caches `fetch_user_by_id` and `fetch_org` results in memory. Entries are dropped once
+    /// they expire, and as soon as this instance (or a clone or client created from it) makes
+    /// a request that changes the user or org.
+    ///
+    /// Changes made elsewhere (e.g. in the dashboard or by another server), and changes that
+    /// only affect a user's orgs indirectly (like renaming or deleting an org they belong to),
+    /// are only picked up once the entry expires.
+    #[cfg(feature = "__reqwest")]
+    pub fn with_cache(self, options: CacheOptions) -> PropelAuth {
+        self.with_cache_backend(InMemoryCache::new(options.max_entries), options.ttl)
+    }
+
+    /// Like [`with_cache`](Self::with_cache), but stores entries in your own backend
+    #[cfg(feature = "__reqwest")]
+    pub fn with_cache_backend<B: CacheBackend + 'static>(
+        mut self,
+        backend: B,
+        ttl: Duration,
+    ) -> PropelAuth {
+        self.config.set_cache(backend, ttl);
+        self
+    }
+
     /// API requests related to users
-    pub fn user(&self) -> UserService {
+    #[cfg(feature = "__reqwest")]
+    pub fn user(&self) -> UserService<'_> {
         UserService {
             config: &self.config,
         }
     }
 
     /// API requests related to organizations
-    pub fn org(&self) -> OrgService {
+    #[cfg(feature = "__reqwest")]
+    pub fn org(&self) -> OrgService<'_> {
         OrgService {
             config: &self.config,
         }
     }
 
     /// API requests related to organizations
-    pub fn api_key(&self) -> ApiKeyService {
+    #[cfg(feature = "__reqwest")]
+    pub fn api_key(&self) -> ApiKeyService<'_> {
         ApiKeyService {
             config: &self.config,
         }
     }
 
     /// Verify access tokens from your frontend
-    pub fn verify(&self) -> TokenService {
+    pub fn verify(&self) -> TokenService<'_> {
         TokenService {
             token_verification_metadata: &self.token_verification_metadata,
             issuer: &self.issuer,
@@ -109,11 +216,21 @@ impl PropelAuth {
     }
 
     /// API requests related to access tokens.
-    pub fn access_token(&self) -> AccessTokenService {
+    #[cfg(feature = "__reqwest")]
+    pub fn access_token(&self) -> AccessTokenService<'_> {
         AccessTokenService {
             config: &self.config,
         }
     }
+
+    /// An owned handle to all backend API requests which implements the traits in
+    /// [`traits`](crate::propelauth::traits), for storing in app state or mocking in tests
+    #[cfg(feature = "__reqwest")]
+    pub fn client(&self) -> PropelAuthClient {
+        PropelAuthClient {
+            config: self.config.clone(),
+        }
+    }
 }
 
 fn validate_auth_url_extract_hostname(auth_url: &str) -> Result<String, InitializationError> {
@@ -126,8 +243,10 @@ fn validate_auth_url_extract_hostname(auth_url: &str) -> Result<String, Initiali
 
 #[cfg(test)]
 mod tests {
-    use crate::propelauth::auth::validate_auth_url_extract_hostname;
+    use crate::models::AuthTokenVerificationMetadata;
+    use crate::propelauth::auth::{validate_auth_url_extract_hostname, PropelAuth};
     use crate::propelauth::errors::InitializationError;
+    use crate::propelauth::options::VerifyOnlyAuthOptions;
 
     #[test]
     fn bad_auth_url_is_rejected() {
@@ -155,4 +274,23 @@ mod tests {
             validate_auth_url_extract_hostname("https://app.blah.co.uk/more").ok()
         );
     }
+
+    #[test]
+    fn init_verify_only_uses_auth_url_as_issuer() {
+        let auth = PropelAuth::init_verify_only(VerifyOnlyAuthOptions {
+            auth_url: "https://auth.example.com".to_string(),
+            token_verification_metadata: AuthTokenVerificationMetadata::new("pem".to_string()),
+        })
+        .expect("Unable to initialize");
+        assert_eq!("https://auth.example.com", auth.issuer);
+
+        assert_eq!(
+            Some(InitializationError::InvalidAuthUrl),
+            PropelAuth::init_verify_only(VerifyOnlyAuthOptions {
+                auth_url: "fake".to_string(),
+                token_verification_metadata: AuthTokenVerificationMetadata::default(),
+            })
+            .err()
+        );
+    }
 }
@@ -0,0 +1,165 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+
+use crate::models::UserMetadata;
+use crate::propelauth::errors::BatchFetchError;
+
+/// The largest batch the backend accepts in a single request
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// Controls how the `*_chunked` lookups split and run their requests
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// How many inputs are sent per request, capped at [`MAX_BATCH_SIZE`]
+    pub chunk_size: usize,
+    /// How many requests may be in flight at once
+    pub concurrency: usize,
+    /// Defaults to false
+    pub include_orgs: Option<bool>,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            chunk_size: MAX_BATCH_SIZE,
+            concurrency: 4,
+            include_orgs: None,
+        }
+    }
+}
+
+/// The merged result of a chunked batch lookup
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchFetchResult {
+    /// Found users, keyed the same way as the non-chunked lookup
+    pub users: HashMap<String, UserMetadata>,
+    /// Inputs without a matching user, in the order they were first given
+    pub not_found: Vec<String>,
+}
+
+/// De-duplicates `inputs` (comparing them with `normalize`), fetches them in chunks of at
+/// most `options.chunk_size` with up to `options.concurrency` requests in flight, and merges
+/// the results. Fails with the first error returned by any chunk.
+pub(crate) async fn fetch_in_chunks<I, N, F, Fut>(
+    inputs: I,
+    options: &BatchOptions,
+    normalize: N,
+    fetch_chunk: F,
+) -> Result<BatchFetchResult, BatchFetchError>
+where
+    I: IntoIterator<Item = String>,
+    N: Fn(&str) -> String,
+    F: Fn(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<HashMap<String, UserMetadata>, BatchFetchError>>,
+{
+    let mut seen = HashSet::new();
+    let inputs: Vec<String> = inputs
+        .into_iter()
+        .filter(|input| seen.insert(normalize(input)))
+        .collect();
+
+    let chunk_size = options.chunk_size.clamp(1, MAX_BATCH_SIZE);
+    let users = stream::iter(inputs.chunks(chunk_size).map(|chunk| chunk.to_vec()))
+        .map(fetch_chunk)
+        .buffer_unordered(options.concurrency.max(1))
+        .try_fold(HashMap::new(), |mut users, chunk| async move {
+            users.extend(chunk);
+            Ok(users)
+        })
+        .await?;
+
+    let found: HashSet<String> = users.keys().map(|key| normalize(key)).collect();
+    let not_found = inputs
+        .into_iter()
+        .filter(|input| !found.contains(&normalize(input)))
+        .collect();
+    Ok(BatchFetchResult { users, not_found })
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::models::CreateUserRequest;
+    use crate::propelauth::batch::BatchOptions;
+    use crate::testing::FakeBackend;
+
+    #[test]
+    fn large_lookups_are_chunked_and_merged() {
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let mut user_ids = vec![];
+            for i in 0..250 {
+                let created = auth
+                    .user()
+                    .create_user(CreateUserRequest {
+                        email: format!("user{}@example.com", i),
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap();
+                user_ids.push(created.user_id);
+            }
+
+            let missing_id = "8a4c2b5e-0d0b-4f7e-9c52-3b8f5e0f6a11".to_string();
+            let mut lookup = user_ids.clone();
+            lookup.extend(user_ids.iter().take(10).cloned());
+            lookup.push(missing_id.clone());
+            lookup.push("not-a-uuid".to_string());
+
+            let result = auth
+                .user()
+                .fetch_users_by_ids_chunked(
+                    lookup,
+                    BatchOptions {
+                        chunk_size: 40,
+                        concurrency: 3,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(250, result.users.len());
+            assert_eq!(vec![missing_id, "not-a-uuid".to_string()], result.not_found);
+
+            // Upper-case IDs are the same users as the lower-case ones PropelAuth returns
+            let result = auth
+                .user()
+                .fetch_users_by_ids_chunked(
+                    vec![
+                        user_ids[0].to_ascii_uppercase(),
+                        user_ids[0].clone(),
+                        user_ids[1].to_ascii_uppercase(),
+                    ],
+                    BatchOptions::default(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(2, result.users.len());
+            assert!(result.users.contains_key(&user_ids[0]));
+            assert!(result.users.contains_key(&user_ids[1]));
+            assert!(result.not_found.is_empty());
+
+            let result = auth
+                .user()
+                .fetch_users_by_emails_chunked(
+                    vec![
+                        "USER1@example.com".to_string(),
+                        "user1@example.com".to_string(),
+                        "nobody@example.com".to_string(),
+                    ],
+                    BatchOptions::default(),
+                )
+                .await
+                .unwrap();
+            assert!(result.users.contains_key("user1@example.com"));
+            assert_eq!(vec!["nobody@example.com".to_string()], result.not_found);
+        });
+    }
+}
@@ -0,0 +1,901 @@
+//! Fluent builders for request models and params, e.g.
+//! `CreateUserRequest::builder(email).password(password).build()`. Builders for requests that
+//! PropelAuth can reject with a `Bad*` model check what they can locally in `build()` and return
+//! that model on error, so it can be handled the same way as the one the API returns.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::apis::api_key_service_api::{ApiKeyQueryParams, CreateApiKeyParams, UpdateApiKeyParams};
+use crate::apis::org_service_api::{
+    CreateSamlConnectionLinkParams, FetchOrgsByQueryParams, FetchPendingInvitesParams,
+    FetchUsersInOrgParams,
+};
+use crate::apis::user_service_api::{
+    FetchUserByEmailParams, FetchUserByIdParams, FetchUserByUsernameParams,
+    FetchUsersByEmailsParams, FetchUsersByIdsParams, FetchUsersByQueryParams,
+    FetchUsersByUsernamesParams,
+};
+use crate::models::{
+    AddUserToOrgRequest, BadCreateMagicLinkRequest, BadCreateOrgRequest, BadCreateUserRequest,
+    BadFetchOrgQuery, BadFetchUsersByQuery, BadFetchUsersInOrgQuery, BadMigrateUserRequest,
+    BadUpdateOrgRequest, BadUpdateUserMetadataRequest, ChangeUserRoleInOrgRequest,
+    CreateAccessTokenV2Request, CreateMagicLinkRequest, CreateOrgRequest, CreateUserRequest,
+    EmailsQuery, FetchOrgOrderBy, FetchUsersOrderBy, InviteUserToOrgRequest, MigrateUserRequest,
+    UpdateMetadataRequest, UpdateOrgRequest, UserIdsQuery, UsernamesQuery,
+};
+
+/// The longest a magic link can be valid for
+pub const MAX_MAGIC_LINK_EXPIRES_IN_HOURS: i64 = 24 * 365;
+
+/// The largest page PropelAuth returns
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+macro_rules! builder {
+    ($(#[$attr:meta])* $target:ident => $builder:ident { $($mode:ident $field:ident: $ty:ty),* $(,)? }) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone)]
+        pub struct $builder {
+            inner: $target,
+        }
+
+        impl $builder {
+            $(setter!($mode $field: $ty);)*
+        }
+    };
+}
+
+macro_rules! setter {
+    (set $field:ident: $ty:ty) => {
+        pub fn $field(mut self, $field: $ty) -> Self {
+            self.inner.$field = $field;
+            self
+        }
+    };
+    (set_into $field:ident: $ty:ty) => {
+        pub fn $field(mut self, $field: impl Into<$ty>) -> Self {
+            self.inner.$field = $field.into();
+            self
+        }
+    };
+    (some $field:ident: $ty:ty) => {
+        pub fn $field(mut self, $field: $ty) -> Self {
+            self.inner.$field = Some($field);
+            self
+        }
+    };
+    (some_into $field:ident: $ty:ty) => {
+        pub fn $field(mut self, $field: impl Into<$ty>) -> Self {
+            self.inner.$field = Some($field.into());
+            self
+        }
+    };
+}
+
+fn check(errors: &mut Option<Vec<String>>, valid: bool, message: &str) {
+    if !valid {
+        errors
+            .get_or_insert_with(Vec::new)
+            .push(message.to_string());
+    }
+}
+
+fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => !local.is_empty() && !domain.is_empty(),
+        None => false,
+    }
+}
+
+fn is_blank(value: &Option<String>) -> bool {
+    value
+        .as_deref()
+        .is_some_and(|value| value.trim().is_empty())
+}
+
+fn check_page(
+    page_size: &mut Option<Vec<String>>,
+    page_number: &mut Option<Vec<String>>,
+    size: Option<i64>,
+    number: Option<i64>,
+) {
+    check(
+        page_size,
+        size.is_none_or(|size| (1..=MAX_PAGE_SIZE).contains(&size)),
+        "Ensure this value is between 1 and 100",
+    );
+    check(
+        page_number,
+        number.is_none_or(|number| number >= 0),
+        "Ensure this value is greater than or equal to 0",
+    );
+}
+
+fn ok_or<T, E: Default + PartialEq>(value: T, errors: E) -> Result<T, E> {
+    if errors == E::default() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+builder!(
+    CreateUserRequest => CreateUserRequestBuilder {
+        set email_confirmed: bool,
+        set send_email_to_confirm_email_address: bool,
+        some_into password: String,
+        some_into username: String,
+        some_into first_name: String,
+        some_into last_name: String,
+        some properties: HashMap<String, Value>,
+        some ignore_domain_restrictions: bool,
+    }
+);
+
+impl CreateUserRequest {
+    pub fn builder(email: impl Into<String>) -> CreateUserRequestBuilder {
+        CreateUserRequestBuilder {
+            inner: CreateUserRequest {
+                email: email.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl CreateUserRequestBuilder {
+    pub fn build(self) -> Result<CreateUserRequest, BadCreateUserRequest> {
+        let request = self.inner;
+        let mut errors = BadCreateUserRequest::default();
+        check(
+            &mut errors.email,
+            is_valid_email(&request.email),
+            "Email is not valid",
+        );
+        check(
+            &mut errors.email,
+            !(request.email_confirmed && request.send_email_to_confirm_email_address),
+            "Can't send a confirmation email to an email that is already confirmed",
+        );
+        check(
+            &mut errors.password,
+            request
+                .password
+                .as_ref()
+                .is_none_or(|password| password.chars().count() >= MIN_PASSWORD_LENGTH),
+            "Password must be at least 8 characters",
+        );
+        check(
+            &mut errors.username,
+            !is_blank(&request.username),
+            "This field may not be blank",
+        );
+        ok_or(request, errors)
+    }
+}
+
+builder!(
+    MigrateUserRequest => MigrateUserRequestBuilder {
+        set email_confirmed: bool,
+        some_into existing_user_id: String,
+        some_into existing_password_hash: String,
+        some enabled: bool,
+        some_into username: String,
+        some_into first_name: String,
+        some_into last_name: String,
+        some_into picture_url: String,
+        some properties: HashMap<String, Value>,
+        some update_password_required: bool,
+    }
+);
+
+impl MigrateUserRequest {
+    pub fn builder(email: impl Into<String>) -> MigrateUserRequestBuilder {
+        MigrateUserRequestBuilder {
+            inner: MigrateUserRequest {
+                email: email.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl MigrateUserRequestBuilder {
+    /// Doesn't check the format of `existing_password_hash`, which `migrate_user` does when the
+    /// client uses strict password hashes
+    #[allow(clippy::result_large_err)]
+    pub fn build(self) -> Result<MigrateUserRequest, BadMigrateUserRequest> {
+        let request = self.inner;
+        let mut errors = BadMigrateUserRequest::default();
+        check(
+            &mut errors.email,
+            is_valid_email(&request.email),
+            "Email is not valid",
+        );
+        check(
+            &mut errors.existing_user_id,
+            !is_blank(&request.existing_user_id),
+            "This field may not be blank",
+        );
+        check(
+            &mut errors.existing_password_hash,
+            !is_blank(&request.existing_password_hash),
+            "This field may not be blank",
+        );
+        check(
+            &mut errors.username,
+            !is_blank(&request.username),
+            "This field may not be blank",
+        );
+        ok_or(request, errors)
+    }
+}
+
+builder!(
+    CreateOrgRequest => CreateOrgRequestBuilder {
+        some_into domain: String,
+        some enable_auto_joining_by_domain: bool,
+        some members_must_have_matching_domain: bool,
+        some max_users: i32,
+        some_into custom_role_mapping_name: String,
+        some_into legacy_org_id: String,
+    }
+);
+
+impl CreateOrgRequest {
+    pub fn builder(name: impl Into<String>) -> CreateOrgRequestBuilder {
+        CreateOrgRequestBuilder {
+            inner: CreateOrgRequest {
+                name: name.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl CreateOrgRequestBuilder {
+    pub fn build(self) -> Result<CreateOrgRequest, BadCreateOrgRequest> {
+        let request = self.inner;
+        let mut errors = BadCreateOrgRequest::default();
+        check(
+            &mut errors.name,
+            !request.name.trim().is_empty(),
+            "This field may not be blank",
+        );
+        ok_or(request, errors)
+    }
+}
+
+builder!(
+    UpdateOrgRequest => UpdateOrgRequestBuilder {
+        some_into name: String,
+        some can_setup_saml: bool,
+        some max_users: i32,
+        some metadata: HashMap<String, Value>,
+        some_into domain: String,
+        some autojoin_by_domain: bool,
+        some restrict_to_domain: bool,
+        some_into legacy_org_id: String,
+        some extra_domains: Vec<String>,
+    }
+);
+
+impl UpdateOrgRequest {
+    pub fn builder() -> UpdateOrgRequestBuilder {
+        UpdateOrgRequestBuilder {
+            inner: UpdateOrgRequest::default(),
+        }
+    }
+}
+
+impl UpdateOrgRequestBuilder {
+    pub fn build(self) -> Result<UpdateOrgRequest, BadUpdateOrgRequest> {
+        let request = self.inner;
+        let mut errors = BadUpdateOrgRequest::default();
+        check(
+            &mut errors.name,
+            !is_blank(&request.name),
+            "This field may not be blank",
+        );
+        ok_or(request, errors)
+    }
+}
+
+builder!(
+    CreateMagicLinkRequest => CreateMagicLinkRequestBuilder {
+        some_into redirect_to_url: String,
+        some expires_in_hours: i64,
+        some create_new_user_if_one_doesnt_exist: bool,
+    }
+);
+
+impl CreateMagicLinkRequest {
+    pub fn builder(email: impl Into<String>) -> CreateMagicLinkRequestBuilder {
+        CreateMagicLinkRequestBuilder {
+            inner: CreateMagicLinkRequest {
+                email: email.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl CreateMagicLinkRequestBuilder {
+    pub fn build(self) -> Result<CreateMagicLinkRequest, BadCreateMagicLinkRequest> {
+        let request = self.inner;
+        let mut errors = BadCreateMagicLinkRequest::default();
+        check(
+            &mut errors.email,
+            is_valid_email(&request.email),
+            "Email is not valid",
+        );
+        check(
+            &mut errors.expires_in_hours,
+            request
+                .expires_in_hours
+                .is_none_or(|hours| (1..=MAX_MAGIC_LINK_EXPIRES_IN_HOURS).contains(&hours)),
+            "Ensure this value is between 1 and 8760",
+        );
+        check(
+            &mut errors.redirect_to_url,
+            !is_blank(&request.redirect_to_url),
+            "This field may not be blank",
+        );
+        ok_or(request, errors)
+    }
+}
+
+builder!(
+    UpdateMetadataRequest => UpdateMetadataRequestBuilder {
+        some_into username: String,
+        some_into first_name: String,
+        some_into last_name: String,
+        some_into picture_url: String,
+        some metadata: HashMap<String, Value>,
+        some properties: HashMap<String, Value>,
+        some update_password_required: bool,
+        some_into legacy_user_id: String,
+    }
+);
+
+impl UpdateMetadataRequest {
+    pub fn builder() -> UpdateMetadataRequestBuilder {
+        UpdateMetadataRequestBuilder {
+            inner: UpdateMetadataRequest::default(),
+        }
+    }
+}
+
+impl UpdateMetadataRequestBuilder {
+    pub fn build(self) -> Result<UpdateMetadataRequest, BadUpdateUserMetadataRequest> {
+        let request = self.inner;
+        let mut errors = BadUpdateUserMetadataRequest::default();
+        check(
+            &mut errors.username,
+            !is_blank(&request.username),
+            "This field may not be blank",
+        );
+        check(
+            &mut errors.picture_url,
+            !is_blank(&request.picture_url),
+            "This field may not be blank",
+        );
+        ok_or(request, errors)
+    }
+}
+
+builder!(
+    CreateAccessTokenV2Request => CreateAccessTokenV2RequestBuilder {
+        some_into active_org_id: String,
+    }
+);
+
+impl CreateAccessTokenV2Request {
+    pub fn builder(
+        user_id: impl Into<String>,
+        duration_in_minutes: u64,
+    ) -> CreateAccessTokenV2RequestBuilder {
+        CreateAccessTokenV2RequestBuilder {
+            inner: CreateAccessTokenV2Request {
+                user_id: user_id.into(),
+                duration_in_minutes,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl CreateAccessTokenV2RequestBuilder {
+    pub fn build(self) -> CreateAccessTokenV2Request {
+        self.inner
+    }
+}
+
+builder!(
+    AddUserToOrgRequest => AddUserToOrgRequestBuilder {
+        set additional_roles: Vec<String>,
+    }
+);
+
+impl AddUserToOrgRequest {
+    pub fn builder(
+        user_id: impl Into<String>,
+        org_id: impl Into<String>,
+        role: impl Into<String>,
+    ) -> AddUserToOrgRequestBuilder {
+        AddUserToOrgRequestBuilder {
+            inner: AddUserToOrgRequest {
+                user_id: user_id.into(),
+                org_id: org_id.into(),
+                role: role.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl AddUserToOrgRequestBuilder {
+    pub fn build(self) -> AddUserToOrgRequest {
+        self.inner
+    }
+}
+
+builder!(
+    ChangeUserRoleInOrgRequest => ChangeUserRoleInOrgRequestBuilder {
+        set additional_roles: Vec<String>,
+    }
+);
+
+impl ChangeUserRoleInOrgRequest {
+    pub fn builder(
+        user_id: impl Into<String>,
+        org_id: impl Into<String>,
+        role: impl Into<String>,
+    ) -> ChangeUserRoleInOrgRequestBuilder {
+        ChangeUserRoleInOrgRequestBuilder {
+            inner: ChangeUserRoleInOrgRequest {
+                user_id: user_id.into(),
+                org_id: org_id.into(),
+                role: role.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl ChangeUserRoleInOrgRequestBuilder {
+    pub fn build(self) -> ChangeUserRoleInOrgRequest {
+        self.inner
+    }
+}
+
+builder!(
+    InviteUserToOrgRequest => InviteUserToOrgRequestBuilder {
+        set additional_roles: Vec<String>,
+    }
+);
+
+impl InviteUserToOrgRequest {
+    pub fn builder(
+        org_id: impl Into<String>,
+        email: impl Into<String>,
+        role: impl Into<String>,
+    ) -> InviteUserToOrgRequestBuilder {
+        InviteUserToOrgRequestBuilder {
+            inner: InviteUserToOrgRequest {
+                org_id: org_id.into(),
+                email: email.into(),
+                role: role.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl InviteUserToOrgRequestBuilder {
+    pub fn build(self) -> InviteUserToOrgRequest {
+        self.inner
+    }
+}
+
+builder!(
+    FetchUsersByQueryParams => FetchUsersByQueryParamsBuilder {
+        some page_size: i64,
+        some page_number: i64,
+        some order_by: FetchUsersOrderBy,
+        some_into email_or_username: String,
+        some include_orgs: bool,
+        some_into legacy_user_id: String,
+    }
+);
+
+impl FetchUsersByQueryParams {
+    pub fn builder() -> FetchUsersByQueryParamsBuilder {
+        FetchUsersByQueryParamsBuilder {
+            inner: FetchUsersByQueryParams::default(),
+        }
+    }
+}
+
+impl FetchUsersByQueryParamsBuilder {
+    pub fn build(self) -> Result<FetchUsersByQueryParams, BadFetchUsersByQuery> {
+        let params = self.inner;
+        let mut errors = BadFetchUsersByQuery::default();
+        check_page(
+            &mut errors.page_size,
+            &mut errors.page_number,
+            params.page_size,
+            params.page_number,
+        );
+        ok_or(params, errors)
+    }
+}
+
+builder!(
+    FetchOrgsByQueryParams => FetchOrgsByQueryParamsBuilder {
+        some page_size: i64,
+        some page_number: i64,
+        some order_by: FetchOrgOrderBy,
+        some_into name: String,
+        some_into legacy_org_id: String,
+        some_into domain: String,
+    }
+);
+
+impl FetchOrgsByQueryParams {
+    pub fn builder() -> FetchOrgsByQueryParamsBuilder {
+        FetchOrgsByQueryParamsBuilder {
+            inner: FetchOrgsByQueryParams::default(),
+        }
+    }
+}
+
+impl FetchOrgsByQueryParamsBuilder {
+    pub fn build(self) -> Result<FetchOrgsByQueryParams, BadFetchOrgQuery> {
+        let params = self.inner;
+        let mut errors = BadFetchOrgQuery::default();
+        check_page(
+            &mut errors.page_size,
+            &mut errors.page_number,
+            params.page_size,
+            params.page_number,
+        );
+        ok_or(params, errors)
+    }
+}
+
+builder!(
+    FetchUsersInOrgParams => FetchUsersInOrgParamsBuilder {
+        some page_size: i64,
+        some page_number: i64,
+        some include_orgs: bool,
+        some_into role: String,
+    }
+);
+
+impl FetchUsersInOrgParams {
+    pub fn builder(org_id: impl Into<String>) -> FetchUsersInOrgParamsBuilder {
+        FetchUsersInOrgParamsBuilder {
+            inner: FetchUsersInOrgParams {
+                org_id: org_id.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl FetchUsersInOrgParamsBuilder {
+    pub fn build(self) -> Result<FetchUsersInOrgParams, BadFetchUsersInOrgQuery> {
+        let params = self.inner;
+        let mut errors = BadFetchUsersInOrgQuery::default();
+        check_page(
+            &mut errors.page_size,
+            &mut errors.page_number,
+            params.page_size,
+            params.page_number,
+        );
+        ok_or(params, errors)
+    }
+}
+
+builder!(
+    FetchPendingInvitesParams => FetchPendingInvitesParamsBuilder {
+        some page_size: i64,
+        some page_number: i64,
+        some_into org_id: String,
+    }
+);
+
+impl FetchPendingInvitesParams {
+    pub fn builder() -> FetchPendingInvitesParamsBuilder {
+        FetchPendingInvitesParamsBuilder {
+            inner: FetchPendingInvitesParams::default(),
+        }
+    }
+}
+
+impl FetchPendingInvitesParamsBuilder {
+    pub fn build(self) -> FetchPendingInvitesParams {
+        self.inner
+    }
+}
+
+builder!(
+    CreateSamlConnectionLinkParams => CreateSamlConnectionLinkParamsBuilder {
+        some expires_in_seconds: i64,
+    }
+);
+
+impl CreateSamlConnectionLinkParams {
+    pub fn builder(org_id: impl Into<String>) -> CreateSamlConnectionLinkParamsBuilder {
+        CreateSamlConnectionLinkParamsBuilder {
+            inner: CreateSamlConnectionLinkParams {
+                org_id: org_id.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl CreateSamlConnectionLinkParamsBuilder {
+    pub fn build(self) -> CreateSamlConnectionLinkParams {
+        self.inner
+    }
+}
+
+builder!(
+    ApiKeyQueryParams => ApiKeyQueryParamsBuilder {
+        some_into user_id: String,
+        some_into user_email: String,
+        some_into org_id: String,
+        some page_size: i64,
+        some page_number: i64,
+    }
+);
+
+impl ApiKeyQueryParams {
+    pub fn builder() -> ApiKeyQueryParamsBuilder {
+        ApiKeyQueryParamsBuilder {
+            inner: ApiKeyQueryParams::default(),
+        }
+    }
+}
+
+impl ApiKeyQueryParamsBuilder {
+    pub fn build(self) -> ApiKeyQueryParams {
+        self.inner
+    }
+}
+
+builder!(
+    CreateApiKeyParams => CreateApiKeyParamsBuilder {
+        some_into metadata: Value,
+        some_into user_id: String,
+        some_into org_id: String,
+    }
+);
+
+impl CreateApiKeyParams {
+    pub fn builder() -> CreateApiKeyParamsBuilder {
+        CreateApiKeyParamsBuilder {
+            inner: CreateApiKeyParams::default(),
+        }
+    }
+}
+
+impl CreateApiKeyParamsBuilder {
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.inner.set_expires_at(Some(expires_at));
+        self
+    }
+
+    pub fn build(self) -> CreateApiKeyParams {
+        self.inner
+    }
+}
+
+builder!(
+    UpdateApiKeyParams => UpdateApiKeyParamsBuilder {
+        some_into metadata: Value,
+    }
+);
+
+impl UpdateApiKeyParams {
+    pub fn builder() -> UpdateApiKeyParamsBuilder {
+        UpdateApiKeyParamsBuilder {
+            inner: UpdateApiKeyParams::default(),
+        }
+    }
+}
+
+impl UpdateApiKeyParamsBuilder {
+    /// Replaces an earlier [`never_expire`](Self::never_expire)
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.inner.set_expires_at(Some(expires_at));
+        self
+    }
+
+    /// Replaces an earlier [`expires_at`](Self::expires_at)
+    pub fn never_expire(mut self) -> Self {
+        self.inner.set_expires_at(None);
+        self
+    }
+
+    pub fn build(self) -> UpdateApiKeyParams {
+        self.inner
+    }
+}
+
+macro_rules! fetch_user_by_builder {
+    ($target:ident => $builder:ident, $field:ident) => {
+        builder!(
+            $target => $builder {
+                some include_orgs: bool,
+            }
+        );
+
+        impl $target {
+            pub fn builder($field: impl Into<String>) -> $builder {
+                $builder {
+                    inner: $target {
+                        $field: $field.into(),
+                        ..Default::default()
+                    },
+                }
+            }
+        }
+
+        impl $builder {
+            pub fn build(self) -> $target {
+                self.inner
+            }
+        }
+    };
+}
+
+fetch_user_by_builder!(FetchUserByIdParams => FetchUserByIdParamsBuilder, user_id);
+fetch_user_by_builder!(FetchUserByEmailParams => FetchUserByEmailParamsBuilder, email);
+fetch_user_by_builder!(FetchUserByUsernameParams => FetchUserByUsernameParamsBuilder, username);
+
+macro_rules! fetch_users_by_builder {
+    ($target:ident => $builder:ident, $query_field:ident: $query:ident { $field:ident }) => {
+        builder!(
+            $target => $builder {
+                some include_orgs: bool,
+            }
+        );
+
+        impl $target {
+            pub fn builder($field: impl IntoIterator<Item = impl Into<String>>) -> $builder {
+                $builder {
+                    inner: $target {
+                        $query_field: $query {
+                            $field: $field.into_iter().map(Into::into).collect(),
+                        },
+                        ..Default::default()
+                    },
+                }
+            }
+        }
+
+        impl $builder {
+            pub fn build(self) -> $target {
+                self.inner
+            }
+        }
+    };
+}
+
+fetch_users_by_builder!(
+    FetchUsersByIdsParams => FetchUsersByIdsParamsBuilder,
+    user_ids_query: UserIdsQuery { user_ids }
+);
+fetch_users_by_builder!(
+    FetchUsersByEmailsParams => FetchUsersByEmailsParamsBuilder,
+    emails_query: EmailsQuery { emails }
+);
+fetch_users_by_builder!(
+    FetchUsersByUsernamesParams => FetchUsersByUsernamesParamsBuilder,
+    usernames_query: UsernamesQuery { usernames }
+);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    use crate::apis::api_key_service_api::UpdateApiKeyParams;
+    use crate::apis::user_service_api::{FetchUsersByIdsParams, FetchUsersByQueryParams};
+    use crate::models::{
+        BadCreateMagicLinkRequest, BadCreateUserRequest, BadFetchUsersByQuery,
+        CreateMagicLinkRequest, CreateOrgRequest, CreateUserRequest,
+    };
+    use crate::propelauth::ids::UserId;
+
+    #[test]
+    fn builds_requests() {
+        let request = CreateUserRequest::builder("a@example.com")
+            .email_confirmed(true)
+            .password("hunter22")
+            .properties(HashMap::from([("plan".to_string(), json!("pro"))]))
+            .build()
+            .unwrap();
+        assert_eq!(
+            CreateUserRequest {
+                email: "a@example.com".to_string(),
+                email_confirmed: true,
+                password: Some("hunter22".to_string()),
+                properties: Some(HashMap::from([("plan".to_string(), json!("pro"))])),
+                ..Default::default()
+            },
+            request
+        );
+
+        let user_id: UserId = "e4b4a1e4-2c7b-4a62-9a83-3f3bdb0f1bd6".parse().unwrap();
+        let params = FetchUsersByIdsParams::builder([user_id])
+            .include_orgs(true)
+            .build();
+        assert_eq!(
+            vec!["e4b4a1e4-2c7b-4a62-9a83-3f3bdb0f1bd6".to_string()],
+            params.user_ids_query.user_ids
+        );
+        assert_eq!(Some(true), params.include_orgs);
+
+        let expires_at = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let params = UpdateApiKeyParams::builder()
+            .never_expire()
+            .expires_at(expires_at)
+            .build();
+        assert_eq!(Some(expires_at), params.expires_at());
+        assert_eq!(None, params.set_to_never_expire);
+    }
+
+    #[test]
+    fn build_returns_the_bad_request_model() {
+        assert_eq!(
+            Err(BadCreateUserRequest {
+                email: Some(vec![
+                    "Email is not valid".to_string(),
+                    "Can't send a confirmation email to an email that is already confirmed"
+                        .to_string(),
+                ]),
+                password: Some(vec!["Password must be at least 8 characters".to_string()]),
+                ..Default::default()
+            }),
+            CreateUserRequest::builder("")
+                .email_confirmed(true)
+                .send_email_to_confirm_email_address(true)
+                .password("short")
+                .build()
+        );
+
+        assert_eq!(
+            Err(BadCreateMagicLinkRequest {
+                expires_in_hours: Some(vec!["Ensure this value is between 1 and 8760".to_string()]),
+                ..Default::default()
+            }),
+            CreateMagicLinkRequest::builder("a@example.com")
+                .expires_in_hours(0)
+                .build()
+        );
+
+        assert!(CreateOrgRequest::builder("  ").build().is_err());
+
+        assert_eq!(
+            BadFetchUsersByQuery {
+                page_size: Some(vec!["Ensure this value is between 1 and 100".to_string()]),
+                ..Default::default()
+            },
+            FetchUsersByQueryParams::builder()
+                .page_size(500)
+                .build()
+                .unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,524 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::apis::configuration::Configuration;
+use crate::models::{FetchOrgResponse, UserMetadata};
+
+/// Identifies a cached lookup. Users fetched with and without their orgs are cached separately.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CacheKey {
+    User {
+        user_id: String,
+        include_orgs: bool,
+    },
+    Org {
+        org_id: String,
+    },
+    /// The ID of the user with this legacy ID
+    LegacyUser {
+        legacy_user_id: String,
+    },
+    /// The ID of the org with this legacy ID
+    LegacyOrg {
+        legacy_org_id: String,
+    },
+}
+
+impl CacheKey {
+    /// IDs are lower-cased, so that reads and writes that spell the same ID differently share a key
+    pub fn user(user_id: &str, include_orgs: bool) -> CacheKey {
+        CacheKey::User {
+            user_id: user_id.to_ascii_lowercase(),
+            include_orgs,
+        }
+    }
+
+    pub fn org(org_id: &str) -> CacheKey {
+        CacheKey::Org {
+            org_id: org_id.to_ascii_lowercase(),
+        }
+    }
+
+    /// Every key a user may be cached under
+    pub fn for_user(user_id: &str) -> Vec<CacheKey> {
+        vec![
+            CacheKey::user(user_id, false),
+            CacheKey::user(user_id, true),
+        ]
+    }
+
+    pub fn for_org(org_id: &str) -> Vec<CacheKey> {
+        vec![CacheKey::org(org_id)]
+    }
+}
+
+/// Formats the key as a string, e.g. for use as a Redis key
+impl fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheKey::User {
+                user_id,
+                include_orgs: false,
+            } => write!(f, "user:{}", user_id),
+            CacheKey::User {
+                user_id,
+                include_orgs: true,
+            } => write!(f, "user:{}:orgs", user_id),
+            CacheKey::Org { org_id } => write!(f, "org:{}", org_id),
+            CacheKey::LegacyUser { legacy_user_id } => write!(f, "legacy_user:{}", legacy_user_id),
+            CacheKey::LegacyOrg { legacy_org_id } => write!(f, "legacy_org:{}", legacy_org_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CachedValue {
+    User(UserMetadata),
+    Org(FetchOrgResponse),
+    /// A user or org ID that a legacy ID resolved to
+    Id(String),
+}
+
+/// Where cached lookups are stored. [`InMemoryCache`] is used by
+/// [`PropelAuth::with_cache`](crate::propelauth::auth::PropelAuth::with_cache), and any other
+/// store (e.g. Redis) can be plugged in with
+/// [`PropelAuth::with_cache_backend`](crate::propelauth::auth::PropelAuth::with_cache_backend).
+///
+/// Backends are best-effort: a failed read should return `None` and a failed write can be
+/// ignored, and the request will go to PropelAuth instead.
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    /// Returns the value stored under `key`, unless it has expired
+    async fn get(&self, key: &CacheKey) -> Option<CachedValue>;
+
+    /// Stores `value` under `key` for `ttl`
+    async fn insert(&self, key: CacheKey, value: CachedValue, ttl: Duration);
+
+    async fn remove(&self, key: &CacheKey);
+}
+
+impl fmt::Debug for dyn CacheBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CacheBackend")
+    }
+}
+
+/// Options for the in-memory cache enabled by
+/// [`PropelAuth::with_cache`](crate::propelauth::auth::PropelAuth::with_cache)
+#[derive(Debug, Clone)]
+pub struct CacheOptions {
+    /// How long a lookup is served from the cache. Defaults to 60 seconds.
+    pub ttl: Duration,
+    /// The most users and orgs kept at once. Defaults to 10,000.
+    pub max_entries: usize,
+}
+
+impl Default for CacheOptions {
+    fn default() -> Self {
+        CacheOptions {
+            ttl: Duration::from_secs(60),
+            max_entries: 10_000,
+        }
+    }
+}
+
+/// A size-bounded [`CacheBackend`] kept in this process. When full, expired entries are
+/// dropped first, followed by the ones closest to expiring.
+#[derive(Debug)]
+pub struct InMemoryCache {
+    max_entries: usize,
+    entries: Mutex<HashMap<CacheKey, (Instant, CachedValue)>>,
+}
+
+impl InMemoryCache {
+    pub fn new(max_entries: usize) -> InMemoryCache {
+        InMemoryCache {
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The number of entries currently stored, including expired ones not yet evicted
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<CacheKey, (Instant, CachedValue)>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl CacheBackend for InMemoryCache {
+    async fn get(&self, key: &CacheKey) -> Option<CachedValue> {
+        let mut entries = self.lock();
+        match entries.get(key) {
+            Some((expires_at, value)) if *expires_at > Instant::now() => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn insert(&self, key: CacheKey, value: CachedValue, ttl: Duration) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut entries = self.lock();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            let now = Instant::now();
+            entries.retain(|_, (expires_at, _)| *expires_at > now);
+        }
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            let soonest_to_expire = entries
+                .iter()
+                .min_by_key(|(_, (expires_at, _))| *expires_at)
+                .map(|(key, _)| key.clone());
+            if let Some(soonest_to_expire) = soonest_to_expire {
+                entries.remove(&soonest_to_expire);
+            }
+        }
+        entries.insert(key, (Instant::now() + ttl, value));
+    }
+
+    async fn remove(&self, key: &CacheKey) {
+        self.lock().remove(key);
+    }
+}
+
+/// The cache backend and TTL shared by every service created from a
+/// [`Configuration`](crate::apis::configuration::Configuration)
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    pub backend: Arc<dyn CacheBackend>,
+    pub ttl: Duration,
+}
+
+pub(crate) async fn read_cached(config: &Configuration, key: &CacheKey) -> Option<CachedValue> {
+    match &config.cache {
+        Some(cache) => cache.backend.get(key).await,
+        None => None,
+    }
+}
+
+pub(crate) async fn write_cached(config: &Configuration, key: CacheKey, value: CachedValue) {
+    if let Some(cache) = &config.cache {
+        cache.backend.insert(key, value, cache.ttl).await;
+    }
+}
+
+/// Runs the mutation `call`, then drops `keys` from the cache whether or not it succeeded
+pub(crate) async fn invalidating<T, Fut>(
+    config: &Configuration,
+    keys: Vec<CacheKey>,
+    call: Fut,
+) -> T
+where
+    Fut: Future<Output = T>,
+{
+    let result = call.await;
+    if let Some(cache) = &config.cache {
+        for key in &keys {
+            cache.backend.remove(key).await;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::models::{FetchOrgResponse, UserMetadata};
+    use crate::propelauth::cache::{CacheBackend, CacheKey, CachedValue, InMemoryCache};
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn org(org_id: &str) -> (CacheKey, CachedValue) {
+        (
+            CacheKey::org(org_id),
+            CachedValue::Org(FetchOrgResponse {
+                org_id: org_id.to_string(),
+                ..Default::default()
+            }),
+        )
+    }
+
+    #[test]
+    fn in_memory_cache_expires_and_evicts_entries() {
+        let cache = InMemoryCache::new(2);
+        block_on(async {
+            let (expiring_key, expiring) = org("expiring");
+            cache
+                .insert(expiring_key.clone(), expiring, Duration::from_millis(0))
+                .await;
+            assert_eq!(None, cache.get(&expiring_key).await);
+
+            let (a_key, a) = org("a");
+            let (b_key, b) = org("b");
+            let (c_key, c) = org("c");
+            cache
+                .insert(a_key.clone(), a, Duration::from_secs(10))
+                .await;
+            cache
+                .insert(b_key.clone(), b.clone(), Duration::from_secs(20))
+                .await;
+            cache
+                .insert(c_key.clone(), c.clone(), Duration::from_secs(30))
+                .await;
+
+            assert_eq!(2, cache.len());
+            assert_eq!(None, cache.get(&a_key).await);
+            assert_eq!(Some(b), cache.get(&b_key).await);
+            assert_eq!(Some(c), cache.get(&c_key).await);
+
+            cache.remove(&b_key).await;
+            assert_eq!(None, cache.get(&b_key).await);
+        });
+    }
+
+    #[test]
+    fn keys_format_as_strings() {
+        let keys = CacheKey::for_user("1234");
+        assert_eq!("user:1234", keys[0].to_string());
+        assert_eq!("user:1234:orgs", keys[1].to_string());
+        assert_eq!("org:5678", CacheKey::for_org("5678")[0].to_string());
+        let legacy_key = CacheKey::LegacyUser {
+            legacy_user_id: "42".to_string(),
+        };
+        assert_eq!("legacy_user:42", legacy_key.to_string());
+
+        let value = CachedValue::User(UserMetadata::default());
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(value, serde_json::from_str(&json).unwrap());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn lookups_are_cached_until_a_mutation() {
+        use std::collections::HashMap;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        use reqwest::Request;
+
+        use crate::apis::interceptor::Interceptor;
+        use crate::apis::user_service_api::{FetchUserByIdParams, UpdateUserMetadataParams};
+        use crate::models::{
+            AddUserToOrgRequest, CreateOrgRequest, CreateUserRequest, UpdateMetadataRequest,
+            UpdateOrgRequest,
+        };
+        use crate::propelauth::cache::CacheOptions;
+        use crate::testing::FakeBackend;
+
+        struct CountRequests(Arc<AtomicUsize>);
+        impl Interceptor for CountRequests {
+            fn before_send(&self, _request: &mut Request) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let fake = FakeBackend::start();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let auth = fake
+            .auth()
+            .with_interceptor(CountRequests(requests.clone()))
+            .with_cache(CacheOptions::default());
+        let requests_made = || requests.swap(0, Ordering::SeqCst);
+
+        block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            let org_id = auth
+                .org()
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id;
+            requests_made();
+
+            let users = auth.user();
+            let fetch_user = |include_orgs| {
+                users.fetch_user_by_id(FetchUserByIdParams {
+                    user_id: user_id.clone(),
+                    include_orgs: Some(include_orgs),
+                })
+            };
+            fetch_user(false).await.unwrap();
+            fetch_user(false).await.unwrap();
+            fetch_user(true).await.unwrap();
+            assert_eq!(2, requests_made());
+
+            auth.user()
+                .update_user_metadata(UpdateUserMetadataParams {
+                    user_id: user_id.clone(),
+                    update_metadata_request: UpdateMetadataRequest {
+                        first_name: Some("Updated".to_string()),
+                        ..Default::default()
+                    },
+                })
+                .await
+                .unwrap();
+            let user = fetch_user(false).await.unwrap();
+            assert_eq!(Some("Updated".to_string()), user.first_name);
+            assert_eq!(2, requests_made());
+
+            auth.org()
+                .add_user_to_org(AddUserToOrgRequest::new(
+                    user_id.clone(),
+                    org_id.clone(),
+                    "Admin".to_string(),
+                ))
+                .await
+                .unwrap();
+            let user = fetch_user(true).await.unwrap();
+            assert!(user.org_id_to_org_info.unwrap().contains_key(&org_id));
+            assert_eq!(2, requests_made());
+
+            let typed_user_id = user_id.parse().unwrap();
+            fetch_user(false).await.unwrap();
+            requests_made();
+            users
+                .enable_user_can_create_orgs_by_id(typed_user_id)
+                .await
+                .unwrap();
+            fetch_user(false).await.unwrap();
+            users
+                .disable_user_can_create_orgs_by_id(typed_user_id)
+                .await
+                .unwrap();
+            fetch_user(false).await.unwrap();
+            assert_eq!(4, requests_made());
+
+            let typed_org_id = org_id.parse().unwrap();
+            auth.org().fetch_org_by_id(typed_org_id).await.unwrap();
+            auth.org().fetch_org_by_id(typed_org_id).await.unwrap();
+            assert_eq!(1, requests_made());
+
+            auth.org()
+                .update_org_by_id(
+                    typed_org_id,
+                    UpdateOrgRequest {
+                        name: Some("Renamed".to_string()),
+                        metadata: Some(HashMap::new()),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            let org = auth.org().fetch_org_by_id(typed_org_id).await.unwrap();
+            assert_eq!("Renamed", org.name);
+            assert_eq!(2, requests_made());
+        });
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn invalidation_ignores_the_case_of_ids() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        use reqwest::Request;
+
+        use crate::apis::interceptor::Interceptor;
+        use crate::apis::org_service_api::AllowOrgToEnableSamlParams;
+        use crate::apis::user_service_api::{FetchUserByIdParams, UpdateUserMetadataParams};
+        use crate::models::{CreateOrgRequest, CreateUserRequest, UpdateMetadataRequest};
+        use crate::propelauth::cache::CacheOptions;
+        use crate::testing::FakeBackend;
+
+        struct CountRequests(Arc<AtomicUsize>);
+        impl Interceptor for CountRequests {
+            fn before_send(&self, _request: &mut Request) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let fake = FakeBackend::start();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let auth = fake
+            .auth()
+            .with_interceptor(CountRequests(requests.clone()))
+            .with_cache(CacheOptions::default());
+        let requests_made = || requests.swap(0, Ordering::SeqCst);
+
+        block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            let org_id = auth
+                .org()
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id;
+
+            let users = auth.user();
+            let fetch_user = || {
+                users.fetch_user_by_id(FetchUserByIdParams {
+                    user_id: user_id.clone(),
+                    include_orgs: None,
+                })
+            };
+            fetch_user().await.unwrap();
+            requests_made();
+            let _ = users
+                .update_user_metadata(UpdateUserMetadataParams {
+                    user_id: user_id.to_ascii_uppercase(),
+                    update_metadata_request: UpdateMetadataRequest::default(),
+                })
+                .await;
+            fetch_user().await.unwrap();
+            assert_eq!(2, requests_made());
+
+            let typed_org_id = org_id.parse().unwrap();
+            auth.org().fetch_org_by_id(typed_org_id).await.unwrap();
+            requests_made();
+            let _ = auth
+                .org()
+                .allow_org_to_enable_saml(AllowOrgToEnableSamlParams {
+                    org_id: org_id.to_ascii_uppercase(),
+                })
+                .await;
+            auth.org().fetch_org_by_id(typed_org_id).await.unwrap();
+            assert_eq!(2, requests_made());
+        });
+    }
+}
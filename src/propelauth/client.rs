@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::apis::access_token_service_api::{CreateAccessTokenParams, CreateAccessTokenV2Params};
+use crate::apis::api_key_service_api::{
+    ApiKeyError, ApiKeyQueryParams, CreateApiKeyParams, UpdateApiKeyParams, ValidateApiKeyParams,
+};
+use crate::apis::configuration::Configuration;
+use crate::apis::org_service_api::{
+    AllowOrgToEnableSamlParams, CreateSamlConnectionLinkParams, DisallowSamlParams,
+    FetchOrgsByQueryParams, FetchPendingInvitesParams, FetchUsersInOrgParams,
+};
+use crate::apis::user_service_api::{
+    FetchUserByEmailParams, FetchUserByIdParams, FetchUserByUsernameParams,
+    FetchUsersByEmailsParams, FetchUsersByIdsParams, FetchUsersByQueryParams,
+    FetchUsersByUsernamesParams, UpdateUserEmailParams, UpdateUserMetadataParams,
+    UpdateUserPasswordParams,
+};
+use crate::models::validate_api_key_response::{
+    ValidateOrgApiKeyResponse, ValidatePersonalApiKeyResponse,
+};
+use crate::models::{
+    AddUserToOrgRequest, ChangeUserRoleInOrgRequest, CreateAccessTokenResponse,
+    CreateApiKeyResponse, CreateMagicLinkRequest, CreateOrgRequest, CreateOrgResponse,
+    CreateSamlConnectionLinkResponse, CreateUserRequest, CreatedUserResponse, FetchApiKeyResponse,
+    FetchApiKeysPagedResponse, FetchOrgResponse, FetchOrgsResponse, FetchSamlSpMetadataResponse,
+    InviteUserToOrgRequest, MagicLink, MigrateUserPasswordRequest, MigrateUserRequest,
+    RemoveUserFromOrgRequest, RevokePendingOrgInviteRequest, UpdateOrgRequest, UserMetadata,
+    UserPagedResponse, ValidateApiKeyResponse,
+};
+use crate::propelauth::access_token::AccessTokenService;
+use crate::propelauth::api_key::ApiKeyService;
+use crate::propelauth::errors::{
+    BatchFetchError, ClearPasswordError, CreateAccessTokenError, CreateMagicLinkError,
+    CreateOrgError, CreateUserError, ErrorsWithNotFound, FetchByQueryError, FetchOrgsByQueryError,
+    FetchUsersInOrgError, InviteUserToOrgError, MigrateUserError, MigrateUserPasswordError,
+    OrgMissingOrRoleError, ResendEmailConfirmationError, SubscribeOrgToRoleMappingError,
+    UpdateOrgError, UpdatePasswordError, UpdateUserEmailError, UpdateUserMetadataError,
+};
+use crate::propelauth::org::OrgService;
+use crate::propelauth::traits::{AccessTokenApi, ApiKeyApi, OrgApi, UserApi};
+use crate::propelauth::user::UserService;
+
+/// An owned handle to the backend API, created with
+/// [`PropelAuth::client`](crate::propelauth::auth::PropelAuth::client).
+///
+/// Unlike the services returned by `auth.user()` and friends, it doesn't borrow from
+/// `PropelAuth`, so it's cheap to clone and can be stored in app state or behind an `Arc` as
+/// any of the [`UserApi`], [`OrgApi`], [`ApiKeyApi`] or [`AccessTokenApi`] traits.
+#[derive(Debug, Clone)]
+pub struct PropelAuthClient {
+    pub(crate) config: Configuration,
+}
+
+impl PropelAuthClient {
+    /// API requests related to users
+    pub fn user(&self) -> UserService<'_> {
+        UserService {
+            config: &self.config,
+        }
+    }
+
+    /// API requests related to organizations
+    pub fn org(&self) -> OrgService<'_> {
+        OrgService {
+            config: &self.config,
+        }
+    }
+
+    /// API requests related to end user API keys
+    pub fn api_key(&self) -> ApiKeyService<'_> {
+        ApiKeyService {
+            config: &self.config,
+        }
+    }
+
+    /// API requests related to access tokens
+    pub fn access_token(&self) -> AccessTokenService<'_> {
+        AccessTokenService {
+            config: &self.config,
+        }
+    }
+}
+
+#[async_trait]
+impl UserApi for PropelAuthClient {
+    async fn fetch_user_by_email(
+        &self,
+        params: FetchUserByEmailParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        self.user().fetch_user_by_email(params).await
+    }
+
+    async fn fetch_user_by_id(
+        &self,
+        params: FetchUserByIdParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        self.user().fetch_user_by_id(params).await
+    }
+
+    async fn fetch_user_by_username(
+        &self,
+        params: FetchUserByUsernameParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        self.user().fetch_user_by_username(params).await
+    }
+
+    async fn fetch_users_by_ids(
+        &self,
+        params: FetchUsersByIdsParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
+        self.user().fetch_users_by_ids(params).await
+    }
+
+    async fn fetch_users_by_emails(
+        &self,
+        params: FetchUsersByEmailsParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
+        self.user().fetch_users_by_emails(params).await
+    }
+
+    async fn fetch_users_by_usernames(
+        &self,
+        params: FetchUsersByUsernamesParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
+        self.user().fetch_users_by_usernames(params).await
+    }
+
+    async fn fetch_users_by_query(
+        &self,
+        params: FetchUsersByQueryParams,
+    ) -> Result<UserPagedResponse, FetchByQueryError> {
+        self.user().fetch_users_by_query(params).await
+    }
+
+    async fn create_user(
+        &self,
+        create_user_request: CreateUserRequest,
+    ) -> Result<CreatedUserResponse, CreateUserError> {
+        self.user().create_user(create_user_request).await
+    }
+
+    async fn delete_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.user().delete_user_by_raw_id(user_id).await
+    }
+
+    async fn disable_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.user().disable_user_by_raw_id(user_id).await
+    }
+
+    async fn logout_all_user_sessions(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.user()
+            .logout_all_user_sessions_by_raw_id(user_id)
+            .await
+    }
+
+    async fn enable_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.user().enable_user_by_raw_id(user_id).await
+    }
+
+    async fn update_user_metadata(
+        &self,
+        params: UpdateUserMetadataParams,
+    ) -> Result<(), UpdateUserMetadataError> {
+        self.user().update_user_metadata(params).await
+    }
+
+    async fn update_user_email(
+        &self,
+        params: UpdateUserEmailParams,
+    ) -> Result<(), UpdateUserEmailError> {
+        self.user().update_user_email(params).await
+    }
+
+    async fn update_user_password(
+        &self,
+        params: UpdateUserPasswordParams,
+    ) -> Result<(), UpdatePasswordError> {
+        self.user().update_user_password(params).await
+    }
+
+    async fn clear_user_password(&self, user_id: String) -> Result<(), ClearPasswordError> {
+        self.user().clear_user_password_by_raw_id(user_id).await
+    }
+
+    async fn disable_user_2fa(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.user().disable_user_2fa_by_raw_id(user_id).await
+    }
+
+    async fn resend_email_confirmation(
+        &self,
+        user_id: String,
+    ) -> Result<(), ResendEmailConfirmationError> {
+        self.user()
+            .resend_email_confirmation_by_raw_id(user_id)
+            .await
+    }
+
+    async fn migrate_user(
+        &self,
+        migrate_user_request: MigrateUserRequest,
+    ) -> Result<CreatedUserResponse, MigrateUserError> {
+        self.user().migrate_user(migrate_user_request).await
+    }
+
+    async fn migrate_user_password(
+        &self,
+        migrate_user_password_request: MigrateUserPasswordRequest,
+    ) -> Result<(), MigrateUserPasswordError> {
+        self.user()
+            .migrate_user_password(migrate_user_password_request)
+            .await
+    }
+
+    async fn create_magic_link(
+        &self,
+        create_magic_link_request: CreateMagicLinkRequest,
+    ) -> Result<MagicLink, CreateMagicLinkError> {
+        self.user()
+            .create_magic_link(create_magic_link_request)
+            .await
+    }
+
+    async fn enable_user_can_create_orgs(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.user()
+            .enable_user_can_create_orgs_by_raw_id(user_id)
+            .await
+    }
+
+    async fn disable_user_can_create_orgs(
+        &self,
+        user_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.user()
+            .disable_user_can_create_orgs_by_raw_id(user_id)
+            .await
+    }
+}
+
+#[async_trait]
+impl OrgApi for PropelAuthClient {
+    async fn fetch_org(&self, org_id: String) -> Result<FetchOrgResponse, ErrorsWithNotFound> {
+        self.org().fetch_org_by_raw_id(org_id).await
+    }
+
+    async fn fetch_orgs_by_query(
+        &self,
+        params: FetchOrgsByQueryParams,
+    ) -> Result<FetchOrgsResponse, FetchOrgsByQueryError> {
+        self.org().fetch_orgs_by_query(params).await
+    }
+
+    async fn fetch_users_in_org(
+        &self,
+        params: FetchUsersInOrgParams,
+    ) -> Result<UserPagedResponse, FetchUsersInOrgError> {
+        self.org().fetch_users_in_org(params).await
+    }
+
+    async fn fetch_custom_role_mappings(
+        &self,
+    ) -> Result<crate::models::FetchCustomRoleMappingsResponse, ErrorsWithNotFound> {
+        self.org().fetch_custom_role_mappings().await
+    }
+
+    async fn fetch_pending_invites(
+        &self,
+        params: FetchPendingInvitesParams,
+    ) -> Result<crate::models::FetchPendingInvitesResponse, ErrorsWithNotFound> {
+        self.org().fetch_pending_invites(params).await
+    }
+
+    async fn add_user_to_org(
+        &self,
+        add_user_to_org_request: AddUserToOrgRequest,
+    ) -> Result<(), OrgMissingOrRoleError> {
+        self.org().add_user_to_org(add_user_to_org_request).await
+    }
+
+    async fn change_user_role_in_org(
+        &self,
+        change_user_role_in_org_request: ChangeUserRoleInOrgRequest,
+    ) -> Result<(), OrgMissingOrRoleError> {
+        self.org()
+            .change_user_role_in_org(change_user_role_in_org_request)
+            .await
+    }
+
+    async fn remove_user_from_org(
+        &self,
+        remove_user_from_org_request: RemoveUserFromOrgRequest,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.org()
+            .remove_user_from_org(remove_user_from_org_request)
+            .await
+    }
+
+    async fn create_org(
+        &self,
+        create_org_request: CreateOrgRequest,
+    ) -> Result<CreateOrgResponse, CreateOrgError> {
+        self.org().create_org(create_org_request).await
+    }
+
+    async fn update_org(
+        &self,
+        org_id: String,
+        update_org_request: UpdateOrgRequest,
+    ) -> Result<(), UpdateOrgError> {
+        self.org()
+            .update_org_by_raw_id(org_id, update_org_request)
+            .await
+    }
+
+    async fn subscribe_org_to_role_mapping(
+        &self,
+        org_id: String,
+        custom_role_mapping_name: String,
+    ) -> Result<(), SubscribeOrgToRoleMappingError> {
+        self.org()
+            .subscribe_org_to_role_mapping(org_id, custom_role_mapping_name)
+            .await
+    }
+
+    async fn allow_org_to_enable_saml(
+        &self,
+        params: AllowOrgToEnableSamlParams,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.org().allow_org_to_enable_saml(params).await
+    }
+
+    async fn disallow_saml(&self, params: DisallowSamlParams) -> Result<(), ErrorsWithNotFound> {
+        self.org().disallow_saml(params).await
+    }
+
+    async fn create_saml_connection_link(
+        &self,
+        params: CreateSamlConnectionLinkParams,
+    ) -> Result<CreateSamlConnectionLinkResponse, ErrorsWithNotFound> {
+        self.org().create_saml_connection_link(params).await
+    }
+
+    async fn fetch_saml_sp_metadata(
+        &self,
+        org_id: String,
+    ) -> Result<FetchSamlSpMetadataResponse, ErrorsWithNotFound> {
+        self.org().fetch_saml_sp_metadata(org_id).await
+    }
+
+    async fn set_saml_idp_metadata(
+        &self,
+        request: crate::models::SetSamlIdpMetadataRequest,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.org().set_saml_idp_metadata(request).await
+    }
+
+    async fn saml_go_live(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.org().saml_go_live_by_raw_id(org_id).await
+    }
+
+    async fn delete_saml_connection(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.org().delete_saml_connection_by_raw_id(org_id).await
+    }
+
+    async fn delete_org(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.org().delete_org_by_raw_id(org_id).await
+    }
+
+    async fn invite_user_to_org(
+        &self,
+        invite_user_to_org_request: InviteUserToOrgRequest,
+    ) -> Result<(), InviteUserToOrgError> {
+        self.org()
+            .invite_user_to_org(invite_user_to_org_request)
+            .await
+    }
+
+    async fn revoke_pending_org_invite(
+        &self,
+        revoke_pending_org_invite_request: RevokePendingOrgInviteRequest,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.org()
+            .revoke_pending_org_invite(revoke_pending_org_invite_request)
+            .await
+    }
+}
+
+#[async_trait]
+impl ApiKeyApi for PropelAuthClient {
+    async fn fetch_current_api_keys(
+        &self,
+        params: ApiKeyQueryParams,
+    ) -> Result<FetchApiKeysPagedResponse, ApiKeyError> {
+        self.api_key().fetch_current_api_keys(params).await
+    }
+
+    async fn fetch_archived_api_keys(
+        &self,
+        params: ApiKeyQueryParams,
+    ) -> Result<FetchApiKeysPagedResponse, ApiKeyError> {
+        self.api_key().fetch_archived_api_keys(params).await
+    }
+
+    async fn fetch_api_key(&self, api_key_id: String) -> Result<FetchApiKeyResponse, ApiKeyError> {
+        self.api_key().fetch_api_key_by_raw_id(api_key_id).await
+    }
+
+    async fn create_api_key(
+        &self,
+        params: CreateApiKeyParams,
+    ) -> Result<CreateApiKeyResponse, ApiKeyError> {
+        self.api_key().create_api_key(params).await
+    }
+
+    async fn update_api_key(
+        &self,
+        api_key_id: String,
+        params: UpdateApiKeyParams,
+    ) -> Result<(), ApiKeyError> {
+        self.api_key()
+            .update_api_key_by_raw_id(api_key_id, params)
+            .await
+    }
+
+    async fn delete_api_key(&self, api_key_id: String) -> Result<(), ApiKeyError> {
+        self.api_key().delete_api_key_by_raw_id(api_key_id).await
+    }
+
+    async fn validate_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidateApiKeyResponse, ApiKeyError> {
+        self.api_key().validate_api_key(params).await
+    }
+
+    async fn validate_personal_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidatePersonalApiKeyResponse, ApiKeyError> {
+        self.api_key().validate_personal_api_key(params).await
+    }
+
+    async fn validate_org_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidateOrgApiKeyResponse, ApiKeyError> {
+        self.api_key().validate_org_api_key(params).await
+    }
+}
+
+#[async_trait]
+impl AccessTokenApi for PropelAuthClient {
+    async fn create_access_token(
+        &self,
+        params: CreateAccessTokenParams,
+    ) -> Result<CreateAccessTokenResponse, CreateAccessTokenError> {
+        self.access_token().create_access_token(params).await
+    }
+
+    async fn create_access_token_v2(
+        &self,
+        params: CreateAccessTokenV2Params,
+    ) -> Result<CreateAccessTokenResponse, CreateAccessTokenError> {
+        self.access_token().create_access_token_v2(params).await
+    }
+}
@@ -0,0 +1,280 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard};
+
+use futures_channel::oneshot;
+
+use crate::apis::configuration::Configuration;
+
+type Waiters = Vec<oneshot::Sender<Box<dyn Any + Send>>>;
+
+/// Lets concurrent identical read requests share a single backend call. The first caller for a
+/// given method and set of parameters makes the request, and everyone who asks for the same
+/// thing while it's in flight receives a clone of its result. Nothing is kept once the request
+/// finishes, so this never serves stale data.
+///
+/// Enable it with
+/// [`PropelAuth::with_request_coalescing`](crate::propelauth::auth::PropelAuth::with_request_coalescing).
+#[derive(Default)]
+pub struct RequestCoalescer {
+    in_flight: Mutex<HashMap<String, Waiters>>,
+}
+
+impl fmt::Debug for RequestCoalescer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestCoalescer")
+            .field("in_flight", &self.lock().len())
+            .finish()
+    }
+}
+
+impl RequestCoalescer {
+    pub fn new() -> RequestCoalescer {
+        RequestCoalescer::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Waiters>> {
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Runs `call` unless a call with the same `key` is already in flight, in which case that
+    /// call's result is awaited and cloned instead.
+    pub(crate) async fn run<T, Fut>(&self, key: String, call: Fut) -> T
+    where
+        T: Clone + Send + 'static,
+        Fut: Future<Output = T>,
+    {
+        loop {
+            let receiver = {
+                let mut in_flight = self.lock();
+                match in_flight.get_mut(&key) {
+                    Some(waiters) => {
+                        let (sender, receiver) = oneshot::channel();
+                        waiters.push(sender);
+                        receiver
+                    }
+                    None => {
+                        in_flight.insert(key.clone(), Vec::new());
+                        break;
+                    }
+                }
+            };
+
+            // The receiver errors if the leading call was dropped before it finished, in which
+            // case we try to lead instead
+            if let Ok(result) = receiver.await {
+                if let Ok(result) = result.downcast::<T>() {
+                    return *result;
+                }
+            }
+        }
+
+        let leader = Leader {
+            coalescer: self,
+            key: Some(key),
+        };
+        let result = call.await;
+        for waiter in leader.finish() {
+            let _ = waiter.send(Box::new(result.clone()));
+        }
+        result
+    }
+}
+
+/// Removes the in-flight entry when the leading call finishes or is dropped. Dropping the
+/// waiters' senders without a result wakes them up so one of them can retry.
+struct Leader<'a> {
+    coalescer: &'a RequestCoalescer,
+    key: Option<String>,
+}
+
+impl Leader<'_> {
+    fn finish(mut self) -> Waiters {
+        self.take_waiters()
+    }
+
+    fn take_waiters(&mut self) -> Waiters {
+        match self.key.take() {
+            Some(key) => self.coalescer.lock().remove(&key).unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        self.take_waiters();
+    }
+}
+
+/// The key identifying a read request, or `None` if coalescing isn't enabled
+pub(crate) fn coalescing_key<P: fmt::Debug + ?Sized>(
+    config: &Configuration,
+    method: &str,
+    params: &P,
+) -> Option<String> {
+    config
+        .request_coalescer
+        .as_ref()
+        .map(|_| format!("{}:{:?}", method, params))
+}
+
+/// Runs `call`, sharing it with identical concurrent calls if there's a `key`
+pub(crate) async fn coalesced<T, Fut>(config: &Configuration, key: Option<String>, call: Fut) -> T
+where
+    T: Clone + Send + 'static,
+    Fut: Future<Output = T>,
+{
+    match (config.request_coalescer.as_deref(), key) {
+        (Some(coalescer), Some(key)) => coalescer.run(key, call).await,
+        _ => call.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use futures_channel::oneshot;
+    use futures_util::future::{join, join3};
+
+    use crate::propelauth::coalesce::RequestCoalescer;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn identical_concurrent_calls_share_one_result() {
+        let coalescer = RequestCoalescer::new();
+        let calls = Cell::new(0);
+        let (release, released) = oneshot::channel::<()>();
+
+        let call = |value: &'static str| {
+            let calls = &calls;
+            async move {
+                calls.set(calls.get() + 1);
+                value.to_string()
+            }
+        };
+        let leader = async {
+            let result = coalescer
+                .run("a".to_string(), async {
+                    released.await.unwrap();
+                    call("first").await
+                })
+                .await;
+            assert!(coalescer.lock().is_empty());
+            result
+        };
+        let follower = coalescer.run("a".to_string(), call("second"));
+        let other_key = async {
+            let result = coalescer.run("b".to_string(), call("other")).await;
+            release.send(()).unwrap();
+            result
+        };
+
+        let results = block_on(join3(leader, follower, other_key));
+        assert_eq!(
+            (
+                "first".to_string(),
+                "first".to_string(),
+                "other".to_string()
+            ),
+            results
+        );
+        assert_eq!(2, calls.get());
+    }
+
+    #[test]
+    fn waiters_take_over_when_the_leader_is_dropped() {
+        let coalescer = RequestCoalescer::new();
+        let (_never_sent, never) = oneshot::channel::<()>();
+
+        let result = block_on(async {
+            let mut leader = Box::pin(coalescer.run("a".to_string(), async {
+                never.await.unwrap();
+                1
+            }));
+            // Start the leading call, then abandon it
+            assert!(futures_util::poll!(leader.as_mut()).is_pending());
+            let follower = coalescer.run("a".to_string(), async { 2 });
+            join(follower, async { drop(leader) }).await.0
+        });
+        assert_eq!(2, result);
+        assert!(coalescer.lock().is_empty());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn services_share_in_flight_reads() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        use futures_util::future::join_all;
+        use reqwest::Request;
+
+        use crate::apis::interceptor::Interceptor;
+        use crate::apis::user_service_api::FetchUserByIdParams;
+        use crate::models::CreateUserRequest;
+        use crate::testing::FakeBackend;
+
+        struct CountRequests(Arc<AtomicUsize>);
+        impl Interceptor for CountRequests {
+            fn before_send(&self, _request: &mut Request) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let fake = FakeBackend::start();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let auth = fake
+            .auth()
+            .with_interceptor(CountRequests(requests.clone()))
+            .with_request_coalescing();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            requests.store(0, Ordering::SeqCst);
+
+            let users = auth.user();
+            let fetches = (0..10).map(|_| {
+                users.fetch_user_by_id(FetchUserByIdParams {
+                    user_id: user_id.clone(),
+                    include_orgs: None,
+                })
+            });
+            for user in join_all(fetches).await {
+                assert_eq!("user@example.com", user.unwrap().email);
+            }
+            assert_eq!(1, requests.load(Ordering::SeqCst));
+
+            // Nothing is cached once the call has finished
+            auth.user()
+                .fetch_user_by_id(FetchUserByIdParams {
+                    user_id,
+                    include_orgs: Some(true),
+                })
+                .await
+                .unwrap();
+            assert_eq!(2, requests.load(Ordering::SeqCst));
+        });
+    }
+}
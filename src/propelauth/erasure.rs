@@ -0,0 +1,418 @@
+//! Erases a user and everything attached to them, one step at a time, so that an interrupted
+//! erasure can simply be run again.
+
+use futures_util::stream::TryStreamExt;
+
+use crate::apis::api_key_service_api::{ApiKeyError, ApiKeyQueryParams};
+use crate::apis::user_service_api::FetchUserByIdParams;
+use crate::models::RemoveUserFromOrgRequest;
+use crate::propelauth::api_key::ApiKeyService;
+use crate::propelauth::errors::ErrorsWithNotFound;
+use crate::propelauth::ids::UserId;
+use crate::propelauth::org::OrgService;
+use crate::propelauth::pagination::PaginationOptions;
+use crate::propelauth::user::UserService;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErasureStep {
+    DeleteApiKeys,
+    RemoveFromOrgs,
+    LogoutAllSessions,
+    DeleteUser,
+    /// Checks that the user can no longer be fetched and has no API keys left
+    Verify,
+}
+
+impl ErasureStep {
+    pub const ALL: [ErasureStep; 5] = [
+        ErasureStep::DeleteApiKeys,
+        ErasureStep::RemoveFromOrgs,
+        ErasureStep::LogoutAllSessions,
+        ErasureStep::DeleteUser,
+        ErasureStep::Verify,
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErasureStepStatus {
+    Done,
+    /// There was nothing left to do, for example because an earlier run already did it
+    AlreadyDone,
+    Failed(String),
+    /// Skipped because an earlier step failed
+    NotAttempted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErasureStepResult {
+    pub step: ErasureStep,
+    pub status: ErasureStepStatus,
+    /// The IDs of the API keys or orgs this step acted on
+    pub affected_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErasureReport {
+    pub user_id: String,
+    pub steps: Vec<ErasureStepResult>,
+}
+
+impl ErasureReport {
+    /// Whether every step succeeded, including the final verification
+    pub fn is_complete(&self) -> bool {
+        self.steps.iter().all(|step| {
+            matches!(
+                step.status,
+                ErasureStepStatus::Done | ErasureStepStatus::AlreadyDone
+            )
+        })
+    }
+}
+
+type StepOutcome = Result<(ErasureStepStatus, Vec<String>), String>;
+
+fn api_key_error(err: ApiKeyError) -> String {
+    match err {
+        ApiKeyError::InvalidIntegrationAPIKey => "Invalid API Key".to_string(),
+        ApiKeyError::PropelAuthRateLimit => "Rate limited by PropelAuth".to_string(),
+        ApiKeyError::NotFound => "Not found".to_string(),
+        err => format!("{:?}", err),
+    }
+}
+
+impl<'a> UserService<'a> {
+    /// Deletes the user's API keys, removes them from their orgs, logs them out everywhere,
+    /// deletes them, and then verifies that nothing is left.
+    ///
+    /// Steps run in that order and stop at the first failure. Each step treats already-missing
+    /// data as done, so running this again after a failure picks up where it left off. Check
+    /// [`ErasureReport::is_complete`] to know whether the user was fully erased.
+    pub async fn erase_user(&self, user_id: UserId) -> ErasureReport {
+        let mut report = ErasureReport {
+            user_id: user_id.to_string(),
+            steps: vec![],
+        };
+        let mut failed = false;
+        for step in ErasureStep::ALL {
+            let (status, affected_ids) = if failed {
+                (ErasureStepStatus::NotAttempted, vec![])
+            } else {
+                match self.run_erasure_step(step, user_id).await {
+                    Ok(outcome) => outcome,
+                    Err(err) => {
+                        failed = true;
+                        (ErasureStepStatus::Failed(err), vec![])
+                    }
+                }
+            };
+            report.steps.push(ErasureStepResult {
+                step,
+                status,
+                affected_ids,
+            });
+        }
+        report
+    }
+
+    async fn run_erasure_step(&self, step: ErasureStep, user_id: UserId) -> StepOutcome {
+        match step {
+            ErasureStep::DeleteApiKeys => self.delete_all_api_keys(&user_id.to_string()).await,
+            ErasureStep::RemoveFromOrgs => self.remove_from_all_orgs(&user_id.to_string()).await,
+            ErasureStep::LogoutAllSessions => {
+                erasure_status(self.logout_all_user_sessions_by_id(user_id).await)
+            }
+            ErasureStep::DeleteUser => erasure_status(self.delete_user_by_id(user_id).await),
+            ErasureStep::Verify => self.verify_erased(&user_id.to_string()).await,
+        }
+    }
+
+    async fn current_api_key_ids(&self, user_id: &str) -> Result<Vec<String>, String> {
+        let params = ApiKeyQueryParams {
+            user_id: Some(user_id.to_string()),
+            ..Default::default()
+        };
+        ApiKeyService {
+            config: self.config,
+        }
+        .fetch_current_api_keys_stream(params, PaginationOptions::default())
+        .map_ok(|api_key| api_key.api_key_id)
+        .try_collect()
+        .await
+        .map_err(api_key_error)
+    }
+
+    async fn delete_all_api_keys(&self, user_id: &str) -> StepOutcome {
+        // Every key is listed before any are deleted, so deleting doesn't shift the pages
+        let api_key_ids = self.current_api_key_ids(user_id).await?;
+        if api_key_ids.is_empty() {
+            return Ok((ErasureStepStatus::AlreadyDone, vec![]));
+        }
+
+        let api_keys = ApiKeyService {
+            config: self.config,
+        };
+        for api_key_id in &api_key_ids {
+            let result = match api_key_id.parse() {
+                Ok(api_key_id) => api_keys.delete_api_key_by_id(api_key_id).await,
+                Err(_) => Err(ApiKeyError::NotFound),
+            };
+            match result {
+                Ok(()) | Err(ApiKeyError::NotFound) => {}
+                Err(err) => return Err(api_key_error(err)),
+            }
+        }
+        Ok((ErasureStepStatus::Done, api_key_ids))
+    }
+
+    async fn remove_from_all_orgs(&self, user_id: &str) -> StepOutcome {
+        let user = self
+            .fetch_user_by_id_uncached(FetchUserByIdParams {
+                user_id: user_id.to_string(),
+                include_orgs: Some(true),
+            })
+            .await;
+        let mut org_ids: Vec<String> = match user {
+            Ok(user) => user
+                .org_id_to_org_info
+                .unwrap_or_default()
+                .into_keys()
+                .collect(),
+            // The user was deleted by an earlier run, which removed them from every org
+            Err(ErrorsWithNotFound::NotFound) => vec![],
+            Err(err) => return Err(err.to_string()),
+        };
+        if org_ids.is_empty() {
+            return Ok((ErasureStepStatus::AlreadyDone, vec![]));
+        }
+
+        org_ids.sort();
+        let orgs = OrgService {
+            config: self.config,
+        };
+        for org_id in &org_ids {
+            let request = RemoveUserFromOrgRequest {
+                user_id: user_id.to_string(),
+                org_id: org_id.clone(),
+            };
+            match orgs.remove_user_from_org(request).await {
+                Ok(()) | Err(ErrorsWithNotFound::NotFound) => {}
+                Err(err) => return Err(err.to_string()),
+            }
+        }
+        Ok((ErasureStepStatus::Done, org_ids))
+    }
+
+    async fn verify_erased(&self, user_id: &str) -> StepOutcome {
+        let user = self
+            .fetch_user_by_id_uncached(FetchUserByIdParams {
+                user_id: user_id.to_string(),
+                include_orgs: None,
+            })
+            .await;
+        match user {
+            Err(ErrorsWithNotFound::NotFound) => {}
+            Ok(_) => return Err("user can still be fetched".to_string()),
+            Err(err) => return Err(err.to_string()),
+        }
+
+        let api_key_ids = self.current_api_key_ids(user_id).await?;
+        if !api_key_ids.is_empty() {
+            return Err(format!(
+                "user still has API keys: {}",
+                api_key_ids.join(", ")
+            ));
+        }
+        Ok((ErasureStepStatus::Done, vec![]))
+    }
+}
+
+fn erasure_status(result: Result<(), ErrorsWithNotFound>) -> StepOutcome {
+    match result {
+        Ok(()) => Ok((ErasureStepStatus::Done, vec![])),
+        Err(ErrorsWithNotFound::NotFound) => Ok((ErasureStepStatus::AlreadyDone, vec![])),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::propelauth::erasure::{
+        ErasureReport, ErasureStep, ErasureStepResult, ErasureStepStatus,
+    };
+
+    #[test]
+    fn only_complete_when_every_step_succeeded() {
+        let report = |statuses: [ErasureStepStatus; 5]| ErasureReport {
+            user_id: "user".to_string(),
+            steps: ErasureStep::ALL
+                .iter()
+                .copied()
+                .zip(statuses)
+                .map(|(step, status)| ErasureStepResult {
+                    step,
+                    status,
+                    affected_ids: vec![],
+                })
+                .collect(),
+        };
+        use ErasureStepStatus::*;
+
+        assert!(report([Done, AlreadyDone, Done, Done, Done]).is_complete());
+        assert!(!report([
+            Done,
+            Failed("rate limited".to_string()),
+            NotAttempted,
+            NotAttempted,
+            NotAttempted
+        ])
+        .is_complete());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn erases_a_user_and_can_run_again() {
+        use crate::apis::api_key_service_api::CreateApiKeyParams;
+        use crate::models::{AddUserToOrgRequest, CreateOrgRequest, CreateUserRequest};
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            let org_id = auth
+                .org()
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id;
+            auth.org()
+                .add_user_to_org(AddUserToOrgRequest {
+                    user_id: user_id.clone(),
+                    org_id: org_id.clone(),
+                    role: "Member".to_string(),
+                    additional_roles: vec![],
+                })
+                .await
+                .unwrap();
+            let api_key_id = auth
+                .api_key()
+                .create_api_key(CreateApiKeyParams {
+                    user_id: Some(user_id.clone()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .api_key_id;
+
+            fake.rate_limit_next_requests(1);
+            let report = auth.user().erase_user(user_id.parse().unwrap()).await;
+            assert!(!report.is_complete());
+            assert_eq!(
+                ErasureStepStatus::Failed("Rate limited by PropelAuth".to_string()),
+                report.steps[0].status
+            );
+            assert_eq!(ErasureStepStatus::NotAttempted, report.steps[4].status);
+
+            let report = auth.user().erase_user(user_id.parse().unwrap()).await;
+            assert!(report.is_complete(), "{:?}", report);
+            assert_eq!(vec![api_key_id], report.steps[0].affected_ids);
+            assert_eq!(vec![org_id], report.steps[1].affected_ids);
+            assert_eq!(ErasureStepStatus::Done, report.steps[3].status);
+            assert!(fake.users().is_empty());
+
+            let report = auth.user().erase_user(user_id.parse().unwrap()).await;
+            assert!(report.is_complete());
+            let statuses: Vec<_> = report.steps.into_iter().map(|step| step.status).collect();
+            assert_eq!(
+                vec![
+                    ErasureStepStatus::AlreadyDone,
+                    ErasureStepStatus::AlreadyDone,
+                    ErasureStepStatus::AlreadyDone,
+                    ErasureStepStatus::AlreadyDone,
+                    ErasureStepStatus::Done,
+                ],
+                statuses
+            );
+        });
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn asks_propelauth_rather_than_the_cache() {
+        use crate::apis::user_service_api::FetchUserByIdParams;
+        use crate::models::{AddUserToOrgRequest, CreateOrgRequest, CreateUserRequest};
+        use crate::propelauth::cache::CacheOptions;
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let cached_auth = fake.auth().with_cache(CacheOptions::default());
+        let auth = fake.auth();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            let org_id = auth
+                .org()
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id;
+            cached_auth
+                .user()
+                .fetch_user_by_id(FetchUserByIdParams {
+                    user_id: user_id.clone(),
+                    include_orgs: Some(true),
+                })
+                .await
+                .unwrap();
+            // Another instance adds the user to an org, so the cached user doesn't know about it
+            auth.org()
+                .add_user_to_org(AddUserToOrgRequest::new(
+                    user_id.clone(),
+                    org_id.clone(),
+                    "Member".to_string(),
+                ))
+                .await
+                .unwrap();
+
+            let report = cached_auth
+                .user()
+                .erase_user(user_id.parse().unwrap())
+                .await;
+            assert!(report.is_complete(), "{:?}", report);
+            assert_eq!(vec![org_id], report.steps[1].affected_ids);
+        });
+    }
+}
@@ -1,9 +1,12 @@
+#[cfg(feature = "__reqwest")]
+use crate::apis::api_key_service_api::ApiKeyError;
 use crate::models::{
     BadCreateAccessTokenError, BadCreateMagicLinkRequest, BadCreateOrgRequest,
     BadCreateUserRequest, BadFetchOrgQuery, BadFetchUsersByQuery, BadFetchUsersInOrgQuery,
-    BadMigrateUserRequest, BadMigrateUserPasswordRequest, BadUpdateOrgRequest, BadUpdatePasswordRequest,
-    BadUpdateUserEmailRequest, BadUpdateUserMetadataRequest,
+    BadMigrateUserPasswordRequest, BadMigrateUserRequest, BadUpdateOrgRequest,
+    BadUpdatePasswordRequest, BadUpdateUserEmailRequest, BadUpdateUserMetadataRequest,
 };
+use crate::propelauth::password_hash::PasswordHashAlgorithm;
 use thiserror::Error;
 
 #[derive(Error, Debug, Eq, PartialEq, Copy, Clone)]
@@ -21,6 +24,18 @@ pub enum InitializationError {
     UnexpectedException,
 }
 
+#[derive(Error, Debug, Eq, PartialEq, Copy, Clone)]
+pub enum InvalidIdError {
+    #[error("Invalid user ID")]
+    UserId,
+
+    #[error("Invalid org ID")]
+    OrgId,
+
+    #[error("Invalid API key ID")]
+    ApiKeyId,
+}
+
 #[derive(Error, Debug, Eq, PartialEq, Copy, Clone)]
 pub enum ErrorsWithNotFound {
     #[error("Invalid API Key")]
@@ -183,6 +198,91 @@ pub enum MigrateUserError {
     UnexpectedException,
 }
 
+#[derive(Error, Debug)]
+pub enum ImportUsersError {
+    #[error("Invalid API Key")]
+    InvalidApiKey,
+
+    #[error("Unable to read the input or write the report: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(feature = "__reqwest")]
+#[derive(Error, Debug, Clone)]
+pub enum ExportUserDataError {
+    #[error("Unable to fetch the user: {0}")]
+    User(ErrorsWithNotFound),
+
+    #[error("Unable to fetch the user's API keys: {0:?}")]
+    ApiKeys(ApiKeyError),
+
+    #[error("Unable to fetch pending invites: {0}")]
+    Invites(ErrorsWithNotFound),
+}
+
+#[derive(Error, Debug)]
+pub enum ExportUsersError {
+    #[error("Unable to fetch users: {0}")]
+    Fetch(#[from] FetchByQueryError),
+
+    #[error("Unable to write the export: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum MetadataMigrationError {
+    #[error("Unable to fetch users: {0}")]
+    FetchUsers(FetchByQueryError),
+
+    #[error("Unable to fetch orgs: {0}")]
+    FetchOrgs(FetchOrgsByQueryError),
+
+    #[error("Unable to read or write the progress file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ModifyMetadataError {
+    #[error("Invalid API Key")]
+    InvalidApiKey,
+
+    #[error("Rate limited by PropelAuth")]
+    PropelAuthRateLimit,
+
+    #[error("Bad request")]
+    BadRequest(BadUpdateUserMetadataRequest),
+
+    #[error("Not found")]
+    NotFound,
+
+    #[error("Unable to convert between the stored data and the requested type: {0}")]
+    Serialization(String),
+
+    #[error("The stored data kept changing while it was being modified")]
+    Conflict,
+
+    #[error("Unexpected exception, please try again")]
+    UnexpectedException,
+}
+
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+pub enum SuspensionError {
+    #[error("Invalid API Key")]
+    InvalidApiKey,
+
+    #[error("Rate limited by PropelAuth")]
+    PropelAuthRateLimit,
+
+    #[error("Not found")]
+    NotFound,
+
+    #[error("User is not suspended")]
+    NotSuspended,
+
+    #[error("Unexpected exception, please try again")]
+    UnexpectedException,
+}
+
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum MigrateUserPasswordError {
     #[error("Invalid API Key")]
@@ -198,6 +298,18 @@ pub enum MigrateUserPasswordError {
     UnexpectedException,
 }
 
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+pub enum InvalidPasswordHashError {
+    #[error("Unrecognized password hash format")]
+    UnrecognizedFormat,
+
+    #[error("Malformed {algorithm} hash: {reason}")]
+    Malformed {
+        algorithm: PasswordHashAlgorithm,
+        reason: String,
+    },
+}
+
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum CreateMagicLinkError {
     #[error("Invalid API Key")]
@@ -414,3 +526,34 @@ impl From<UnauthorizedError> for UnauthorizedOrForbiddenError {
         }
     }
 }
+
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+pub enum ResolveIdError {
+    #[error("Invalid API Key")]
+    InvalidApiKey,
+
+    #[error("Rate limited by PropelAuth")]
+    PropelAuthRateLimit,
+
+    #[error("Not found")]
+    NotFound,
+
+    /// More than one user or org has the legacy ID
+    #[error("Legacy ID matches more than one record: {}", .0.join(", "))]
+    Ambiguous(Vec<String>),
+
+    #[error("Unexpected exception, please try again")]
+    UnexpectedException,
+}
+
+/// An error from an operation run on a user or org given by an
+/// [`IdOrLegacy`](crate::propelauth::legacy_id::IdOrLegacy)
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+pub enum ResolvedIdError<E> {
+    /// The ID couldn't be resolved, so the operation wasn't run
+    #[error(transparent)]
+    Resolve(#[from] ResolveIdError),
+
+    #[error(transparent)]
+    Operation(E),
+}
@@ -0,0 +1,144 @@
+//! Typed IDs, so that an org ID can't be passed where a user ID is expected. Each one wraps a
+//! [`Uuid`], parses from and formats to the string PropelAuth uses, and (de)serializes as that
+//! string.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+use crate::propelauth::errors::InvalidIdError;
+
+macro_rules! id_type {
+    ($(#[$attr:meta])* $name:ident, $error:expr, $format:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(Uuid);
+
+        impl $name {
+            pub fn new(id: Uuid) -> Self {
+                $name(id)
+            }
+
+            pub fn as_uuid(&self) -> &Uuid {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0.$format(), f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = InvalidIdError;
+
+            fn from_str(id: &str) -> Result<Self, Self::Err> {
+                Uuid::parse_str(id).map($name).map_err(|_| $error)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = InvalidIdError;
+
+            fn try_from(id: &str) -> Result<Self, Self::Error> {
+                id.parse()
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = InvalidIdError;
+
+            fn try_from(id: String) -> Result<Self, Self::Error> {
+                id.parse()
+            }
+        }
+
+        impl From<Uuid> for $name {
+            fn from(id: Uuid) -> Self {
+                $name(id)
+            }
+        }
+
+        impl From<$name> for Uuid {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.to_string()
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let id = String::deserialize(deserializer)?;
+                id.parse().map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+id_type!(
+    /// A user's ID, formatted like `e4b4a1e4-2c7b-4a62-9a83-3f3bdb0f1bd6`
+    UserId,
+    InvalidIdError::UserId,
+    hyphenated
+);
+
+id_type!(
+    /// An org's ID, formatted like `e4b4a1e4-2c7b-4a62-9a83-3f3bdb0f1bd6`
+    OrgId,
+    InvalidIdError::OrgId,
+    hyphenated
+);
+
+id_type!(
+    /// An API key's ID, formatted without hyphens like `e4b4a1e42c7b4a629a833f3bdb0f1bd6`
+    ApiKeyId,
+    InvalidIdError::ApiKeyId,
+    simple
+);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::propelauth::errors::InvalidIdError;
+    use crate::propelauth::ids::{ApiKeyId, OrgId, UserId};
+
+    #[test]
+    fn round_trips_through_strings_and_serde() {
+        let user_id: UserId = "E4B4A1E4-2C7B-4A62-9A83-3F3BDB0F1BD6".parse().unwrap();
+        assert_eq!("e4b4a1e4-2c7b-4a62-9a83-3f3bdb0f1bd6", user_id.to_string());
+        assert_eq!(
+            json!("e4b4a1e4-2c7b-4a62-9a83-3f3bdb0f1bd6"),
+            serde_json::to_value(user_id).unwrap()
+        );
+        assert_eq!(
+            user_id,
+            serde_json::from_value(json!("e4b4a1e4-2c7b-4a62-9a83-3f3bdb0f1bd6")).unwrap()
+        );
+
+        let api_key_id: ApiKeyId = "e4b4a1e42c7b4a629a833f3bdb0f1bd6".parse().unwrap();
+        assert_eq!("e4b4a1e42c7b4a629a833f3bdb0f1bd6", api_key_id.to_string());
+        assert_eq!(api_key_id.as_uuid(), user_id.as_uuid());
+    }
+
+    #[test]
+    fn rejects_ids_that_are_not_uuids() {
+        assert_eq!(Err(InvalidIdError::OrgId), "acme".parse::<OrgId>());
+        assert!(serde_json::from_value::<UserId>(json!("1234")).is_err());
+    }
+}
@@ -0,0 +1,1174 @@
+//! Bulk import of users from a legacy system, built on
+//! [`UserService::migrate_user`](crate::propelauth::user::UserService::migrate_user) and
+//! [`OrgService::add_user_to_org`](crate::propelauth::org::OrgService::add_user_to_org).
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::{self, BufRead, BufReader, Lines, Read, Write};
+use std::iter::Enumerate;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use futures_util::stream::{self, StreamExt};
+use serde_json::Value;
+
+use crate::apis::user_service_api::FetchUserByEmailParams;
+use crate::models::{AddUserToOrgRequest, MigrateUserRequest, UserMetadata};
+use crate::propelauth::errors::{
+    ErrorsWithNotFound, ImportUsersError, MigrateUserError, OrgMissingOrRoleError,
+};
+use crate::propelauth::helpers::is_valid_id;
+use crate::propelauth::org::OrgService;
+use crate::propelauth::user::UserService;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// A header row followed by one user per record. `properties` and `orgs` are JSON-encoded
+    /// columns, and empty cells are treated as missing.
+    Csv,
+    /// One JSON-encoded [`ImportUserRow`] per line
+    Jsonl,
+}
+
+/// A single user to import
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ImportUserRow {
+    pub email: String,
+    /// Defaults to false
+    #[serde(default)]
+    pub email_confirmed: bool,
+    #[serde(default)]
+    pub first_name: Option<String>,
+    #[serde(default)]
+    pub last_name: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub picture_url: Option<String>,
+    /// The user's ID in the legacy system
+    #[serde(default)]
+    pub legacy_user_id: Option<String>,
+    #[serde(default)]
+    pub password_hash: Option<String>,
+    /// A base32 encoded TOTP secret
+    #[serde(default)]
+    pub mfa_secret: Option<String>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub update_password_required: Option<bool>,
+    #[serde(default)]
+    pub properties: Option<HashMap<String, Value>>,
+    #[serde(default)]
+    pub orgs: Vec<ImportOrgMembership>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ImportOrgMembership {
+    pub org_id: String,
+    pub role: String,
+    #[serde(default)]
+    pub additional_roles: Vec<String>,
+}
+
+/// The same columns as [`ImportUserRow`], with the nested fields still JSON-encoded
+#[derive(Deserialize)]
+struct CsvImportRow {
+    email: String,
+    #[serde(default)]
+    email_confirmed: Option<bool>,
+    #[serde(default)]
+    first_name: Option<String>,
+    #[serde(default)]
+    last_name: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    picture_url: Option<String>,
+    #[serde(default)]
+    legacy_user_id: Option<String>,
+    #[serde(default)]
+    password_hash: Option<String>,
+    #[serde(default)]
+    mfa_secret: Option<String>,
+    #[serde(default)]
+    enabled: Option<bool>,
+    #[serde(default)]
+    update_password_required: Option<bool>,
+    #[serde(default)]
+    properties: Option<String>,
+    #[serde(default)]
+    orgs: Option<String>,
+}
+
+impl CsvImportRow {
+    fn into_row(self) -> Result<ImportUserRow, String> {
+        let properties = match self.properties {
+            Some(properties) => Some(
+                serde_json::from_str(&properties)
+                    .map_err(|_| "properties must be a JSON object".to_string())?,
+            ),
+            None => None,
+        };
+        let orgs = match self.orgs {
+            Some(orgs) => serde_json::from_str(&orgs).map_err(|_| {
+                "orgs must be a JSON array of {\"org_id\", \"role\"} objects".to_string()
+            })?,
+            None => vec![],
+        };
+
+        Ok(ImportUserRow {
+            email: self.email,
+            email_confirmed: self.email_confirmed.unwrap_or(false),
+            first_name: self.first_name,
+            last_name: self.last_name,
+            username: self.username,
+            picture_url: self.picture_url,
+            legacy_user_id: self.legacy_user_id,
+            password_hash: self.password_hash,
+            mfa_secret: self.mfa_secret,
+            enabled: self.enabled,
+            update_password_required: self.update_password_required,
+            properties,
+            orgs,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// How many users are imported at once. Defaults to 4.
+    pub concurrency: usize,
+    /// Caps the requests sent to PropelAuth across all workers. Defaults to 10.
+    pub max_requests_per_second: Option<u32>,
+    /// How many times a request is retried after PropelAuth rate limits it. Defaults to 3.
+    pub max_retries: u32,
+    /// How long to wait before the first retry, doubling after each one. Defaults to 1 second.
+    pub retry_delay: Duration,
+    /// A file which gets one JSON-encoded [`ImportRowResult`] appended per row as it finishes.
+    /// It's also the checkpoint: when an import is run again with the same file, rows whose
+    /// email it records as imported or rejected are skipped, and partially imported rows only
+    /// retry their missing orgs.
+    pub report_path: Option<PathBuf>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        ImportOptions {
+            concurrency: 4,
+            max_requests_per_second: Some(10),
+            max_retries: 3,
+            retry_delay: Duration::from_secs(1),
+            report_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRowStatus {
+    Imported,
+    /// The user was created, but adding them to some of their orgs failed
+    PartiallyImported,
+    /// The row failed local validation, so nothing was sent
+    Invalid,
+    /// PropelAuth refused to create the user, e.g. because the email is already in use
+    Rejected,
+    /// Creating the user failed in a way that may succeed on another run
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportRowResult {
+    /// The line number for JSONL, or the record number (not counting the header) for CSV
+    pub row: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub status: ImportRowStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// Orgs the user still needs to be added to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_org_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+impl ImportRowResult {
+    fn new(row: usize, email: Option<String>, status: ImportRowStatus) -> ImportRowResult {
+        ImportRowResult {
+            row,
+            email,
+            status,
+            user_id: None,
+            failed_org_ids: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+/// How many rows ended up in each state. Rows skipped because an earlier run already
+/// finished them are only counted in `skipped`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub partially_imported: usize,
+    pub invalid: usize,
+    pub rejected: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+enum ImportJob {
+    Migrate(ImportUserRow),
+    AddToOrgs {
+        email: String,
+        user_id: String,
+        orgs: Vec<ImportOrgMembership>,
+    },
+}
+
+enum ImportStep {
+    /// The row was finished locally, without sending anything
+    Done(ImportRowResult),
+    /// An earlier run already finished the row
+    Skipped,
+    Run(usize, ImportJob),
+}
+
+/// Decides what to do with each row as it's read
+struct ImportPlanner {
+    /// Keyed by [`email_key`]
+    previous_results: HashMap<String, ImportRowResult>,
+    first_row_by_email: HashMap<String, usize>,
+}
+
+impl ImportPlanner {
+    fn plan(&mut self, row: usize, parsed: Result<ImportUserRow, String>) -> ImportStep {
+        let user = match parsed {
+            Ok(user) => user,
+            Err(error) => {
+                let mut result = ImportRowResult::new(row, None, ImportRowStatus::Invalid);
+                result.errors.push(error);
+                return ImportStep::Done(result);
+            }
+        };
+
+        let mut errors = validate_row(&user);
+        let email = email_key(&user.email);
+        if let Some(first_row) = self.first_row_by_email.get(&email) {
+            errors.push(format!("email is a duplicate of row {}", first_row));
+        } else {
+            self.first_row_by_email.insert(email.clone(), row);
+        }
+        if !errors.is_empty() {
+            let mut result = ImportRowResult::new(row, Some(user.email), ImportRowStatus::Invalid);
+            result.errors = errors;
+            return ImportStep::Done(result);
+        }
+
+        match self.previous_results.get(&email) {
+            Some(previous)
+                if previous.status == ImportRowStatus::Imported
+                    || previous.status == ImportRowStatus::Rejected =>
+            {
+                ImportStep::Skipped
+            }
+            Some(ImportRowResult {
+                status: ImportRowStatus::PartiallyImported,
+                user_id: Some(user_id),
+                failed_org_ids,
+                ..
+            }) => {
+                let orgs = user
+                    .orgs
+                    .into_iter()
+                    .filter(|membership| failed_org_ids.contains(&membership.org_id))
+                    .collect();
+                ImportStep::Run(
+                    row,
+                    ImportJob::AddToOrgs {
+                        email: user.email,
+                        user_id: user_id.clone(),
+                        orgs,
+                    },
+                )
+            }
+            _ => ImportStep::Run(row, ImportJob::Migrate(user)),
+        }
+    }
+}
+
+impl<'a> UserService<'a> {
+    /// Imports users from a CSV or JSONL export of a legacy system. Each row is validated
+    /// locally, then created with `migrate_user` and added to its orgs with `add_user_to_org`,
+    /// with up to `options.concurrency` rows in flight at once. Rows are read as they're
+    /// needed, so the input is never held in memory as a whole.
+    ///
+    /// A problem with one row never stops the import; see the returned summary and the
+    /// report for what happened to each row. The import is only aborted if the input or report
+    /// can't be read or written, or if PropelAuth rejects the API key.
+    ///
+    /// If PropelAuth says a row's email or legacy ID is already in use, and there is a user
+    /// with that email whose legacy ID matches the row's, the row is treated as already
+    /// created and the import carries on adding them to the orgs they aren't in yet. That way
+    /// an import that was interrupted after creating a user, but before recording it in the
+    /// report, can safely be run again.
+    pub async fn import_users<R: Read>(
+        &self,
+        input: R,
+        format: ImportFormat,
+        options: ImportOptions,
+    ) -> Result<ImportSummary, ImportUsersError> {
+        let (previous_results, mut report) = match &options.report_path {
+            Some(path) => {
+                let (previous_results, report) = open_report(path)?;
+                (previous_results, Some(report))
+            }
+            None => (HashMap::new(), None),
+        };
+
+        let mut planner = ImportPlanner {
+            previous_results,
+            first_row_by_email: HashMap::new(),
+        };
+        let mut summary = ImportSummary::default();
+        let rate_limiter = RateLimiter::new(options.max_requests_per_second);
+        let mut results = stream::iter(RowReader::new(input, format))
+            .map(|parsed| {
+                let step = parsed.map(|(row, parsed)| planner.plan(row, parsed));
+                self.run_import_step(step, &rate_limiter, &options)
+            })
+            .buffer_unordered(options.concurrency.max(1));
+        while let Some(outcome) = results.next().await {
+            match outcome? {
+                Some((result, invalid_api_key)) => {
+                    record(&mut summary, &mut report, &result)?;
+                    if invalid_api_key {
+                        return Err(ImportUsersError::InvalidApiKey);
+                    }
+                }
+                None => summary.skipped += 1,
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Returns the row's result and whether PropelAuth rejected the API key, or `None` if the
+    /// row was skipped
+    async fn run_import_step(
+        &self,
+        step: Result<ImportStep, ImportUsersError>,
+        rate_limiter: &RateLimiter,
+        options: &ImportOptions,
+    ) -> Result<Option<(ImportRowResult, bool)>, ImportUsersError> {
+        match step? {
+            ImportStep::Done(result) => Ok(Some((result, false))),
+            ImportStep::Skipped => Ok(None),
+            ImportStep::Run(row, job) => Ok(Some(
+                self.run_import_job(row, job, rate_limiter, options).await,
+            )),
+        }
+    }
+
+    /// Returns the row's result, and whether PropelAuth rejected the API key
+    async fn run_import_job(
+        &self,
+        row: usize,
+        job: ImportJob,
+        rate_limiter: &RateLimiter,
+        options: &ImportOptions,
+    ) -> (ImportRowResult, bool) {
+        let (email, user_id, orgs) = match job {
+            ImportJob::Migrate(user) => {
+                let email = user.email.clone();
+                let orgs = user.orgs.clone();
+                let request = to_migrate_user_request(user);
+                let migrated = with_retries(
+                    rate_limiter,
+                    options,
+                    |err| *err == MigrateUserError::PropelAuthRateLimit,
+                    || self.migrate_user(request.clone()),
+                )
+                .await;
+
+                match migrated {
+                    Ok(created) => (email, created.user_id, orgs),
+                    Err(err) if is_already_in_use(&err) => {
+                        match self
+                            .find_imported_user(&request, rate_limiter, options)
+                            .await
+                        {
+                            Ok(Some(existing)) => {
+                                let joined = existing.org_id_to_org_info.unwrap_or_default();
+                                let orgs = orgs
+                                    .into_iter()
+                                    .filter(|membership| !joined.contains_key(&membership.org_id))
+                                    .collect();
+                                (email, existing.user_id, orgs)
+                            }
+                            Ok(None) => return migrate_failed(row, email, err),
+                            Err(lookup_err) => {
+                                let mut result =
+                                    ImportRowResult::new(row, Some(email), ImportRowStatus::Failed);
+                                result.errors.push(lookup_err.to_string());
+                                return (result, lookup_err == ErrorsWithNotFound::InvalidApiKey);
+                            }
+                        }
+                    }
+                    Err(err) => return migrate_failed(row, email, err),
+                }
+            }
+            ImportJob::AddToOrgs {
+                email,
+                user_id,
+                orgs,
+            } => (email, user_id, orgs),
+        };
+        self.add_to_orgs(row, email, user_id, orgs, rate_limiter, options)
+            .await
+    }
+
+    /// Looks up the user a previous run created for `request`, if there is one. Without a legacy
+    /// ID there's no telling that user apart from someone who already had the email.
+    async fn find_imported_user(
+        &self,
+        request: &MigrateUserRequest,
+        rate_limiter: &RateLimiter,
+        options: &ImportOptions,
+    ) -> Result<Option<UserMetadata>, ErrorsWithNotFound> {
+        if request.existing_user_id.is_none() {
+            return Ok(None);
+        }
+
+        let params = FetchUserByEmailParams {
+            email: request.email.clone(),
+            include_orgs: Some(true),
+        };
+        let found = with_retries(
+            rate_limiter,
+            options,
+            |err| *err == ErrorsWithNotFound::PropelAuthRateLimit,
+            || self.fetch_user_by_email(params.clone()),
+        )
+        .await;
+        match found {
+            Ok(user) if user.legacy_user_id == request.existing_user_id => Ok(Some(user)),
+            Ok(_) | Err(ErrorsWithNotFound::NotFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the row's result, and whether PropelAuth rejected the API key
+    async fn add_to_orgs(
+        &self,
+        row: usize,
+        email: String,
+        user_id: String,
+        orgs: Vec<ImportOrgMembership>,
+        rate_limiter: &RateLimiter,
+        options: &ImportOptions,
+    ) -> (ImportRowResult, bool) {
+        let org_service = OrgService {
+            config: self.config,
+        };
+        let mut result = ImportRowResult::new(row, Some(email), ImportRowStatus::Imported);
+        let mut invalid_api_key = false;
+        for membership in orgs {
+            if invalid_api_key {
+                result.failed_org_ids.push(membership.org_id);
+                continue;
+            }
+
+            let request = AddUserToOrgRequest {
+                user_id: user_id.clone(),
+                org_id: membership.org_id.clone(),
+                role: membership.role,
+                additional_roles: membership.additional_roles,
+            };
+            let added = with_retries(
+                rate_limiter,
+                options,
+                |err| *err == OrgMissingOrRoleError::PropelAuthRateLimit,
+                || org_service.add_user_to_org(request.clone()),
+            )
+            .await;
+            if let Err(err) = added {
+                invalid_api_key = err == OrgMissingOrRoleError::InvalidApiKey;
+                result.errors.push(format!(
+                    "Unable to add to org {}: {}",
+                    membership.org_id, err
+                ));
+                result.failed_org_ids.push(membership.org_id);
+            }
+        }
+
+        if !result.failed_org_ids.is_empty() {
+            result.status = ImportRowStatus::PartiallyImported;
+        }
+        result.user_id = Some(user_id);
+        (result, invalid_api_key)
+    }
+}
+
+/// Returns the result for a row `migrate_user` failed on, and whether PropelAuth rejected the
+/// API key
+fn migrate_failed(row: usize, email: String, err: MigrateUserError) -> (ImportRowResult, bool) {
+    let status = match err {
+        MigrateUserError::BadRequest(_) => ImportRowStatus::Rejected,
+        _ => ImportRowStatus::Failed,
+    };
+    let mut result = ImportRowResult::new(row, Some(email), status);
+    result.errors.push(match &err {
+        MigrateUserError::BadRequest(bad_request) => {
+            serde_json::to_string(bad_request).unwrap_or_default()
+        }
+        _ => err.to_string(),
+    });
+    (result, err == MigrateUserError::InvalidApiKey)
+}
+
+fn is_already_in_use(err: &MigrateUserError) -> bool {
+    match err {
+        MigrateUserError::BadRequest(bad_request) => {
+            bad_request.email.is_some() || bad_request.existing_user_id.is_some()
+        }
+        _ => false,
+    }
+}
+
+/// Rows and report entries are matched up by email, since it's required and unique
+fn email_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+fn to_migrate_user_request(user: ImportUserRow) -> MigrateUserRequest {
+    MigrateUserRequest {
+        email: user.email.trim().to_string(),
+        email_confirmed: user.email_confirmed,
+        existing_user_id: non_empty(user.legacy_user_id),
+        existing_password_hash: non_empty(user.password_hash),
+        existing_mfa_base32_encoded_secret: non_empty(user.mfa_secret)
+            .map(|secret| secret.replace(' ', "").to_uppercase()),
+        enabled: user.enabled,
+        username: non_empty(user.username),
+        first_name: non_empty(user.first_name),
+        last_name: non_empty(user.last_name),
+        picture_url: non_empty(user.picture_url),
+        properties: user.properties,
+        update_password_required: user.update_password_required,
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+fn validate_row(user: &ImportUserRow) -> Vec<String> {
+    let mut errors = vec![];
+
+    let email = user.email.trim();
+    if email.is_empty() {
+        errors.push("email is required".to_string());
+    } else if !is_plausible_email(email) {
+        errors.push("email is not a valid email address".to_string());
+    }
+
+    if let Some(secret) = non_empty(user.mfa_secret.clone()) {
+        if !is_base32(&secret) {
+            errors.push("mfa_secret must be base32 encoded".to_string());
+        }
+    }
+
+    let mut org_ids = HashSet::new();
+    for membership in &user.orgs {
+        if !is_valid_id(&membership.org_id) {
+            errors.push(format!("{} is not a valid org ID", membership.org_id));
+        }
+        if membership.role.trim().is_empty() {
+            errors.push(format!("org {} is missing a role", membership.org_id));
+        }
+        if !org_ids.insert(&membership.org_id) {
+            errors.push(format!(
+                "org {} is listed more than once",
+                membership.org_id
+            ));
+        }
+    }
+
+    errors
+}
+
+fn is_plausible_email(email: &str) -> bool {
+    match email.rsplit_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+fn is_base32(secret: &str) -> bool {
+    let secret = secret.replace(' ', "");
+    let secret = secret.trim_end_matches('=');
+    !secret.is_empty()
+        && secret
+            .chars()
+            .all(|c| c.is_ascii_alphabetic() || ('2'..='7').contains(&c))
+}
+
+type ParsedRow = (usize, Result<ImportUserRow, String>);
+
+/// Reads one row at a time, numbered as described on [`ImportRowResult::row`]
+enum RowReader<R: Read> {
+    Csv(Enumerate<csv::DeserializeRecordsIntoIter<R, CsvImportRow>>),
+    Jsonl(Enumerate<Lines<BufReader<R>>>),
+}
+
+impl<R: Read> RowReader<R> {
+    fn new(input: R, format: ImportFormat) -> RowReader<R> {
+        match format {
+            ImportFormat::Csv => RowReader::Csv(
+                csv::Reader::from_reader(input)
+                    .into_deserialize()
+                    .enumerate(),
+            ),
+            ImportFormat::Jsonl => RowReader::Jsonl(BufReader::new(input).lines().enumerate()),
+        }
+    }
+}
+
+impl<R: Read> Iterator for RowReader<R> {
+    type Item = Result<ParsedRow, ImportUsersError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            RowReader::Csv(records) => {
+                let (index, record) = records.next()?;
+                let parsed = match record {
+                    Ok(record) => record.into_row(),
+                    Err(err) if err.is_io_error() => return Some(Err(io::Error::from(err).into())),
+                    Err(err) => Err(err.to_string()),
+                };
+                Some(Ok((index + 1, parsed)))
+            }
+            RowReader::Jsonl(lines) => loop {
+                let (index, line) = lines.next()?;
+                let line = match line {
+                    Ok(line) => line,
+                    Err(err) => return Some(Err(err.into())),
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let parsed = serde_json::from_str(&line).map_err(|err| err.to_string());
+                return Some(Ok((index + 1, parsed)));
+            },
+        }
+    }
+}
+
+/// Reads the results of previous runs from the report, and opens it for appending
+fn open_report(path: &Path) -> io::Result<(HashMap<String, ImportRowResult>, File)> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
+        Err(err) => return Err(err),
+    };
+
+    // Results are matched to rows by email rather than row number, so the input can be
+    // reordered or added to between runs. Only results that record progress are kept, and the
+    // last one for an email wins. A line cut short by a crash doesn't parse, so that row is
+    // simply imported again.
+    let mut previous_results = HashMap::new();
+    for line in String::from_utf8_lossy(&contents).lines() {
+        let result = match serde_json::from_str::<ImportRowResult>(line) {
+            Ok(result) => result,
+            Err(_) => continue,
+        };
+        let records_progress = matches!(
+            result.status,
+            ImportRowStatus::Imported
+                | ImportRowStatus::PartiallyImported
+                | ImportRowStatus::Rejected
+        );
+        if let (true, Some(email)) = (records_progress, &result.email) {
+            previous_results.insert(email_key(email), result);
+        }
+    }
+
+    let mut report = OpenOptions::new().create(true).append(true).open(path)?;
+    if !contents.is_empty() && !contents.ends_with(b"\n") {
+        report.write_all(b"\n")?;
+    }
+    Ok((previous_results, report))
+}
+
+fn record(
+    summary: &mut ImportSummary,
+    report: &mut Option<File>,
+    result: &ImportRowResult,
+) -> Result<(), ImportUsersError> {
+    match result.status {
+        ImportRowStatus::Imported => summary.imported += 1,
+        ImportRowStatus::PartiallyImported => summary.partially_imported += 1,
+        ImportRowStatus::Invalid => summary.invalid += 1,
+        ImportRowStatus::Rejected => summary.rejected += 1,
+        ImportRowStatus::Failed => summary.failed += 1,
+    }
+
+    if let Some(report) = report {
+        let mut line = serde_json::to_string(result).map_err(io::Error::from)?;
+        line.push('\n');
+        report.write_all(line.as_bytes())?;
+        report.flush()?;
+    }
+    Ok(())
+}
+
+/// Retries `call` with exponential backoff while `is_rate_limited` says PropelAuth rate
+/// limited it, waiting on `rate_limiter` before every attempt
+async fn with_retries<T, E, F, Fut>(
+    rate_limiter: &RateLimiter,
+    options: &ImportOptions,
+    is_rate_limited: fn(&E) -> bool,
+    call: F,
+) -> Result<T, E>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut delay = options.retry_delay;
+    let mut retries = 0;
+    loop {
+        rate_limiter.wait().await;
+        match call().await {
+            Err(err) if is_rate_limited(&err) && retries < options.max_retries => {
+                retries += 1;
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Spaces requests evenly so that at most `max_requests_per_second` start each second
+struct RateLimiter {
+    interval: Option<Duration>,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(max_requests_per_second: Option<u32>) -> RateLimiter {
+        RateLimiter {
+            interval: max_requests_per_second
+                .filter(|max| *max > 0)
+                .map(|max| Duration::from_secs(1) / max),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Instant> {
+        self.next_slot.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn wait(&self) {
+        let interval = match self.interval {
+            Some(interval) => interval,
+            None => return,
+        };
+
+        let wait = {
+            let mut next_slot = self.lock();
+            let now = Instant::now();
+            let slot = (*next_slot).max(now);
+            *next_slot = slot + interval;
+            slot - now
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::propelauth::import::{
+        is_base32, is_plausible_email, validate_row, ImportFormat, ImportOrgMembership,
+        ImportUserRow, RowReader,
+    };
+
+    #[test]
+    fn csv_rows_decode_nested_json_columns() {
+        let csv = "email,email_confirmed,first_name,properties,orgs\n\
+                   a@example.com,true,,\"{\"\"plan\"\": \"\"pro\"\"}\",\"[{\"\"org_id\"\": \"\"1189c444-8a2d-4c41-8b4b-ae43ce79a492\"\", \"\"role\"\": \"\"Admin\"\"}]\"\n\
+                   b@example.com,maybe,Bob,,\n\
+                   c@example.com,,,not json,\n";
+        let rows: Vec<_> = RowReader::new(csv.as_bytes(), ImportFormat::Csv)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(3, rows.len());
+
+        let first = rows[0].1.as_ref().unwrap();
+        assert!(first.email_confirmed);
+        assert_eq!(None, first.first_name);
+        assert_eq!(
+            Some(&serde_json::json!("pro")),
+            first.properties.as_ref().unwrap().get("plan")
+        );
+        assert_eq!("Admin", first.orgs[0].role);
+
+        assert!(rows[1].1.is_err());
+        assert_eq!(
+            Err("properties must be a JSON object".to_string()),
+            rows[2].1
+        );
+    }
+
+    #[test]
+    fn rows_are_validated_locally() {
+        let row = ImportUserRow {
+            email: "not an email".to_string(),
+            mfa_secret: Some("not base32!".to_string()),
+            orgs: vec![
+                ImportOrgMembership {
+                    org_id: "acme".to_string(),
+                    role: "".to_string(),
+                    additional_roles: vec![],
+                },
+                ImportOrgMembership {
+                    org_id: "acme".to_string(),
+                    role: "Admin".to_string(),
+                    additional_roles: vec![],
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                "email is not a valid email address",
+                "mfa_secret must be base32 encoded",
+                "acme is not a valid org ID",
+                "org acme is missing a role",
+                "acme is not a valid org ID",
+                "org acme is listed more than once",
+            ],
+            validate_row(&row)
+        );
+
+        assert!(is_plausible_email("first.last+tag@example.co.uk"));
+        assert!(!is_plausible_email("user@localhost"));
+        assert!(is_base32("JBSW Y3DP EHPK 3PXP"));
+        assert!(is_base32("jbswy3dpehpk3pxp===="));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn imports_users_and_resumes_from_the_report() {
+        use std::time::Duration;
+
+        use crate::models::CreateOrgRequest;
+        use crate::propelauth::import::{ImportOptions, ImportRowResult, ImportSummary};
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let report_path =
+            std::env::temp_dir().join(format!("propelauth-import-{}.jsonl", uuid::Uuid::new_v4()));
+
+        runtime.block_on(async {
+            let org_id = auth
+                .org()
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id;
+            let missing_org_id = "8a4c2b5e-0d0b-4f7e-9c52-3b8f5e0f6a11";
+            let input = format!(
+                "{}\n{}\n{}\n{}\n{}\n",
+                serde_json::json!({
+                    "email": "alice@example.com",
+                    "email_confirmed": true,
+                    "legacy_user_id": "legacy-1",
+                    "orgs": [{"org_id": org_id, "role": "Admin"}]
+                }),
+                serde_json::json!({
+                    "email": "bob@example.com",
+                    "orgs": [
+                        {"org_id": org_id, "role": "Member"},
+                        {"org_id": missing_org_id, "role": "Member"}
+                    ]
+                }),
+                serde_json::json!({"email": "ALICE@example.com"}),
+                serde_json::json!({"email": "nope"}),
+                "{not json",
+            );
+            let options = ImportOptions {
+                concurrency: 2,
+                max_requests_per_second: None,
+                retry_delay: Duration::from_millis(10),
+                report_path: Some(report_path.clone()),
+                ..Default::default()
+            };
+
+            fake.rate_limit_next_requests(1);
+            let summary = auth
+                .user()
+                .import_users(input.as_bytes(), ImportFormat::Jsonl, options.clone())
+                .await
+                .unwrap();
+            assert_eq!(
+                ImportSummary {
+                    imported: 1,
+                    partially_imported: 1,
+                    invalid: 3,
+                    ..Default::default()
+                },
+                summary
+            );
+            assert_eq!(2, fake.users().len());
+
+            let report = std::fs::read_to_string(&report_path).unwrap();
+            let results: Vec<ImportRowResult> = report
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+            let bob = results.iter().find(|result| result.row == 2).unwrap();
+            assert_eq!(vec![missing_org_id.to_string()], bob.failed_org_ids);
+            let duplicate = results.iter().find(|result| result.row == 3).unwrap();
+            assert_eq!(vec!["email is a duplicate of row 1"], duplicate.errors);
+
+            // Alice is skipped and only Bob's missing org is retried
+            let summary = auth
+                .user()
+                .import_users(input.as_bytes(), ImportFormat::Jsonl, options)
+                .await
+                .unwrap();
+            assert_eq!(
+                ImportSummary {
+                    partially_imported: 1,
+                    invalid: 3,
+                    skipped: 1,
+                    ..Default::default()
+                },
+                summary
+            );
+            assert_eq!(2, fake.users().len());
+        });
+
+        std::fs::remove_file(&report_path).unwrap();
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn picks_up_users_created_before_a_crash() {
+        use crate::apis::user_service_api::FetchUserByEmailParams;
+        use crate::models::{AddUserToOrgRequest, CreateOrgRequest, MigrateUserRequest};
+        use crate::propelauth::import::{ImportOptions, ImportRowResult, ImportSummary};
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let report_path =
+            std::env::temp_dir().join(format!("propelauth-import-{}.jsonl", uuid::Uuid::new_v4()));
+
+        runtime.block_on(async {
+            let mut org_ids = vec![];
+            for name in ["Acme", "Initech"].iter() {
+                let org = auth
+                    .org()
+                    .create_org(CreateOrgRequest {
+                        name: name.to_string(),
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap();
+                org_ids.push(org.org_id);
+            }
+
+            // A previous run created Alice and added her to the first org, then crashed before
+            // writing her result. Bob's email already belongs to someone else.
+            let alice = auth
+                .user()
+                .migrate_user(MigrateUserRequest {
+                    email: "alice@example.com".to_string(),
+                    existing_user_id: Some("legacy-1".to_string()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            auth.org()
+                .add_user_to_org(AddUserToOrgRequest::new(
+                    alice.user_id.clone(),
+                    org_ids[0].clone(),
+                    "Admin".to_string(),
+                ))
+                .await
+                .unwrap();
+            auth.user()
+                .migrate_user(MigrateUserRequest {
+                    email: "bob@example.com".to_string(),
+                    existing_user_id: Some("someone-else".to_string()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+
+            let input = format!(
+                "{}\n{}\n",
+                serde_json::json!({
+                    "email": "Alice@example.com",
+                    "legacy_user_id": "legacy-1",
+                    "orgs": [
+                        {"org_id": org_ids[0], "role": "Admin"},
+                        {"org_id": org_ids[1], "role": "Member"}
+                    ]
+                }),
+                serde_json::json!({"email": "bob@example.com", "legacy_user_id": "legacy-2"}),
+            );
+            let options = ImportOptions {
+                max_requests_per_second: None,
+                report_path: Some(report_path.clone()),
+                ..Default::default()
+            };
+            let summary = auth
+                .user()
+                .import_users(input.as_bytes(), ImportFormat::Jsonl, options.clone())
+                .await
+                .unwrap();
+            assert_eq!(
+                ImportSummary {
+                    imported: 1,
+                    rejected: 1,
+                    ..Default::default()
+                },
+                summary
+            );
+
+            let report = std::fs::read_to_string(&report_path).unwrap();
+            let alice_result: ImportRowResult =
+                serde_json::from_str(report.lines().find(|line| line.contains("Alice")).unwrap())
+                    .unwrap();
+            assert_eq!(Some(alice.user_id), alice_result.user_id);
+            assert!(alice_result.errors.is_empty());
+            assert_eq!(2, fake.users().len());
+            let alice = auth
+                .user()
+                .fetch_user_by_email(FetchUserByEmailParams {
+                    email: "alice@example.com".to_string(),
+                    include_orgs: Some(true),
+                })
+                .await
+                .unwrap();
+            let orgs = alice.org_id_to_org_info.unwrap();
+            assert_eq!("Admin", orgs[&org_ids[0]].user_role);
+            assert_eq!("Member", orgs[&org_ids[1]].user_role);
+
+            // Progress is keyed by email, so reordering the input doesn't redo anything
+            let reordered: Vec<&str> = input.lines().rev().collect();
+            let summary = auth
+                .user()
+                .import_users(
+                    reordered.join("\n").as_bytes(),
+                    ImportFormat::Jsonl,
+                    options,
+                )
+                .await
+                .unwrap();
+            assert_eq!(
+                ImportSummary {
+                    skipped: 2,
+                    ..Default::default()
+                },
+                summary
+            );
+        });
+
+        std::fs::remove_file(&report_path).unwrap();
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn rejects_rows_without_a_legacy_id_whose_email_is_taken() {
+        use crate::apis::user_service_api::FetchUserByEmailParams;
+        use crate::models::{CreateOrgRequest, CreateUserRequest};
+        use crate::propelauth::import::{ImportOptions, ImportSummary};
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let org_id = auth
+                .org()
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id;
+            auth.user()
+                .create_user(CreateUserRequest {
+                    email: "carol@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+
+            let input = serde_json::json!({
+                "email": "carol@example.com",
+                "orgs": [{"org_id": org_id, "role": "Admin"}]
+            })
+            .to_string();
+            let options = ImportOptions {
+                max_requests_per_second: None,
+                ..Default::default()
+            };
+            let summary = auth
+                .user()
+                .import_users(input.as_bytes(), ImportFormat::Jsonl, options)
+                .await
+                .unwrap();
+            assert_eq!(
+                ImportSummary {
+                    rejected: 1,
+                    ..Default::default()
+                },
+                summary
+            );
+
+            let carol = auth
+                .user()
+                .fetch_user_by_email(FetchUserByEmailParams {
+                    email: "carol@example.com".to_string(),
+                    include_orgs: Some(true),
+                })
+                .await
+                .unwrap();
+            assert!(carol.org_id_to_org_info.unwrap_or_default().is_empty());
+        });
+    }
+}
@@ -0,0 +1,442 @@
+//! Resolves the IDs users and orgs had before they were migrated to PropelAuth, i.e.
+//! `MigrateUserRequest::existing_user_id` and `CreateOrgRequest::legacy_org_id`.
+
+use std::future::Future;
+use std::str::FromStr;
+
+use crate::apis::org_service_api::FetchOrgsByQueryParams;
+use crate::apis::user_service_api::{FetchUserByIdParams, FetchUsersByQueryParams};
+use crate::propelauth::cache::{read_cached, write_cached, CacheKey, CachedValue};
+use crate::propelauth::errors::{
+    FetchByQueryError, FetchOrgsByQueryError, ResolveIdError, ResolvedIdError,
+};
+use crate::propelauth::helpers::is_valid_id;
+use crate::propelauth::ids::{OrgId, UserId};
+use crate::propelauth::org::OrgService;
+use crate::propelauth::user::UserService;
+
+/// A user or org ID that is either PropelAuth's own or the legacy ID from before the migration
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IdOrLegacy {
+    Id(String),
+    Legacy(String),
+}
+
+impl IdOrLegacy {
+    /// Treats UUIDs as PropelAuth IDs and anything else as a legacy ID. If your legacy IDs are
+    /// UUIDs too, use [`IdOrLegacy::Legacy`] directly instead.
+    pub fn detect(id: impl Into<String>) -> IdOrLegacy {
+        let id = id.into();
+        if is_valid_id(&id) {
+            IdOrLegacy::Id(id)
+        } else {
+            IdOrLegacy::Legacy(id)
+        }
+    }
+}
+
+impl From<FetchByQueryError> for ResolveIdError {
+    fn from(err: FetchByQueryError) -> Self {
+        match err {
+            FetchByQueryError::InvalidApiKey => ResolveIdError::InvalidApiKey,
+            FetchByQueryError::PropelAuthRateLimit => ResolveIdError::PropelAuthRateLimit,
+            _ => ResolveIdError::UnexpectedException,
+        }
+    }
+}
+
+impl From<FetchOrgsByQueryError> for ResolveIdError {
+    fn from(err: FetchOrgsByQueryError) -> Self {
+        match err {
+            FetchOrgsByQueryError::InvalidApiKey => ResolveIdError::InvalidApiKey,
+            FetchOrgsByQueryError::PropelAuthRateLimit => ResolveIdError::PropelAuthRateLimit,
+            _ => ResolveIdError::UnexpectedException,
+        }
+    }
+}
+
+fn parse_id<T: FromStr>(id: &str) -> Result<T, ResolveIdError> {
+    id.parse().map_err(|_| ResolveIdError::NotFound)
+}
+
+fn single_match(mut ids: Vec<String>) -> Result<String, ResolveIdError> {
+    match ids.len() {
+        0 => Err(ResolveIdError::NotFound),
+        1 => Ok(ids.remove(0)),
+        _ => Err(ResolveIdError::Ambiguous(ids)),
+    }
+}
+
+impl<'a> UserService<'a> {
+    /// Returns the PropelAuth user ID for `id`, looking legacy IDs up by `legacy_user_id`.
+    ///
+    /// PropelAuth IDs are parsed but not checked against PropelAuth, so an ID that isn't a UUID
+    /// is `NotFound`. Resolved legacy IDs are cached if the cache is enabled, so a legacy ID
+    /// that is later reassigned may resolve to the old user until the entry expires.
+    pub async fn resolve_user_id(&self, id: IdOrLegacy) -> Result<UserId, ResolveIdError> {
+        let legacy_user_id = match id {
+            IdOrLegacy::Id(user_id) => return parse_id(&user_id),
+            IdOrLegacy::Legacy(legacy_user_id) => legacy_user_id,
+        };
+        let cache_key = CacheKey::LegacyUser {
+            legacy_user_id: legacy_user_id.clone(),
+        };
+        if let Some(CachedValue::Id(user_id)) = read_cached(self.config, &cache_key).await {
+            return parse_id(&user_id);
+        }
+
+        // Two results are enough to know the legacy ID is ambiguous
+        let users = self
+            .fetch_users_by_query(FetchUsersByQueryParams {
+                page_size: Some(2),
+                legacy_user_id: Some(legacy_user_id),
+                ..Default::default()
+            })
+            .await?
+            .users;
+        let user_id = single_match(users.into_iter().map(|user| user.user_id).collect())?;
+        write_cached(self.config, cache_key, CachedValue::Id(user_id.clone())).await;
+        parse_id(&user_id)
+    }
+}
+
+impl<'a> OrgService<'a> {
+    /// Returns the PropelAuth org ID for `id`, looking legacy IDs up by `legacy_org_id`. See
+    /// [`UserService::resolve_user_id`].
+    pub async fn resolve_org_id(&self, id: IdOrLegacy) -> Result<OrgId, ResolveIdError> {
+        let legacy_org_id = match id {
+            IdOrLegacy::Id(org_id) => return parse_id(&org_id),
+            IdOrLegacy::Legacy(legacy_org_id) => legacy_org_id,
+        };
+        let cache_key = CacheKey::LegacyOrg {
+            legacy_org_id: legacy_org_id.clone(),
+        };
+        if let Some(CachedValue::Id(org_id)) = read_cached(self.config, &cache_key).await {
+            return parse_id(&org_id);
+        }
+
+        let orgs = self
+            .fetch_orgs_by_query(FetchOrgsByQueryParams {
+                page_size: Some(2),
+                legacy_org_id: Some(legacy_org_id),
+                ..Default::default()
+            })
+            .await?
+            .orgs;
+        let org_id = single_match(orgs.into_iter().map(|org| org.org_id).collect())?;
+        write_cached(self.config, cache_key, CachedValue::Id(org_id.clone())).await;
+        parse_id(&org_id)
+    }
+}
+
+impl<'a> UserService<'a> {
+    /// Resolves `id` like [`resolve_user_id`](Self::resolve_user_id), then runs `operation`
+    /// with the PropelAuth ID. This lets any method that takes a [`UserId`] be called with an
+    /// [`IdOrLegacy`].
+    pub async fn with_resolved_user_id<T, E, F, Fut>(
+        &self,
+        id: IdOrLegacy,
+        operation: F,
+    ) -> Result<T, ResolvedIdError<E>>
+    where
+        F: FnOnce(UserId) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let user_id = self.resolve_user_id(id).await?;
+        operation(user_id).await.map_err(ResolvedIdError::Operation)
+    }
+
+    /// The cached legacy ID lookups to drop when `user_id` is deleted or given
+    /// `new_legacy_user_id`. Finding the user's current legacy ID may take a request, so this is
+    /// only done when the cache is enabled.
+    pub(crate) async fn legacy_cache_keys(
+        &self,
+        user_id: &str,
+        new_legacy_user_id: Option<&String>,
+    ) -> Vec<CacheKey> {
+        if self.config.cache.is_none() {
+            return vec![];
+        }
+
+        let mut legacy_user_ids: Vec<String> = new_legacy_user_id.into_iter().cloned().collect();
+        let params = FetchUserByIdParams {
+            user_id: user_id.to_string(),
+            include_orgs: None,
+        };
+        if let Ok(user) = self.fetch_user_by_id(params).await {
+            legacy_user_ids.extend(user.legacy_user_id);
+        }
+        legacy_user_ids
+            .into_iter()
+            .map(|legacy_user_id| CacheKey::LegacyUser { legacy_user_id })
+            .collect()
+    }
+}
+
+impl<'a> OrgService<'a> {
+    /// Resolves `id` like [`resolve_org_id`](Self::resolve_org_id), then runs `operation` with
+    /// the PropelAuth ID. See [`UserService::with_resolved_user_id`].
+    pub async fn with_resolved_org_id<T, E, F, Fut>(
+        &self,
+        id: IdOrLegacy,
+        operation: F,
+    ) -> Result<T, ResolvedIdError<E>>
+    where
+        F: FnOnce(OrgId) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let org_id = self.resolve_org_id(id).await?;
+        operation(org_id).await.map_err(ResolvedIdError::Operation)
+    }
+
+    /// The cached legacy ID lookups to drop when `org_id` is deleted or given
+    /// `new_legacy_org_id`. See [`UserService::legacy_cache_keys`].
+    pub(crate) async fn legacy_cache_keys(
+        &self,
+        org_id: &str,
+        new_legacy_org_id: Option<&String>,
+    ) -> Vec<CacheKey> {
+        if self.config.cache.is_none() {
+            return vec![];
+        }
+
+        let mut legacy_org_ids: Vec<String> = new_legacy_org_id.into_iter().cloned().collect();
+        if let Ok(org) = self.fetch_org_by_raw_id(org_id.to_string()).await {
+            legacy_org_ids.extend(org.legacy_org_id);
+        }
+        legacy_org_ids
+            .into_iter()
+            .map(|legacy_org_id| CacheKey::LegacyOrg { legacy_org_id })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::propelauth::legacy_id::IdOrLegacy;
+
+    #[test]
+    fn detects_legacy_ids() {
+        let id = "8a4c2b5e-0d0b-4f7e-9c52-3b8f5e0f6a11";
+        assert_eq!(IdOrLegacy::Id(id.to_string()), IdOrLegacy::detect(id));
+        assert_eq!(
+            IdOrLegacy::Legacy("12345".to_string()),
+            IdOrLegacy::detect("12345")
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn resolves_and_caches_legacy_ids() {
+        use crate::models::{CreateOrgRequest, MigrateUserRequest};
+        use crate::propelauth::cache::CacheOptions;
+        use crate::propelauth::errors::ResolveIdError;
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth().with_cache(CacheOptions::default());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let mut user_ids = vec![];
+            for (email, legacy_user_id) in [
+                ("a@example.com", "1"),
+                ("b@example.com", "2"),
+                ("c@example.com", "2"),
+            ] {
+                let user_id = auth
+                    .user()
+                    .migrate_user(MigrateUserRequest {
+                        email: email.to_string(),
+                        existing_user_id: Some(legacy_user_id.to_string()),
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap()
+                    .user_id;
+                user_ids.push(user_id);
+            }
+            let org_id = auth
+                .org()
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    legacy_org_id: Some("acme".to_string()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id;
+
+            let users = auth.user();
+            assert_eq!(
+                Ok(user_ids[0].parse().unwrap()),
+                users.resolve_user_id(IdOrLegacy::detect("1")).await
+            );
+            assert_eq!(
+                Ok(user_ids[1].parse().unwrap()),
+                users
+                    .resolve_user_id(IdOrLegacy::detect(&user_ids[1]))
+                    .await
+            );
+            match users.resolve_user_id(IdOrLegacy::detect("2")).await {
+                Err(ResolveIdError::Ambiguous(mut ids)) => {
+                    ids.sort();
+                    let mut expected = user_ids[1..].to_vec();
+                    expected.sort();
+                    assert_eq!(expected, ids);
+                }
+                result => panic!("expected an ambiguous match, got {:?}", result),
+            }
+            assert_eq!(
+                Err(ResolveIdError::NotFound),
+                users.resolve_user_id(IdOrLegacy::detect("3")).await
+            );
+
+            assert_eq!(
+                Ok(org_id.parse().unwrap()),
+                auth.org()
+                    .resolve_org_id(IdOrLegacy::Legacy("acme".to_string()))
+                    .await
+            );
+
+            // Served from the cache even when PropelAuth can't be reached
+            fake.rate_limit_next_requests(1);
+            assert_eq!(
+                Ok(user_ids[0].parse().unwrap()),
+                users.resolve_user_id(IdOrLegacy::detect("1")).await
+            );
+            assert_eq!(
+                Err(ResolveIdError::PropelAuthRateLimit),
+                users.resolve_user_id(IdOrLegacy::detect("3")).await
+            );
+        });
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn runs_operations_on_legacy_ids_and_forgets_stale_ones() {
+        use crate::apis::user_service_api::UpdateUserMetadataParams;
+        use crate::models::{
+            CreateOrgRequest, MigrateUserRequest, UpdateMetadataRequest, UpdateOrgRequest,
+        };
+        use crate::propelauth::cache::CacheOptions;
+        use crate::propelauth::errors::{ErrorsWithNotFound, ResolveIdError, ResolvedIdError};
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth().with_cache(CacheOptions::default());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let users = auth.user();
+            let mut user_ids = vec![];
+            for (email, legacy_user_id) in [("a@example.com", "1"), ("b@example.com", "2")] {
+                let user_id = users
+                    .migrate_user(MigrateUserRequest {
+                        email: email.to_string(),
+                        existing_user_id: Some(legacy_user_id.to_string()),
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap()
+                    .user_id;
+                user_ids.push(user_id);
+            }
+            let resolve =
+                |legacy_user_id: &str| users.resolve_user_id(IdOrLegacy::detect(legacy_user_id));
+
+            users
+                .with_resolved_user_id(IdOrLegacy::detect("1"), |user_id| {
+                    users.disable_user_by_id(user_id)
+                })
+                .await
+                .unwrap();
+            assert!(
+                !fake
+                    .users()
+                    .iter()
+                    .find(|u| u.user_id == user_ids[0])
+                    .unwrap()
+                    .enabled
+            );
+            assert_eq!(
+                Err(ResolvedIdError::Resolve(ResolveIdError::NotFound)),
+                users
+                    .with_resolved_user_id(IdOrLegacy::detect("3"), |user_id| {
+                        users.disable_user_by_id(user_id)
+                    })
+                    .await
+            );
+
+            // Giving the user a new legacy ID drops the cached old one
+            users
+                .update_user_metadata(UpdateUserMetadataParams {
+                    user_id: user_ids[0].clone(),
+                    update_metadata_request: UpdateMetadataRequest {
+                        legacy_user_id: Some("7".to_string()),
+                        ..Default::default()
+                    },
+                })
+                .await
+                .unwrap();
+            assert_eq!(Err(ResolveIdError::NotFound), resolve("1").await);
+            assert_eq!(Ok(user_ids[0].parse().unwrap()), resolve("7").await);
+
+            assert_eq!(Ok(user_ids[1].parse().unwrap()), resolve("2").await);
+            users
+                .delete_user_by_id(user_ids[1].parse().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(Err(ResolveIdError::NotFound), resolve("2").await);
+
+            let orgs = auth.org();
+            let org_id = orgs
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    legacy_org_id: Some("acme".to_string()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id
+                .parse()
+                .unwrap();
+            let resolve =
+                |legacy_org_id: &str| orgs.resolve_org_id(IdOrLegacy::detect(legacy_org_id));
+            assert_eq!(Ok(org_id), resolve("acme").await);
+            orgs.update_org_by_id(
+                org_id,
+                UpdateOrgRequest {
+                    legacy_org_id: Some("acme-inc".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+            assert_eq!(Err(ResolveIdError::NotFound), resolve("acme").await);
+            assert_eq!(Ok(org_id), resolve("acme-inc").await);
+
+            assert_eq!(
+                Ok(()),
+                orgs.with_resolved_org_id(IdOrLegacy::detect("acme-inc"), |org_id| {
+                    orgs.delete_org_by_id(org_id)
+                })
+                .await
+            );
+            assert_eq!(Err(ResolveIdError::NotFound), resolve("acme-inc").await);
+            assert_eq!(
+                Err(ResolvedIdError::Operation(ErrorsWithNotFound::NotFound)),
+                orgs.with_resolved_org_id(IdOrLegacy::Id(org_id.to_string()), |org_id| {
+                    orgs.delete_org_by_id(org_id)
+                })
+                .await
+            );
+        });
+    }
+}
@@ -0,0 +1,396 @@
+//! Disables or deletes users who haven't been active for a while, based on
+//! [`UserMetadata::last_active_at`].
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures_util::stream::StreamExt;
+use serde_json::Value;
+
+use crate::apis::user_service_api::FetchUsersByQueryParams;
+use crate::models::{FetchUsersOrderBy, UserMetadata};
+use crate::propelauth::errors::{ErrorsWithNotFound, FetchByQueryError};
+use crate::propelauth::ids::UserId;
+use crate::propelauth::pagination::PaginationOptions;
+use crate::propelauth::user::UserService;
+
+/// Rules for [`UserService::apply_inactivity_policy`]
+#[derive(Debug, Clone, Default)]
+pub struct InactivityPolicy {
+    /// Disable enabled users who haven't been active for this long
+    pub disable_after: Option<Duration>,
+    /// Delete users who haven't been active for this long, whether or not they're disabled
+    pub delete_after: Option<Duration>,
+    /// Never touch members of these orgs
+    pub exempt_org_ids: Vec<String>,
+    /// Never touch users with any of these properties set to the given value
+    pub exempt_properties: HashMap<String, Value>,
+    /// Log users out of all their sessions before disabling them
+    pub logout_before_disabling: bool,
+    /// Only report what would happen, without disabling or deleting anyone
+    pub dry_run: bool,
+    pub pagination: PaginationOptions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InactiveUserAction {
+    Disable,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InactiveUserOutcome {
+    /// The action was taken
+    Applied,
+    /// The action would have been taken, but this was a dry run
+    DryRun,
+    /// The user is exempt because of their orgs or properties
+    Exempt,
+    /// The action failed, and the user was left as is
+    Failed(String),
+}
+
+/// What happened to a single inactive user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InactiveUserResult {
+    pub user_id: String,
+    pub email: String,
+    pub last_active_at: i64,
+    pub action: InactiveUserAction,
+    pub outcome: InactiveUserOutcome,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InactivityReport {
+    /// How many users had been inactive for longer than the shortest limit
+    pub scanned: usize,
+    /// Every user an action applied to, least recently active first
+    pub results: Vec<InactiveUserResult>,
+}
+
+impl InactivityReport {
+    pub fn count(&self, action: InactiveUserAction, outcome: &InactiveUserOutcome) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.action == action && &result.outcome == outcome)
+            .count()
+    }
+}
+
+impl InactivityPolicy {
+    fn action_for(&self, user: &UserMetadata, now: i64) -> Option<InactiveUserAction> {
+        let inactive_for = now.saturating_sub(user.last_active_at);
+        let exceeds = |limit: Option<Duration>| {
+            limit.is_some_and(|limit| inactive_for >= limit.as_secs() as i64)
+        };
+        if exceeds(self.delete_after) {
+            Some(InactiveUserAction::Delete)
+        } else if exceeds(self.disable_after) && user.enabled {
+            Some(InactiveUserAction::Disable)
+        } else {
+            None
+        }
+    }
+
+    /// Users are scanned least recently active first, so once someone is more recent than
+    /// every limit, so is everyone after them
+    fn is_past_candidates(&self, user: &UserMetadata, now: i64) -> bool {
+        let shortest_limit = match (self.disable_after, self.delete_after) {
+            (Some(disable), Some(delete)) => disable.min(delete),
+            (Some(limit), None) | (None, Some(limit)) => limit,
+            (None, None) => return true,
+        };
+        now.saturating_sub(user.last_active_at) < shortest_limit.as_secs() as i64
+    }
+
+    fn is_exempt(&self, user: &UserMetadata) -> bool {
+        let in_exempt_org = user.org_id_to_org_info.as_ref().is_some_and(|orgs| {
+            self.exempt_org_ids
+                .iter()
+                .any(|org_id| orgs.contains_key(org_id))
+        });
+        let has_exempt_property = user.properties.as_ref().is_some_and(|properties| {
+            self.exempt_properties
+                .iter()
+                .any(|(key, value)| properties.get(key) == Some(value))
+        });
+        in_exempt_org || has_exempt_property
+    }
+}
+
+impl<'a> UserService<'a> {
+    /// Disables or deletes users according to `policy`, walking through users from least to
+    /// most recently active and stopping at the first one who is within every limit.
+    ///
+    /// All candidates are found before any of them are changed, so that deleting users doesn't
+    /// shift the pages being read. A failure to disable or delete one user is recorded in the
+    /// report and doesn't stop the others; only failing to fetch users aborts the run.
+    pub async fn apply_inactivity_policy(
+        &self,
+        policy: &InactivityPolicy,
+    ) -> Result<InactivityReport, FetchByQueryError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs() as i64);
+        let params = FetchUsersByQueryParams {
+            order_by: Some(FetchUsersOrderBy::LastActiveAtAsc),
+            include_orgs: Some(!policy.exempt_org_ids.is_empty()),
+            ..Default::default()
+        };
+
+        let mut report = InactivityReport::default();
+        let mut candidates = vec![];
+        let mut users =
+            Box::pin(self.fetch_users_by_query_stream(params, policy.pagination.clone()));
+        while let Some(user) = users.next().await {
+            let user = user?;
+            if policy.is_past_candidates(&user, now) {
+                break;
+            }
+            report.scanned += 1;
+            if let Some(action) = policy.action_for(&user, now) {
+                candidates.push((user, action));
+            }
+        }
+
+        for (user, action) in candidates {
+            let outcome = if policy.is_exempt(&user) {
+                InactiveUserOutcome::Exempt
+            } else if policy.dry_run {
+                InactiveUserOutcome::DryRun
+            } else {
+                match self.apply_inactive_user_action(&user, action, policy).await {
+                    Ok(()) => InactiveUserOutcome::Applied,
+                    Err(err) => InactiveUserOutcome::Failed(err.to_string()),
+                }
+            };
+            report.results.push(InactiveUserResult {
+                user_id: user.user_id,
+                email: user.email,
+                last_active_at: user.last_active_at,
+                action,
+                outcome,
+            });
+        }
+        Ok(report)
+    }
+
+    async fn apply_inactive_user_action(
+        &self,
+        user: &UserMetadata,
+        action: InactiveUserAction,
+        policy: &InactivityPolicy,
+    ) -> Result<(), ErrorsWithNotFound> {
+        let user_id: UserId = user
+            .user_id
+            .parse()
+            .map_err(|_| ErrorsWithNotFound::NotFound)?;
+        match action {
+            InactiveUserAction::Delete => self.delete_user_by_id(user_id).await,
+            InactiveUserAction::Disable => {
+                if policy.logout_before_disabling {
+                    self.logout_all_user_sessions_by_id(user_id).await?;
+                }
+                self.disable_user_by_id(user_id).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use crate::models::{UserInOrg, UserMetadata};
+    use crate::propelauth::lifecycle::{InactiveUserAction, InactivityPolicy};
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    #[test]
+    fn picks_the_action_for_how_long_a_user_has_been_inactive() {
+        let now = 1_000 * DAY;
+        let policy = InactivityPolicy {
+            disable_after: Some(Duration::from_secs(90 * DAY as u64)),
+            delete_after: Some(Duration::from_secs(365 * DAY as u64)),
+            ..Default::default()
+        };
+        let user = |days_inactive: i64, enabled: bool| UserMetadata {
+            last_active_at: now - days_inactive * DAY,
+            enabled,
+            ..Default::default()
+        };
+
+        assert_eq!(None, policy.action_for(&user(89, true), now));
+        assert!(policy.is_past_candidates(&user(89, true), now));
+        assert_eq!(
+            Some(InactiveUserAction::Disable),
+            policy.action_for(&user(90, true), now)
+        );
+        assert!(!policy.is_past_candidates(&user(90, true), now));
+        assert_eq!(None, policy.action_for(&user(200, false), now));
+        assert_eq!(
+            Some(InactiveUserAction::Delete),
+            policy.action_for(&user(365, false), now)
+        );
+    }
+
+    #[test]
+    fn exempts_users_by_org_or_property() {
+        let policy = InactivityPolicy {
+            exempt_org_ids: vec!["internal".to_string()],
+            exempt_properties: HashMap::from([("vip".to_string(), json!(true))]),
+            ..Default::default()
+        };
+        let user = |org_id: &str, vip: bool| UserMetadata {
+            org_id_to_org_info: Some(HashMap::from([(org_id.to_string(), UserInOrg::default())])),
+            properties: Some(HashMap::from([("vip".to_string(), json!(vip))])),
+            ..Default::default()
+        };
+
+        assert!(policy.is_exempt(&user("internal", false)));
+        assert!(policy.is_exempt(&user("customer", true)));
+        assert!(!policy.is_exempt(&user("customer", false)));
+        assert!(!policy.is_exempt(&UserMetadata::default()));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn disables_and_deletes_inactive_users() {
+        use std::sync::{Arc, Mutex};
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        use reqwest::Request;
+
+        use crate::apis::interceptor::Interceptor;
+        use crate::models::CreateUserRequest;
+        use crate::propelauth::lifecycle::InactiveUserOutcome;
+        use crate::propelauth::pagination::PaginationOptions;
+        use crate::testing::FakeBackend;
+
+        struct RecordPaths(Arc<Mutex<Vec<String>>>);
+        impl Interceptor for RecordPaths {
+            fn before_send(&self, request: &mut Request) {
+                let path = request.url().path().to_string();
+                self.0.lock().unwrap().push(path);
+            }
+        }
+
+        let fake = FakeBackend::start();
+        let paths = Arc::new(Mutex::new(vec![]));
+        let auth = fake.auth().with_interceptor(RecordPaths(paths.clone()));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        runtime.block_on(async {
+            let mut user_ids = HashMap::new();
+            for (name, days_inactive, vip) in [
+                ("ancient", 400, false),
+                ("stale", 100, false),
+                ("stale-vip", 120, true),
+                ("recent", 10, false),
+            ] {
+                let user_id = auth
+                    .user()
+                    .create_user(CreateUserRequest {
+                        email: format!("{}@example.com", name),
+                        properties: Some(HashMap::from([("vip".to_string(), json!(vip))])),
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap()
+                    .user_id;
+                fake.modify_user(&user_id, |user| {
+                    user.last_active_at = now - days_inactive * DAY
+                });
+                user_ids.insert(name, user_id);
+            }
+            let mut policy = InactivityPolicy {
+                disable_after: Some(Duration::from_secs(90 * DAY as u64)),
+                delete_after: Some(Duration::from_secs(365 * DAY as u64)),
+                exempt_properties: HashMap::from([("vip".to_string(), json!(true))]),
+                logout_before_disabling: true,
+                dry_run: true,
+                pagination: PaginationOptions {
+                    page_size: Some(1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let report = auth.user().apply_inactivity_policy(&policy).await.unwrap();
+            assert_eq!(3, report.scanned);
+            let outcomes: Vec<_> = report
+                .results
+                .iter()
+                .map(|result| (result.email.as_str(), result.action, &result.outcome))
+                .collect();
+            assert_eq!(
+                vec![
+                    (
+                        "ancient@example.com",
+                        InactiveUserAction::Delete,
+                        &InactiveUserOutcome::DryRun
+                    ),
+                    (
+                        "stale-vip@example.com",
+                        InactiveUserAction::Disable,
+                        &InactiveUserOutcome::Exempt
+                    ),
+                    (
+                        "stale@example.com",
+                        InactiveUserAction::Disable,
+                        &InactiveUserOutcome::DryRun
+                    ),
+                ],
+                outcomes
+            );
+            assert_eq!(4, fake.users().len());
+
+            policy.dry_run = false;
+            paths.lock().unwrap().clear();
+            let report = auth.user().apply_inactivity_policy(&policy).await.unwrap();
+            assert_eq!(
+                1,
+                report.count(InactiveUserAction::Delete, &InactiveUserOutcome::Applied)
+            );
+            assert_eq!(
+                1,
+                report.count(InactiveUserAction::Disable, &InactiveUserOutcome::Applied)
+            );
+
+            let users = fake.users();
+            assert_eq!(3, users.len());
+            let stale = users
+                .iter()
+                .find(|user| user.user_id == user_ids["stale"])
+                .unwrap();
+            assert!(!stale.enabled);
+            assert!(paths.lock().unwrap().contains(&format!(
+                "/api/backend/v1/user/{}/logout_all_sessions",
+                user_ids["stale"]
+            )));
+
+            // Disabled users aren't disabled again
+            let report = auth.user().apply_inactivity_policy(&policy).await.unwrap();
+            assert_eq!(
+                vec![InactiveUserOutcome::Exempt],
+                report
+                    .results
+                    .into_iter()
+                    .map(|result| result.outcome)
+                    .collect::<Vec<_>>()
+            );
+        });
+    }
+}
@@ -0,0 +1,429 @@
+//! Typed read-modify-write access to a user's metadata and properties.
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::apis::user_service_api::{FetchUserByIdParams, UpdateUserMetadataParams};
+use crate::models::{UpdateMetadataRequest, UserMetadata};
+use crate::propelauth::errors::{ErrorsWithNotFound, ModifyMetadataError, UpdateUserMetadataError};
+use crate::propelauth::ids::UserId;
+use crate::propelauth::user::UserService;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MetadataField {
+    #[default]
+    Metadata,
+    Properties,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ModifyMetadataOptions {
+    pub field: MetadataField,
+    /// Re-fetch the user just before writing, and start over from the fresh data if any of the
+    /// stored values changed since they were read. This narrows the window in which another
+    /// writer's changes can be lost, but PropelAuth has no conditional writes, so it can't
+    /// close it entirely.
+    pub detect_conflicts: bool,
+    /// How many times to start over after a conflict before giving up
+    pub max_conflict_retries: usize,
+}
+
+/// The keys a modification changed
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MetadataDiff {
+    /// Keys that were added or given a new value
+    pub set: HashMap<String, Value>,
+    /// Keys that were removed
+    pub removed: Vec<String>,
+}
+
+impl MetadataDiff {
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.removed.is_empty()
+    }
+
+    pub(crate) fn between(before: &Map<String, Value>, after: &Map<String, Value>) -> MetadataDiff {
+        let set = after
+            .iter()
+            .filter(|(key, value)| before.get(*key) != Some(*value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let mut removed: Vec<String> = before
+            .keys()
+            .filter(|key| !after.contains_key(*key))
+            .cloned()
+            .collect();
+        removed.sort();
+        MetadataDiff { set, removed }
+    }
+
+    /// `stored` with this diff's changes applied
+    pub(crate) fn apply_to(&self, stored: &Map<String, Value>) -> HashMap<String, Value> {
+        let mut values: HashMap<String, Value> = stored
+            .iter()
+            .filter(|(key, _)| !self.removed.contains(*key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        values.extend(self.set.clone());
+        values
+    }
+}
+
+/// The result of [`UserService::modify_user_metadata`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModifiedMetadata<T> {
+    /// The value as it was written
+    pub value: T,
+    pub diff: MetadataDiff,
+}
+
+impl MetadataField {
+    pub(crate) fn read(self, user: &UserMetadata) -> Map<String, Value> {
+        let stored = match self {
+            MetadataField::Metadata => user.metadata.as_ref(),
+            MetadataField::Properties => user.properties.as_ref(),
+        };
+        stored
+            .map(|values| values.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default()
+    }
+}
+
+fn to_object<T: Serialize>(value: &T) -> Result<Map<String, Value>, ModifyMetadataError> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(object)) => Ok(object),
+        Ok(_) => Err(ModifyMetadataError::Serialization(
+            "the type must serialize to a JSON object".to_string(),
+        )),
+        Err(err) => Err(ModifyMetadataError::Serialization(err.to_string())),
+    }
+}
+
+impl<'a> UserService<'a> {
+    /// Reads the user's metadata as a `T`, lets `modify` change it, and writes back the keys
+    /// whose values changed. PropelAuth replaces the metadata as a whole when it's updated, so
+    /// the changes are applied to the metadata as it was read and all of it is sent, which
+    /// keeps keys that `T` doesn't know about as they were.
+    ///
+    /// `T` is deserialized from the whole metadata object, so fields missing from it need
+    /// `#[serde(default)]`. A field that serializes to nothing after the change, such as an
+    /// `Option` with `skip_serializing_if`, is removed.
+    pub async fn modify_user_metadata<T, F>(
+        &self,
+        user_id: UserId,
+        modify: F,
+    ) -> Result<ModifiedMetadata<T>, ModifyMetadataError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnMut(&mut T),
+    {
+        self.modify_user_metadata_with(user_id, ModifyMetadataOptions::default(), modify)
+            .await
+    }
+
+    /// Like [`modify_user_metadata`](Self::modify_user_metadata), but for the user's properties
+    pub async fn modify_user_properties<T, F>(
+        &self,
+        user_id: UserId,
+        modify: F,
+    ) -> Result<ModifiedMetadata<T>, ModifyMetadataError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnMut(&mut T),
+    {
+        let options = ModifyMetadataOptions {
+            field: MetadataField::Properties,
+            ..Default::default()
+        };
+        self.modify_user_metadata_with(user_id, options, modify)
+            .await
+    }
+
+    /// Like [`modify_user_metadata`](Self::modify_user_metadata), with control over which field
+    /// is modified and how conflicting writes are handled. `modify` is called again for each
+    /// retry after a conflict.
+    pub async fn modify_user_metadata_with<T, F>(
+        &self,
+        user_id: UserId,
+        options: ModifyMetadataOptions,
+        mut modify: F,
+    ) -> Result<ModifiedMetadata<T>, ModifyMetadataError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnMut(&mut T),
+    {
+        let user_id = user_id.to_string();
+        let mut attempt = 0;
+        loop {
+            let stored = self.read_metadata_field(&user_id, options.field).await?;
+            let mut value: T = serde_json::from_value(Value::Object(stored.clone()))
+                .map_err(|err| ModifyMetadataError::Serialization(err.to_string()))?;
+            let before = to_object(&value)?;
+            modify(&mut value);
+            let diff = MetadataDiff::between(&before, &to_object(&value)?);
+            if diff.is_empty() {
+                return Ok(ModifiedMetadata { value, diff });
+            }
+
+            if options.detect_conflicts {
+                let current = self.read_metadata_field(&user_id, options.field).await?;
+                if current != stored {
+                    if attempt >= options.max_conflict_retries {
+                        return Err(ModifyMetadataError::Conflict);
+                    }
+                    attempt += 1;
+                    continue;
+                }
+            }
+
+            let update = diff.apply_to(&stored);
+            let update_metadata_request = match options.field {
+                MetadataField::Metadata => UpdateMetadataRequest {
+                    metadata: Some(update),
+                    ..Default::default()
+                },
+                MetadataField::Properties => UpdateMetadataRequest {
+                    properties: Some(update),
+                    ..Default::default()
+                },
+            };
+            self.update_user_metadata(UpdateUserMetadataParams {
+                user_id,
+                update_metadata_request,
+            })
+            .await
+            .map_err(|err| match err {
+                UpdateUserMetadataError::InvalidApiKey => ModifyMetadataError::InvalidApiKey,
+                UpdateUserMetadataError::PropelAuthRateLimit => {
+                    ModifyMetadataError::PropelAuthRateLimit
+                }
+                UpdateUserMetadataError::BadRequest(bad_request) => {
+                    ModifyMetadataError::BadRequest(bad_request)
+                }
+                UpdateUserMetadataError::NotFound => ModifyMetadataError::NotFound,
+                UpdateUserMetadataError::UnexpectedException => {
+                    ModifyMetadataError::UnexpectedException
+                }
+            })?;
+            return Ok(ModifiedMetadata { value, diff });
+        }
+    }
+
+    async fn read_metadata_field(
+        &self,
+        user_id: &str,
+        field: MetadataField,
+    ) -> Result<Map<String, Value>, ModifyMetadataError> {
+        let user = self
+            .fetch_user_by_id_uncached(FetchUserByIdParams {
+                user_id: user_id.to_string(),
+                include_orgs: None,
+            })
+            .await
+            .map_err(|err| match err {
+                ErrorsWithNotFound::InvalidApiKey => ModifyMetadataError::InvalidApiKey,
+                ErrorsWithNotFound::PropelAuthRateLimit => ModifyMetadataError::PropelAuthRateLimit,
+                ErrorsWithNotFound::NotFound => ModifyMetadataError::NotFound,
+                ErrorsWithNotFound::UnexpectedException => ModifyMetadataError::UnexpectedException,
+            })?;
+        Ok(field.read(&user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::{json, Map, Value};
+
+    use crate::propelauth::metadata::MetadataDiff;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(object) => object,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn diffs_only_changed_keys() {
+        let before = object(json!({"plan": "free", "seats": 3, "trial_ends": 100}));
+        let after = object(json!({"plan": "pro", "seats": 3, "tags": ["beta"]}));
+        let diff = MetadataDiff::between(&before, &after);
+        assert_eq!(
+            MetadataDiff {
+                set: HashMap::from([
+                    ("plan".to_string(), json!("pro")),
+                    ("tags".to_string(), json!(["beta"])),
+                ]),
+                removed: vec!["trial_ends".to_string()],
+            },
+            diff
+        );
+        assert_eq!(
+            HashMap::from([
+                ("plan".to_string(), json!("pro")),
+                ("seats".to_string(), json!(3)),
+                ("tags".to_string(), json!(["beta"])),
+            ]),
+            diff.apply_to(&before)
+        );
+        assert!(MetadataDiff::between(&before, &before).is_empty());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn modifies_typed_metadata_and_retries_on_conflict() {
+        use std::cell::Cell;
+        use std::sync::{Arc, Mutex};
+
+        use reqwest::{Method, Request};
+
+        use crate::apis::interceptor::Interceptor;
+
+        use crate::apis::user_service_api::UpdateUserMetadataParams;
+        use crate::models::{CreateUserRequest, UpdateMetadataRequest};
+        use crate::propelauth::errors::ModifyMetadataError;
+        use crate::propelauth::metadata::ModifyMetadataOptions;
+        use crate::testing::FakeBackend;
+
+        #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+        struct Billing {
+            #[serde(default)]
+            plan: String,
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            trial_ends: Option<i64>,
+        }
+
+        /// Records the body of every metadata update
+        struct CaptureUpdates(Arc<Mutex<Vec<Value>>>);
+        impl Interceptor for CaptureUpdates {
+            fn before_send(&self, request: &mut Request) {
+                if request.method() == Method::PUT {
+                    let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+                    let body = serde_json::from_slice(body).unwrap();
+                    self.0.lock().unwrap().push(body);
+                }
+            }
+        }
+
+        let fake = FakeBackend::start();
+        let updates = Arc::new(Mutex::new(vec![]));
+        let auth = fake
+            .auth()
+            .with_interceptor(CaptureUpdates(updates.clone()));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            let stored = || {
+                fake.users()
+                    .into_iter()
+                    .find(|user| user.user_id == user_id)
+                    .unwrap()
+                    .metadata
+                    .unwrap_or_default()
+            };
+            auth.user()
+                .update_user_metadata(UpdateUserMetadataParams {
+                    user_id: user_id.clone(),
+                    update_metadata_request: UpdateMetadataRequest {
+                        metadata: Some(HashMap::from([
+                            ("plan".to_string(), json!("free")),
+                            ("trial_ends".to_string(), json!(100)),
+                            ("theme".to_string(), json!("dark")),
+                        ])),
+                        ..Default::default()
+                    },
+                })
+                .await
+                .unwrap();
+
+            let modified = auth
+                .user()
+                .modify_user_metadata(user_id.parse().unwrap(), |billing: &mut Billing| {
+                    billing.plan = "pro".to_string();
+                    billing.trial_ends = None;
+                })
+                .await
+                .unwrap();
+            assert_eq!(vec!["trial_ends".to_string()], modified.diff.removed);
+            // PropelAuth replaces the metadata, so all of it is sent, unknown keys included
+            assert_eq!(
+                Some(json!({"metadata": {"plan": "pro", "theme": "dark"}})),
+                updates.lock().unwrap().pop()
+            );
+            assert_eq!(
+                HashMap::from([
+                    ("plan".to_string(), json!("pro")),
+                    ("theme".to_string(), json!("dark")),
+                ]),
+                stored()
+            );
+
+            // Another writer changes the metadata between every read and write
+            let options = ModifyMetadataOptions {
+                detect_conflicts: true,
+                max_conflict_retries: 2,
+                ..Default::default()
+            };
+            let calls = Cell::new(0);
+            let result = auth
+                .user()
+                .modify_user_metadata_with(
+                    user_id.parse().unwrap(),
+                    options.clone(),
+                    |b: &mut Billing| {
+                        calls.set(calls.get() + 1);
+                        fake.modify_user(&user_id, |user| {
+                            let metadata = user.metadata.get_or_insert_with(HashMap::new);
+                            metadata.insert("theme".to_string(), json!(calls.get()));
+                        });
+                        b.plan = "enterprise".to_string();
+                    },
+                )
+                .await;
+            assert_eq!(Err(ModifyMetadataError::Conflict), result);
+            assert_eq!(3, calls.get());
+            assert_eq!(Some(&json!("pro")), stored().get("plan"));
+
+            // Once the other writer stops, the retry goes through
+            let calls = Cell::new(0);
+            auth.user()
+                .modify_user_metadata_with(user_id.parse().unwrap(), options, |b: &mut Billing| {
+                    calls.set(calls.get() + 1);
+                    if calls.get() == 1 {
+                        fake.modify_user(&user_id, |user| {
+                            let metadata = user.metadata.get_or_insert_with(HashMap::new);
+                            metadata.insert("theme".to_string(), json!("light"));
+                        });
+                    }
+                    b.plan = "enterprise".to_string();
+                })
+                .await
+                .unwrap();
+            assert_eq!(2, calls.get());
+            assert_eq!(
+                HashMap::from([
+                    ("plan".to_string(), json!("enterprise")),
+                    ("theme".to_string(), json!("light")),
+                ]),
+                stored()
+            );
+        });
+    }
+}
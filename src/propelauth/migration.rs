@@ -0,0 +1,472 @@
+//! Bulk migrations of user or org metadata, such as renaming or reshaping keys across every
+//! user in a project.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use futures_util::stream::{Stream, StreamExt, TryStreamExt};
+use serde_json::{Map, Value};
+
+use crate::apis::org_service_api::FetchOrgsByQueryParams;
+use crate::apis::user_service_api::{FetchUsersByQueryParams, UpdateUserMetadataParams};
+use crate::models::{UpdateMetadataRequest, UpdateOrgRequest};
+use crate::propelauth::errors::{MetadataMigrationError, UpdateOrgError};
+use crate::propelauth::metadata::{MetadataDiff, MetadataField};
+use crate::propelauth::org::OrgService;
+use crate::propelauth::pagination::PaginationOptions;
+use crate::propelauth::user::UserService;
+
+#[derive(Debug, Clone)]
+pub struct MigrationOptions {
+    /// Only compute the changes, without writing them. Progress isn't recorded on a dry run.
+    pub dry_run: bool,
+    /// How many writes can be in flight at once
+    pub concurrency: usize,
+    /// A JSONL file recording the outcome for each user or org. Those that were already
+    /// migrated or needed no change are skipped when the migration is run again with the
+    /// same file, while failures are retried.
+    pub progress_path: Option<PathBuf>,
+    pub pagination: PaginationOptions,
+}
+
+impl Default for MigrationOptions {
+    fn default() -> Self {
+        MigrationOptions {
+            dry_run: false,
+            concurrency: 4,
+            progress_path: None,
+            pagination: PaginationOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStatus {
+    Migrated,
+    /// The transform didn't change anything
+    Unchanged,
+    /// The changes weren't written because this is a dry run
+    DryRun,
+    Failed(String),
+}
+
+/// The outcome of migrating a single user or org
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    /// The user or org ID
+    pub id: String,
+    pub status: MigrationStatus,
+    pub diff: MetadataDiff,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MigrationReport {
+    pub migrated: usize,
+    pub unchanged: usize,
+    pub dry_run: usize,
+    pub failed: usize,
+    /// Already done according to the progress file
+    pub skipped: usize,
+    /// Every user or org the transform changed, including failures and dry runs
+    pub changes: Vec<MigrationRecord>,
+}
+
+impl<'a> UserService<'a> {
+    /// Applies `transform` to the metadata or properties of every user it changes something
+    /// for, and writes back the result. PropelAuth replaces metadata and properties as a whole
+    /// when they're updated, so the whole transformed object is sent and keys the transform
+    /// removes are gone afterwards.
+    ///
+    /// The migration only stops early if users can't be fetched or the progress file can't be
+    /// written. Failed writes are recorded and retried on the next run.
+    pub async fn run_metadata_migration<F>(
+        &self,
+        field: MetadataField,
+        options: MigrationOptions,
+        transform: F,
+    ) -> Result<MigrationReport, MetadataMigrationError>
+    where
+        F: Fn(&mut HashMap<String, Value>),
+    {
+        let items = self
+            .fetch_users_by_query_stream(
+                FetchUsersByQueryParams::default(),
+                options.pagination.clone(),
+            )
+            .map_ok(move |user| (user.user_id.clone(), field.read(&user)))
+            .map_err(MetadataMigrationError::FetchUsers);
+        run_migration(items, options, transform, |user_id, values| async move {
+            let update_metadata_request = match field {
+                MetadataField::Metadata => UpdateMetadataRequest {
+                    metadata: Some(values),
+                    ..Default::default()
+                },
+                MetadataField::Properties => UpdateMetadataRequest {
+                    properties: Some(values),
+                    ..Default::default()
+                },
+            };
+            self.update_user_metadata(UpdateUserMetadataParams {
+                user_id,
+                update_metadata_request,
+            })
+            .await
+            .map_err(|err| err.to_string())
+        })
+        .await
+    }
+}
+
+impl<'a> OrgService<'a> {
+    /// Applies `transform` to the metadata of every org, writing back the whole transformed
+    /// metadata. See [`UserService::run_metadata_migration`].
+    pub async fn run_metadata_migration<F>(
+        &self,
+        options: MigrationOptions,
+        transform: F,
+    ) -> Result<MigrationReport, MetadataMigrationError>
+    where
+        F: Fn(&mut HashMap<String, Value>),
+    {
+        let items = self
+            .fetch_orgs_by_query_stream(
+                FetchOrgsByQueryParams::default(),
+                options.pagination.clone(),
+            )
+            .map_ok(|org| (org.org_id, org.metadata.metadata.into_iter().collect()))
+            .map_err(MetadataMigrationError::FetchOrgs);
+        run_migration(items, options, transform, |org_id, values| async move {
+            let update_org_request = UpdateOrgRequest {
+                metadata: Some(values),
+                ..Default::default()
+            };
+            let org_id = org_id
+                .parse()
+                .map_err(|_| UpdateOrgError::NotFound.to_string())?;
+            self.update_org_by_id(org_id, update_org_request)
+                .await
+                .map_err(|err| err.to_string())
+        })
+        .await
+    }
+}
+
+async fn run_migration<S, F, W, Fut>(
+    items: S,
+    options: MigrationOptions,
+    transform: F,
+    write: W,
+) -> Result<MigrationReport, MetadataMigrationError>
+where
+    S: Stream<Item = Result<(String, Map<String, Value>), MetadataMigrationError>>,
+    F: Fn(&mut HashMap<String, Value>),
+    W: Fn(String, HashMap<String, Value>) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let (done, mut progress) = match &options.progress_path {
+        Some(path) => open_progress(path, options.dry_run)?,
+        None => (HashSet::new(), None),
+    };
+
+    let mut report = MigrationReport::default();
+    let skipped = Cell::new(0);
+    let dry_run = options.dry_run;
+    let migrate = |(id, before): (String, Map<String, Value>)| {
+        let mut after: HashMap<String, Value> = before.clone().into_iter().collect();
+        transform(&mut after);
+        let diff = MetadataDiff::between(&before, &after.clone().into_iter().collect());
+        let write = &write;
+        async move {
+            let status = if diff.is_empty() {
+                MigrationStatus::Unchanged
+            } else if dry_run {
+                MigrationStatus::DryRun
+            } else {
+                match write(id.clone(), after).await {
+                    Ok(()) => MigrationStatus::Migrated,
+                    Err(err) => MigrationStatus::Failed(err),
+                }
+            };
+            Ok(MigrationRecord { id, status, diff })
+        }
+    };
+
+    let mut records = Box::pin(
+        items
+            .try_filter(|(id, _)| {
+                let is_done = done.contains(id);
+                if is_done {
+                    skipped.set(skipped.get() + 1);
+                }
+                std::future::ready(!is_done)
+            })
+            .map_ok(migrate)
+            .try_buffer_unordered(options.concurrency.max(1)),
+    );
+    while let Some(record) = records.next().await {
+        let record = record?;
+        if let Some(progress) = &mut progress {
+            let mut line = serde_json::to_string(&record).map_err(io::Error::from)?;
+            line.push('\n');
+            progress.write_all(line.as_bytes())?;
+        }
+        match record.status {
+            MigrationStatus::Migrated => report.migrated += 1,
+            MigrationStatus::Unchanged => report.unchanged += 1,
+            MigrationStatus::DryRun => report.dry_run += 1,
+            MigrationStatus::Failed(_) => report.failed += 1,
+        }
+        if record.status != MigrationStatus::Unchanged {
+            report.changes.push(record);
+        }
+    }
+    report.skipped = skipped.get();
+    Ok(report)
+}
+
+/// Reads the IDs that previous runs finished with, and unless this is a dry run, opens the
+/// file for appending
+fn open_progress(path: &Path, dry_run: bool) -> io::Result<(HashSet<String>, Option<File>)> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
+        Err(err) => return Err(err),
+    };
+
+    // The last record for an ID wins, and a line cut short by a crash is ignored
+    let mut statuses = HashMap::new();
+    for line in String::from_utf8_lossy(&contents).lines() {
+        if let Ok(record) = serde_json::from_str::<MigrationRecord>(line) {
+            statuses.insert(record.id, record.status);
+        }
+    }
+    let done = statuses
+        .into_iter()
+        .filter(|(_, status)| {
+            matches!(
+                status,
+                MigrationStatus::Migrated | MigrationStatus::Unchanged
+            )
+        })
+        .map(|(id, _)| id)
+        .collect();
+    if dry_run {
+        return Ok((done, None));
+    }
+
+    let mut progress = OpenOptions::new().create(true).append(true).open(path)?;
+    if !contents.is_empty() && !contents.ends_with(b"\n") {
+        progress.write_all(b"\n")?;
+    }
+    Ok((done, Some(progress)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use std::future::ready;
+    use std::io::Write;
+
+    use futures_util::stream;
+    use serde_json::{json, Map, Value};
+
+    use crate::propelauth::migration::{
+        run_migration, MigrationOptions, MigrationRecord, MigrationStatus,
+    };
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn split_plan(metadata: &mut HashMap<String, Value>) {
+        if let Some(plan) = metadata.remove("plan") {
+            let (tier, seats) = plan.as_str().unwrap().split_once(':').unwrap();
+            metadata.insert("plan_tier".to_string(), json!(tier));
+            metadata.insert(
+                "plan_seats".to_string(),
+                json!(seats.parse::<i64>().unwrap()),
+            );
+        }
+    }
+
+    fn items(
+        items: Vec<(&'static str, Value)>,
+    ) -> impl futures_util::Stream<
+        Item = Result<
+            (String, Map<String, Value>),
+            crate::propelauth::errors::MetadataMigrationError,
+        >,
+    > {
+        stream::iter(items.into_iter().map(|(id, metadata)| match metadata {
+            Value::Object(metadata) => Ok((id.to_string(), metadata)),
+            _ => unreachable!(),
+        }))
+    }
+
+    #[test]
+    fn writes_changed_keys_and_resumes_from_progress() {
+        let progress_path =
+            std::env::temp_dir().join(format!("propelauth-migration-{}.jsonl", std::process::id()));
+        let records = || {
+            items(vec![
+                ("a", json!({"plan": "pro:5", "theme": "dark"})),
+                ("b", json!({"theme": "light"})),
+                ("c", json!({"plan": "free:1"})),
+            ])
+        };
+        let writes = RefCell::new(vec![]);
+        let rate_limited = Cell::new(false);
+        let write = |id: String, update: HashMap<String, Value>| {
+            if id == "c" && !rate_limited.replace(true) {
+                return ready(Err("Rate limited by PropelAuth".to_string()));
+            }
+            writes.borrow_mut().push((id, update));
+            ready(Ok(()))
+        };
+        let options = MigrationOptions {
+            concurrency: 1,
+            progress_path: Some(progress_path.clone()),
+            ..Default::default()
+        };
+
+        let dry_run = MigrationOptions {
+            dry_run: true,
+            ..options.clone()
+        };
+        let report = block_on(run_migration(records(), dry_run, split_plan, write)).unwrap();
+        assert_eq!(
+            (2, 1, 0),
+            (report.dry_run, report.unchanged, report.migrated)
+        );
+        assert_eq!(vec!["plan".to_string()], report.changes[0].diff.removed);
+        assert!(writes.borrow().is_empty());
+        assert!(!progress_path.exists());
+
+        // "c" fails the first time around
+        let report =
+            block_on(run_migration(records(), options.clone(), split_plan, write)).unwrap();
+        assert_eq!(
+            (1, 1, 1),
+            (report.migrated, report.unchanged, report.failed)
+        );
+        assert_eq!(
+            vec![(
+                "a".to_string(),
+                HashMap::from([
+                    ("plan_tier".to_string(), json!("pro")),
+                    ("plan_seats".to_string(), json!(5)),
+                    ("theme".to_string(), json!("dark")),
+                ])
+            )],
+            *writes.borrow()
+        );
+
+        // Only users or orgs that are still there count as skipped
+        let mut progress = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&progress_path)
+            .unwrap();
+        let deleted = r#"{"id":"deleted","status":"migrated","diff":{"set":{},"removed":[]}}"#;
+        writeln!(progress, "{}", deleted).unwrap();
+
+        let report = block_on(run_migration(records(), options, split_plan, write)).unwrap();
+        assert_eq!((1, 2, 0), (report.migrated, report.skipped, report.failed));
+        assert_eq!("c", writes.borrow()[1].0);
+
+        let progress = std::fs::read_to_string(&progress_path).unwrap();
+        let statuses: Vec<_> = progress
+            .lines()
+            .map(|line| serde_json::from_str::<MigrationRecord>(line).unwrap())
+            .map(|record| (record.id, record.status))
+            .collect();
+        assert_eq!(
+            vec![
+                ("a".to_string(), MigrationStatus::Migrated),
+                ("b".to_string(), MigrationStatus::Unchanged),
+                (
+                    "c".to_string(),
+                    MigrationStatus::Failed("Rate limited by PropelAuth".to_string())
+                ),
+                ("deleted".to_string(), MigrationStatus::Migrated),
+                ("c".to_string(), MigrationStatus::Migrated),
+            ],
+            statuses
+        );
+        std::fs::remove_file(&progress_path).unwrap();
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn migrates_org_metadata() {
+        use crate::models::{CreateOrgRequest, UpdateOrgRequest};
+        use crate::propelauth::pagination::PaginationOptions;
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let mut org_ids = vec![];
+            for name in ["Acme", "Beta"] {
+                let org_id = auth
+                    .org()
+                    .create_org(CreateOrgRequest {
+                        name: name.to_string(),
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap()
+                    .org_id;
+                org_ids.push(org_id.parse().unwrap());
+            }
+            auth.org()
+                .update_org_by_id(
+                    org_ids[0],
+                    UpdateOrgRequest {
+                        metadata: Some(HashMap::from([("plan".to_string(), json!("pro:5"))])),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+
+            let report = auth
+                .org()
+                .run_metadata_migration(
+                    MigrationOptions {
+                        pagination: PaginationOptions {
+                            page_size: Some(1),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    split_plan,
+                )
+                .await
+                .unwrap();
+            assert_eq!((1, 1), (report.migrated, report.unchanged));
+
+            let acme = auth.org().fetch_org_by_id(org_ids[0]).await.unwrap();
+            assert_eq!(
+                HashMap::from([
+                    ("plan_tier".to_string(), json!("pro")),
+                    ("plan_seats".to_string(), json!(5)),
+                ]),
+                acme.metadata.metadata
+            );
+        });
+    }
+}
@@ -1,10 +1,49 @@
+#[cfg(feature = "__reqwest")]
+pub mod access_token;
+#[cfg(feature = "__reqwest")]
 pub mod api_key;
 pub mod auth;
+#[cfg(feature = "__reqwest")]
+pub mod batch;
+#[cfg(feature = "__reqwest")]
+pub mod builders;
+#[cfg(feature = "__reqwest")]
+pub mod cache;
+#[cfg(feature = "__reqwest")]
+pub mod client;
+#[cfg(feature = "__reqwest")]
+pub mod coalesce;
+#[cfg(feature = "__reqwest")]
+pub mod erasure;
 pub mod errors;
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(feature = "__reqwest")]
 pub(crate) mod helpers;
+pub mod ids;
+#[cfg(feature = "import")]
+pub mod import;
+#[cfg(feature = "__reqwest")]
+pub mod legacy_id;
+#[cfg(feature = "__reqwest")]
+pub mod lifecycle;
+#[cfg(feature = "__reqwest")]
+pub mod metadata;
+#[cfg(feature = "__reqwest")]
+pub mod migration;
 pub mod options;
+#[cfg(feature = "__reqwest")]
 pub mod org;
+#[cfg(feature = "__reqwest")]
+pub mod pagination;
+pub mod password_hash;
+#[cfg(feature = "__reqwest")]
+pub mod suspension;
 pub mod token;
 pub mod token_models;
+#[cfg(feature = "__reqwest")]
+pub mod traits;
+#[cfg(feature = "__reqwest")]
 pub mod user;
-pub mod access_token;
+#[cfg(feature = "__reqwest")]
+pub mod user_data;
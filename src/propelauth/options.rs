@@ -19,6 +19,13 @@ pub struct AuthOptions {
     pub api_key: String,
 }
 
+/// Options for an instance that only verifies access tokens and never calls the backend API.
+/// The token verification metadata can be obtained from your PropelAuth project.
+pub struct VerifyOnlyAuthOptions {
+    pub auth_url: String,
+    pub token_verification_metadata: AuthTokenVerificationMetadata,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum RequiredOrg<'a> {
     OrgId(&'a str),
@@ -6,47 +6,80 @@ use crate::apis::org_service_api::{
     RemoveUserFromOrgParams, RevokePendingOrgInviteParams, SubscribeOrgToRoleMappingParams,
     UpdateOrgParams,
 };
+use crate::models::fetch_pending_invites::PendingInviteResponse;
 use crate::models::{
     AddUserToOrgRequest, ChangeUserRoleInOrgRequest, CreateOrgRequest, CreateOrgResponse,
-    CreateSamlConnectionLinkResponse, FetchOrgResponse, FetchOrgsResponse,
+    CreateSamlConnectionLinkResponse, FetchOrgBasicResponse, FetchOrgResponse, FetchOrgsResponse,
     FetchSamlSpMetadataResponse, InviteUserToOrgRequest, RemoveUserFromOrgRequest,
     RevokePendingOrgInviteRequest, SubscribeOrgToRoleMappingRequest, UpdateOrgRequest,
-    UserPagedResponse,
+    UserMetadata, UserPagedResponse,
 };
+use crate::propelauth::cache::{invalidating, read_cached, write_cached, CacheKey, CachedValue};
+use crate::propelauth::coalesce::{coalesced, coalescing_key};
 use crate::propelauth::errors::{
     CreateOrgError, ErrorsWithNotFound, FetchOrgsByQueryError, FetchUsersInOrgError,
     OrgMissingOrRoleError, UpdateOrgError,
 };
 use crate::propelauth::helpers::{is_valid_id, map_autogenerated_error};
+use crate::propelauth::ids::OrgId;
+use crate::propelauth::pagination::{paginate, Page, PaginationOptions};
 
 use super::errors::{InviteUserToOrgError, SubscribeOrgToRoleMappingError};
+use futures_util::stream::Stream;
 
 pub struct OrgService<'a> {
     pub(crate) config: &'a Configuration,
 }
 
-impl OrgService<'_> {
-    /// Fetch an organization by it's ID
+impl<'a> OrgService<'a> {
+    #[deprecated(note = "use `fetch_org_by_id`, which takes an `OrgId`")]
     pub async fn fetch_org(&self, org_id: String) -> Result<FetchOrgResponse, ErrorsWithNotFound> {
+        self.fetch_org_by_raw_id(org_id).await
+    }
+
+    /// Fetch an organization by it's ID
+    pub async fn fetch_org_by_id(
+        &self,
+        org_id: OrgId,
+    ) -> Result<FetchOrgResponse, ErrorsWithNotFound> {
+        self.fetch_org_by_raw_id(org_id.to_string()).await
+    }
+
+    /// Sends `org_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn fetch_org_by_raw_id(
+        &self,
+        org_id: String,
+    ) -> Result<FetchOrgResponse, ErrorsWithNotFound> {
         if !is_valid_id(&org_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        let params = FetchOrgParams { org_id };
-        crate::apis::org_service_api::fetch_org(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })
+        let cache_key = CacheKey::org(&org_id);
+        if let Some(CachedValue::Org(org)) = read_cached(self.config, &cache_key).await {
+            return Ok(org);
+        }
+
+        let key = coalescing_key(self.config, "fetch_org", &org_id);
+        let org = coalesced(self.config, key, async move {
+            let params = FetchOrgParams { org_id };
+            crate::apis::org_service_api::fetch_org(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })
+        })
+        .await?;
+        write_cached(self.config, cache_key, CachedValue::Org(org.clone())).await;
+        Ok(org)
     }
 
     /// Fetch and page over organizations
@@ -54,169 +87,294 @@ impl OrgService<'_> {
         &self,
         params: FetchOrgsByQueryParams,
     ) -> Result<FetchOrgsResponse, FetchOrgsByQueryError> {
-        let response = crate::apis::org_service_api::fetch_orgs_by_query(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    FetchOrgsByQueryError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (
-                            _,
-                            Some(crate::apis::org_service_api::FetchOrgsByQueryError::Status400(
-                                bad_request,
-                            )),
-                        ) => FetchOrgsByQueryError::BadRequest(bad_request),
-                        (401, _) => FetchOrgsByQueryError::InvalidApiKey,
-                        (429, _) => FetchOrgsByQueryError::PropelAuthRateLimit,
-                        _ => FetchOrgsByQueryError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(response)
+        let key = coalescing_key(self.config, "fetch_orgs_by_query", &params);
+        coalesced(self.config, key, async move {
+            let response = crate::apis::org_service_api::fetch_orgs_by_query(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        FetchOrgsByQueryError::UnexpectedException,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (
+                                _,
+                                Some(
+                                    crate::apis::org_service_api::FetchOrgsByQueryError::Status400(
+                                        bad_request,
+                                    ),
+                                ),
+                            ) => FetchOrgsByQueryError::BadRequest(bad_request),
+                            (401, _) => FetchOrgsByQueryError::InvalidApiKey,
+                            (429, _) => FetchOrgsByQueryError::PropelAuthRateLimit,
+                            _ => FetchOrgsByQueryError::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(response)
+        })
+        .await
+    }
+
+    /// Lazily walks every page of [`fetch_orgs_by_query`](Self::fetch_orgs_by_query), one org at a time
+    pub fn fetch_orgs_by_query_stream(
+        &self,
+        params: FetchOrgsByQueryParams,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<FetchOrgBasicResponse, FetchOrgsByQueryError>> + 'a {
+        let config = self.config;
+        paginate(
+            params.page_number.unwrap_or(0),
+            options.page_size.or(params.page_size),
+            options.prefetch,
+            move |page_number, page_size| {
+                let params = FetchOrgsByQueryParams {
+                    page_number: Some(page_number),
+                    page_size,
+                    ..params.clone()
+                };
+                async move {
+                    let response = OrgService { config }.fetch_orgs_by_query(params).await?;
+                    Ok(Page {
+                        items: response.orgs,
+                        has_more_results: response.has_more_results,
+                    })
+                }
+            },
+        )
     }
 
     pub async fn fetch_users_in_org(
         &self,
         params: FetchUsersInOrgParams,
     ) -> Result<UserPagedResponse, FetchUsersInOrgError> {
-        if !is_valid_id(&params.org_id) {
-            return Ok(UserPagedResponse::default());
-        }
+        let key = coalescing_key(self.config, "fetch_users_in_org", &params);
+        coalesced(self.config, key, async move {
+            if !is_valid_id(&params.org_id) {
+                return Ok(UserPagedResponse::default());
+            }
+
+            let response = crate::apis::org_service_api::fetch_users_in_org(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        FetchUsersInOrgError::UnexpectedException,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (
+                                _,
+                                Some(
+                                    crate::apis::org_service_api::FetchUsersInOrgError::Status400(
+                                        bad_request,
+                                    ),
+                                ),
+                            ) => FetchUsersInOrgError::BadRequest(bad_request),
+                            (401, _) => FetchUsersInOrgError::InvalidApiKey,
+                            (429, _) => FetchUsersInOrgError::PropelAuthRateLimit,
+                            _ => FetchUsersInOrgError::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(response)
+        })
+        .await
+    }
 
-        let response = crate::apis::org_service_api::fetch_users_in_org(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    FetchUsersInOrgError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (
-                            _,
-                            Some(crate::apis::org_service_api::FetchUsersInOrgError::Status400(
-                                bad_request,
-                            )),
-                        ) => FetchUsersInOrgError::BadRequest(bad_request),
-                        (401, _) => FetchUsersInOrgError::InvalidApiKey,
-                        (429, _) => FetchUsersInOrgError::PropelAuthRateLimit,
-                        _ => FetchUsersInOrgError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(response)
+    /// Lazily walks every page of [`fetch_users_in_org`](Self::fetch_users_in_org), one user at a time
+    pub fn fetch_users_in_org_stream(
+        &self,
+        params: FetchUsersInOrgParams,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<UserMetadata, FetchUsersInOrgError>> + 'a {
+        let config = self.config;
+        paginate(
+            params.page_number.unwrap_or(0),
+            options.page_size.or(params.page_size),
+            options.prefetch,
+            move |page_number, page_size| {
+                let params = FetchUsersInOrgParams {
+                    page_number: Some(page_number),
+                    page_size,
+                    ..params.clone()
+                };
+                async move {
+                    let response = OrgService { config }.fetch_users_in_org(params).await?;
+                    Ok(Page {
+                        items: response.users,
+                        has_more_results: response.has_more_results,
+                    })
+                }
+            },
+        )
     }
 
     pub async fn fetch_custom_role_mappings(
         &self,
     ) -> Result<crate::models::FetchCustomRoleMappingsResponse, ErrorsWithNotFound> {
-        crate::apis::org_service_api::fetch_custom_role_mappings(&self.config)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })
+        let key = coalescing_key(self.config, "fetch_custom_role_mappings", &());
+        coalesced(self.config, key, async move {
+            crate::apis::org_service_api::fetch_custom_role_mappings(self.config)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })
+        })
+        .await
     }
 
     pub async fn fetch_pending_invites(
         &self,
         params: FetchPendingInvitesParams,
     ) -> Result<crate::models::FetchPendingInvitesResponse, ErrorsWithNotFound> {
-        crate::apis::org_service_api::fetch_pending_invites(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })
+        let key = coalescing_key(self.config, "fetch_pending_invites", &params);
+        coalesced(self.config, key, async move {
+            crate::apis::org_service_api::fetch_pending_invites(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })
+        })
+        .await
+    }
+
+    /// Lazily walks every page of [`fetch_pending_invites`](Self::fetch_pending_invites), one invite at a time
+    pub fn fetch_pending_invites_stream(
+        &self,
+        params: FetchPendingInvitesParams,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<PendingInviteResponse, ErrorsWithNotFound>> + 'a {
+        let config = self.config;
+        paginate(
+            params.page_number.unwrap_or(0),
+            options.page_size.or(params.page_size),
+            options.prefetch,
+            move |page_number, page_size| {
+                let params = FetchPendingInvitesParams {
+                    page_number: Some(page_number),
+                    page_size,
+                    ..params.clone()
+                };
+                async move {
+                    let response = OrgService { config }.fetch_pending_invites(params).await?;
+                    Ok(Page {
+                        items: response.invites,
+                        has_more_results: response.has_more_results,
+                    })
+                }
+            },
+        )
     }
 
     pub async fn add_user_to_org(
         &self,
         add_user_to_org_request: AddUserToOrgRequest,
     ) -> Result<(), OrgMissingOrRoleError> {
-        let params = AddUserToOrgParams {
-            add_user_to_org_request,
-        };
-        crate::apis::org_service_api::add_user_to_org(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    OrgMissingOrRoleError::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        400 => OrgMissingOrRoleError::UnknownRoleError,
-                        401 => OrgMissingOrRoleError::InvalidApiKey,
-                        429 => OrgMissingOrRoleError::PropelAuthRateLimit,
-                        404 => OrgMissingOrRoleError::NotFound,
-                        _ => OrgMissingOrRoleError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(
+            self.config,
+            CacheKey::for_user(&add_user_to_org_request.user_id),
+            async move {
+                let params = AddUserToOrgParams {
+                    add_user_to_org_request,
+                };
+                crate::apis::org_service_api::add_user_to_org(self.config, params)
+                    .await
+                    .map_err(|err| {
+                        map_autogenerated_error(
+                            err,
+                            OrgMissingOrRoleError::UnexpectedException,
+                            |status_code, _| match status_code.as_u16() {
+                                400 => OrgMissingOrRoleError::UnknownRoleError,
+                                401 => OrgMissingOrRoleError::InvalidApiKey,
+                                429 => OrgMissingOrRoleError::PropelAuthRateLimit,
+                                404 => OrgMissingOrRoleError::NotFound,
+                                _ => OrgMissingOrRoleError::UnexpectedException,
+                            },
+                        )
+                    })?;
+                Ok(())
+            },
+        )
+        .await
     }
 
     pub async fn change_user_role_in_org(
         &self,
         change_user_role_in_org_request: ChangeUserRoleInOrgRequest,
     ) -> Result<(), OrgMissingOrRoleError> {
-        let params = ChangeUserRoleInOrgParams {
-            change_user_role_in_org_request,
-        };
-        crate::apis::org_service_api::change_user_role_in_org(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    OrgMissingOrRoleError::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        400 => OrgMissingOrRoleError::UnknownRoleError,
-                        401 => OrgMissingOrRoleError::InvalidApiKey,
-                        429 => OrgMissingOrRoleError::PropelAuthRateLimit,
-                        404 => OrgMissingOrRoleError::NotFound,
-                        _ => OrgMissingOrRoleError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(
+            self.config,
+            CacheKey::for_user(&change_user_role_in_org_request.user_id),
+            async move {
+                let params = ChangeUserRoleInOrgParams {
+                    change_user_role_in_org_request,
+                };
+                crate::apis::org_service_api::change_user_role_in_org(self.config, params)
+                    .await
+                    .map_err(|err| {
+                        map_autogenerated_error(
+                            err,
+                            OrgMissingOrRoleError::UnexpectedException,
+                            |status_code, _| match status_code.as_u16() {
+                                400 => OrgMissingOrRoleError::UnknownRoleError,
+                                401 => OrgMissingOrRoleError::InvalidApiKey,
+                                429 => OrgMissingOrRoleError::PropelAuthRateLimit,
+                                404 => OrgMissingOrRoleError::NotFound,
+                                _ => OrgMissingOrRoleError::UnexpectedException,
+                            },
+                        )
+                    })?;
+                Ok(())
+            },
+        )
+        .await
     }
 
     pub async fn remove_user_from_org(
         &self,
         remove_user_from_org_request: RemoveUserFromOrgRequest,
     ) -> Result<(), ErrorsWithNotFound> {
-        let params = RemoveUserFromOrgParams {
-            remove_user_from_org_request,
-        };
-        crate::apis::org_service_api::remove_user_from_org(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(
+            self.config,
+            CacheKey::for_user(&remove_user_from_org_request.user_id),
+            async move {
+                let params = RemoveUserFromOrgParams {
+                    remove_user_from_org_request,
+                };
+                crate::apis::org_service_api::remove_user_from_org(self.config, params)
+                    .await
+                    .map_err(|err| {
+                        map_autogenerated_error(
+                            err,
+                            ErrorsWithNotFound::UnexpectedException,
+                            |status_code, _| match status_code.as_u16() {
+                                401 => ErrorsWithNotFound::InvalidApiKey,
+                                429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                                404 => ErrorsWithNotFound::NotFound,
+                                _ => ErrorsWithNotFound::UnexpectedException,
+                            },
+                        )
+                    })?;
+                Ok(())
+            },
+        )
+        .await
     }
 
     pub async fn create_org(
@@ -224,7 +382,7 @@ impl OrgService<'_> {
         create_org_request: CreateOrgRequest,
     ) -> Result<CreateOrgResponse, CreateOrgError> {
         let params = CreateOrgParams { create_org_request };
-        let org = crate::apis::org_service_api::create_org(&self.config, params)
+        let org = crate::apis::org_service_api::create_org(self.config, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -246,40 +404,67 @@ impl OrgService<'_> {
         Ok(org)
     }
 
+    #[deprecated(note = "use `update_org_by_id`, which takes an `OrgId`")]
     pub async fn update_org(
         &self,
         org_id: String,
         update_org_request: UpdateOrgRequest,
+    ) -> Result<(), UpdateOrgError> {
+        self.update_org_by_raw_id(org_id, update_org_request).await
+    }
+
+    pub async fn update_org_by_id(
+        &self,
+        org_id: OrgId,
+        update_org_request: UpdateOrgRequest,
+    ) -> Result<(), UpdateOrgError> {
+        self.update_org_by_raw_id(org_id.to_string(), update_org_request)
+            .await
+    }
+
+    /// Sends `org_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn update_org_by_raw_id(
+        &self,
+        org_id: String,
+        update_org_request: UpdateOrgRequest,
     ) -> Result<(), UpdateOrgError> {
         if !is_valid_id(&org_id) {
             return Err(UpdateOrgError::NotFound);
         }
 
-        let params = UpdateOrgParams {
-            org_id,
-            update_org_request,
-        };
-        crate::apis::org_service_api::update_org(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    UpdateOrgError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (
-                            _,
-                            Some(crate::apis::org_service_api::UpdateOrgError::Status400(
-                                bad_request,
-                            )),
-                        ) => UpdateOrgError::BadRequest(bad_request),
-                        (401, _) => UpdateOrgError::InvalidApiKey,
-                        (429, _) => UpdateOrgError::PropelAuthRateLimit,
-                        (404, _) => UpdateOrgError::NotFound,
-                        _ => UpdateOrgError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        let mut cache_keys = CacheKey::for_org(&org_id);
+        if let Some(legacy_org_id) = &update_org_request.legacy_org_id {
+            let legacy_cache_keys = self.legacy_cache_keys(&org_id, Some(legacy_org_id)).await;
+            cache_keys.extend(legacy_cache_keys);
+        }
+        invalidating(self.config, cache_keys, async move {
+            let params = UpdateOrgParams {
+                org_id,
+                update_org_request,
+            };
+            crate::apis::org_service_api::update_org(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        UpdateOrgError::UnexpectedException,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (
+                                _,
+                                Some(crate::apis::org_service_api::UpdateOrgError::Status400(
+                                    bad_request,
+                                )),
+                            ) => UpdateOrgError::BadRequest(bad_request),
+                            (401, _) => UpdateOrgError::InvalidApiKey,
+                            (429, _) => UpdateOrgError::PropelAuthRateLimit,
+                            (404, _) => UpdateOrgError::NotFound,
+                            _ => UpdateOrgError::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
     pub async fn subscribe_org_to_role_mapping(
@@ -287,87 +472,96 @@ impl OrgService<'_> {
         org_id: String,
         custom_role_mapping_name: String,
     ) -> Result<(), SubscribeOrgToRoleMappingError> {
-        if !is_valid_id(&org_id) {
-            return Err(SubscribeOrgToRoleMappingError::NotFound);
-        }
-
-        let params = SubscribeOrgToRoleMappingParams {
-            org_id,
-            update_org_request: SubscribeOrgToRoleMappingRequest {
-                custom_role_mapping_name,
-            },
-        };
-        crate::apis::org_service_api::subscribe_org_to_role_mapping(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    SubscribeOrgToRoleMappingError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (
-                            _,
-                            Some(crate::apis::org_service_api::SubscribeOrgToRoleMappingError::Status400(
-                                bad_request,
-                            )),
-                        ) => SubscribeOrgToRoleMappingError::BadRequest(bad_request),
-                        (401, _) => SubscribeOrgToRoleMappingError::InvalidApiKey,
-                        (429, _) => SubscribeOrgToRoleMappingError::PropelAuthRateLimit,
-                        (404, _) => SubscribeOrgToRoleMappingError::NotFound,
-                        _ => SubscribeOrgToRoleMappingError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(self.config, CacheKey::for_org(&org_id), async move {
+            if !is_valid_id(&org_id) {
+                return Err(SubscribeOrgToRoleMappingError::NotFound);
+            }
+
+            let params = SubscribeOrgToRoleMappingParams {
+                org_id,
+                update_org_request: SubscribeOrgToRoleMappingRequest {
+                    custom_role_mapping_name,
+                },
+            };
+            crate::apis::org_service_api::subscribe_org_to_role_mapping(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        SubscribeOrgToRoleMappingError::UnexpectedException,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (
+                                _,
+                                Some(crate::apis::org_service_api::SubscribeOrgToRoleMappingError::Status400(
+                                    bad_request,
+                                )),
+                            ) => SubscribeOrgToRoleMappingError::BadRequest(bad_request),
+                            (401, _) => SubscribeOrgToRoleMappingError::InvalidApiKey,
+                            (429, _) => SubscribeOrgToRoleMappingError::PropelAuthRateLimit,
+                            (404, _) => SubscribeOrgToRoleMappingError::NotFound,
+                            _ => SubscribeOrgToRoleMappingError::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
     pub async fn allow_org_to_enable_saml(
         &self,
         params: AllowOrgToEnableSamlParams,
     ) -> Result<(), ErrorsWithNotFound> {
-        if !is_valid_id(&params.org_id) {
-            return Err(ErrorsWithNotFound::NotFound);
-        }
-
-        crate::apis::org_service_api::allow_org_to_enable_saml(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(self.config, CacheKey::for_org(&params.org_id), async move {
+            if !is_valid_id(&params.org_id) {
+                return Err(ErrorsWithNotFound::NotFound);
+            }
+
+            crate::apis::org_service_api::allow_org_to_enable_saml(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
     pub async fn disallow_saml(
         &self,
         params: DisallowSamlParams,
     ) -> Result<(), ErrorsWithNotFound> {
-        if !is_valid_id(&params.org_id) {
-            return Err(ErrorsWithNotFound::NotFound);
-        }
-
-        crate::apis::org_service_api::disallow_saml(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(self.config, CacheKey::for_org(&params.org_id), async move {
+            if !is_valid_id(&params.org_id) {
+                return Err(ErrorsWithNotFound::NotFound);
+            }
+
+            crate::apis::org_service_api::disallow_saml(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
     pub async fn create_saml_connection_link(
@@ -378,7 +572,7 @@ impl OrgService<'_> {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        crate::apis::org_service_api::create_saml_connection_link(&self.config, params)
+        crate::apis::org_service_api::create_saml_connection_link(self.config, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -398,117 +592,182 @@ impl OrgService<'_> {
         &self,
         org_id: String,
     ) -> Result<FetchSamlSpMetadataResponse, ErrorsWithNotFound> {
-        if !is_valid_id(&org_id) {
-            return Err(ErrorsWithNotFound::NotFound);
-        }
-
-        crate::apis::org_service_api::fetch_saml_sp_metadata(&self.config, org_id)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })
+        let key = coalescing_key(self.config, "fetch_saml_sp_metadata", &org_id);
+        coalesced(self.config, key, async move {
+            if !is_valid_id(&org_id) {
+                return Err(ErrorsWithNotFound::NotFound);
+            }
+
+            crate::apis::org_service_api::fetch_saml_sp_metadata(self.config, org_id)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })
+        })
+        .await
     }
 
     pub async fn set_saml_idp_metadata(
         &self,
         request: crate::models::SetSamlIdpMetadataRequest,
     ) -> Result<(), ErrorsWithNotFound> {
-        if !is_valid_id(&request.org_id) {
-            return Err(ErrorsWithNotFound::NotFound);
-        }
-
-        crate::apis::org_service_api::set_saml_idp_metadata(&self.config, request)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(
+            self.config,
+            CacheKey::for_org(&request.org_id),
+            async move {
+                if !is_valid_id(&request.org_id) {
+                    return Err(ErrorsWithNotFound::NotFound);
+                }
+
+                crate::apis::org_service_api::set_saml_idp_metadata(self.config, request)
+                    .await
+                    .map_err(|err| {
+                        map_autogenerated_error(
+                            err,
+                            ErrorsWithNotFound::UnexpectedException,
+                            |status_code, _| match status_code.as_u16() {
+                                401 => ErrorsWithNotFound::InvalidApiKey,
+                                429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                                404 => ErrorsWithNotFound::NotFound,
+                                _ => ErrorsWithNotFound::UnexpectedException,
+                            },
+                        )
+                    })?;
+                Ok(())
+            },
+        )
+        .await
     }
 
+    #[deprecated(note = "use `saml_go_live_by_id`, which takes an `OrgId`")]
     pub async fn saml_go_live(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.saml_go_live_by_raw_id(org_id).await
+    }
+
+    pub async fn saml_go_live_by_id(&self, org_id: OrgId) -> Result<(), ErrorsWithNotFound> {
+        self.saml_go_live_by_raw_id(org_id.to_string()).await
+    }
+
+    /// Sends `org_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn saml_go_live_by_raw_id(
+        &self,
+        org_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
         if !is_valid_id(&org_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        crate::apis::org_service_api::saml_go_live(&self.config, org_id)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(self.config, CacheKey::for_org(&org_id), async move {
+            crate::apis::org_service_api::saml_go_live(self.config, org_id)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
+    #[deprecated(note = "use `delete_saml_connection_by_id`, which takes an `OrgId`")]
     pub async fn delete_saml_connection(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.delete_saml_connection_by_raw_id(org_id).await
+    }
+
+    pub async fn delete_saml_connection_by_id(
+        &self,
+        org_id: OrgId,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.delete_saml_connection_by_raw_id(org_id.to_string())
+            .await
+    }
+
+    /// Sends `org_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn delete_saml_connection_by_raw_id(
+        &self,
+        org_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
         if !is_valid_id(&org_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        crate::apis::org_service_api::delete_saml_connection(&self.config, org_id)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(self.config, CacheKey::for_org(&org_id), async move {
+            crate::apis::org_service_api::delete_saml_connection(self.config, org_id)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
+    #[deprecated(note = "use `delete_org_by_id`, which takes an `OrgId`")]
     pub async fn delete_org(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.delete_org_by_raw_id(org_id).await
+    }
+
+    pub async fn delete_org_by_id(&self, org_id: OrgId) -> Result<(), ErrorsWithNotFound> {
+        self.delete_org_by_raw_id(org_id.to_string()).await
+    }
+
+    /// Sends `org_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn delete_org_by_raw_id(
+        &self,
+        org_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
         if !is_valid_id(&org_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        let params = DeleteOrgParams { org_id };
-
-        crate::apis::org_service_api::delete_org(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        let mut cache_keys = CacheKey::for_org(&org_id);
+        cache_keys.extend(self.legacy_cache_keys(&org_id, None).await);
+        invalidating(self.config, cache_keys, async move {
+            let params = DeleteOrgParams { org_id };
+
+            crate::apis::org_service_api::delete_org(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
     pub async fn invite_user_to_org(
@@ -520,7 +779,7 @@ impl OrgService<'_> {
             return Err(InviteUserToOrgError::NotFound);
         }
 
-        crate::apis::user_service_api::invite_user_to_org(&self.config, invite_user_to_org_request)
+        crate::apis::user_service_api::invite_user_to_org(self.config, invite_user_to_org_request)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -550,7 +809,7 @@ impl OrgService<'_> {
         let params = RevokePendingOrgInviteParams {
             revoke_pending_org_invite_request,
         };
-        crate::apis::org_service_api::revoke_pending_org_invite(&self.config, params)
+        crate::apis::org_service_api::revoke_pending_org_invite(self.config, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
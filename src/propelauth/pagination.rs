@@ -0,0 +1,189 @@
+use std::future::{ready, Future};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use futures_util::future::Either;
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
+
+/// Controls how the `*_stream` methods walk through pages
+#[derive(Debug, Clone, Default)]
+pub struct PaginationOptions {
+    /// The number of items requested per page. Falls back to the `page_size` in the params,
+    /// and then to the API's default.
+    pub page_size: Option<i64>,
+    /// How many pages to request ahead of the one being consumed. With the default of 0, the
+    /// next page is only requested once every item of the current one has been read.
+    pub prefetch: usize,
+}
+
+/// A single page, as returned by any of the paged endpoints
+pub(crate) struct Page<T> {
+    pub(crate) items: Vec<T>,
+    pub(crate) has_more_results: bool,
+}
+
+/// Walks pages starting from `first_page`, calling `fetch_page(page_number, page_size)` for
+/// each one, until a page says there are no more results or a request fails.
+pub(crate) fn paginate<'a, T, E, F, Fut>(
+    first_page: i64,
+    page_size: Option<i64>,
+    prefetch: usize,
+    fetch_page: F,
+) -> impl Stream<Item = Result<T, E>> + 'a
+where
+    T: 'a,
+    E: 'a,
+    F: FnMut(i64, Option<i64>) -> Fut + 'a,
+    Fut: Future<Output = Result<Page<T>, E>> + 'a,
+{
+    let mut fetch_page = fetch_page;
+    let done = Arc::new(AtomicBool::new(false));
+    let stop_requesting = done.clone();
+    stream::iter(first_page..)
+        .take_while(move |_| ready(!stop_requesting.load(Ordering::SeqCst)))
+        .map(move |page_number| fetch_page(page_number, page_size))
+        .buffered(prefetch + 1)
+        // Pages prefetched past the last one are dropped rather than yielded
+        .scan(done, |done, page| {
+            if done.load(Ordering::SeqCst) {
+                return ready(None);
+            }
+            let is_last_page = page.as_ref().map_or(true, |page| !page.has_more_results);
+            done.store(is_last_page, Ordering::SeqCst);
+            ready(Some(page))
+        })
+        .flat_map(|page| match page {
+            Ok(page) => Either::Left(stream::iter(page.items.into_iter().map(Ok))),
+            Err(err) => Either::Right(stream::once(ready(Err(err)))),
+        })
+}
+
+/// Collects every item of a `*_stream`, stopping once `max_items` have been read so that an
+/// unexpectedly large result set can't exhaust memory. No further pages are requested after
+/// the cap is reached.
+pub async fn collect_all<T, E, S>(stream: S, max_items: usize) -> Result<Vec<T>, E>
+where
+    S: Stream<Item = Result<T, E>>,
+{
+    stream.take(max_items).try_collect().await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::future::ready;
+
+    use futures_util::stream::StreamExt;
+
+    use crate::propelauth::pagination::{collect_all, paginate, Page};
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn pages_of_three(
+        requested: &Cell<Vec<i64>>,
+        last_page: i64,
+    ) -> impl FnMut(i64, Option<i64>) -> std::future::Ready<Result<Page<i64>, String>> + '_ {
+        move |page_number, _| {
+            let mut pages = requested.take();
+            pages.push(page_number);
+            requested.set(pages);
+            ready(Ok(Page {
+                items: (page_number * 3..page_number * 3 + 3).collect(),
+                has_more_results: page_number < last_page,
+            }))
+        }
+    }
+
+    #[test]
+    fn walks_pages_until_there_are_no_more_results() {
+        let requested = Cell::new(vec![]);
+        let items: Vec<Result<i64, String>> =
+            block_on(paginate(0, None, 0, pages_of_three(&requested, 2)).collect());
+        assert_eq!((0..9).map(Ok).collect::<Vec<_>>(), items);
+        assert_eq!(vec![0, 1, 2], requested.take());
+
+        let items: Vec<Result<i64, String>> =
+            block_on(paginate(1, Some(3), 0, pages_of_three(&requested, 2)).collect());
+        assert_eq!((3..9).map(Ok).collect::<Vec<_>>(), items);
+    }
+
+    #[test]
+    fn collect_all_stops_requesting_pages_at_the_cap() {
+        let requested = Cell::new(vec![]);
+        let items = block_on(collect_all(
+            paginate(0, None, 0, pages_of_three(&requested, 100)),
+            4,
+        ));
+        assert_eq!(Ok(vec![0, 1, 2, 3]), items);
+        assert_eq!(vec![0, 1], requested.take());
+    }
+
+    #[test]
+    fn stops_after_an_error() {
+        let stream = paginate(0, None, 2, |page_number, _| {
+            ready(if page_number == 1 {
+                Err("rate limited".to_string())
+            } else {
+                Ok(Page {
+                    items: vec![page_number],
+                    has_more_results: true,
+                })
+            })
+        });
+        let items: Vec<Result<i64, String>> = block_on(stream.collect());
+        assert_eq!(vec![Ok(0), Err("rate limited".to_string())], items);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn service_streams_walk_the_backend_pages() {
+        use crate::apis::user_service_api::FetchUsersByQueryParams;
+        use crate::models::CreateUserRequest;
+        use crate::propelauth::pagination::PaginationOptions;
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            for i in 0..25 {
+                auth.user()
+                    .create_user(CreateUserRequest {
+                        email: format!("user{}@example.com", i),
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap();
+            }
+
+            let users = auth.user().fetch_users_by_query_stream(
+                FetchUsersByQueryParams::default(),
+                PaginationOptions {
+                    page_size: Some(10),
+                    prefetch: 1,
+                },
+            );
+            let emails: Vec<String> = collect_all(users, 100)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|user| user.email)
+                .collect();
+            assert_eq!(
+                (0..25)
+                    .map(|i| format!("user{}@example.com", i))
+                    .collect::<Vec<_>>(),
+                emails
+            );
+        });
+    }
+}
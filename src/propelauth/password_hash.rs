@@ -0,0 +1,610 @@
+//! Local validation of password hashes imported from other systems, so that malformed hashes
+//! are caught before they're sent to `migrate_user` or `migrate_user_password`.
+
+use std::fmt;
+
+use crate::propelauth::errors::InvalidPasswordHashError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PasswordHashAlgorithm {
+    Bcrypt,
+    Argon2id,
+    Argon2i,
+    Argon2d,
+    Scrypt,
+    Pbkdf2Sha1,
+    Pbkdf2Sha256,
+    Pbkdf2Sha512,
+    SaltedSha1,
+    SaltedSha256,
+    SaltedSha512,
+}
+
+impl fmt::Display for PasswordHashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PasswordHashAlgorithm::Bcrypt => "bcrypt",
+            PasswordHashAlgorithm::Argon2id => "argon2id",
+            PasswordHashAlgorithm::Argon2i => "argon2i",
+            PasswordHashAlgorithm::Argon2d => "argon2d",
+            PasswordHashAlgorithm::Scrypt => "scrypt",
+            PasswordHashAlgorithm::Pbkdf2Sha1 => "PBKDF2-SHA1",
+            PasswordHashAlgorithm::Pbkdf2Sha256 => "PBKDF2-SHA256",
+            PasswordHashAlgorithm::Pbkdf2Sha512 => "PBKDF2-SHA512",
+            PasswordHashAlgorithm::SaltedSha1 => "salted SHA-1",
+            PasswordHashAlgorithm::SaltedSha256 => "salted SHA-256",
+            PasswordHashAlgorithm::SaltedSha512 => "salted SHA-512",
+        })
+    }
+}
+
+/// A well-formed password hash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordHashInfo {
+    pub algorithm: PasswordHashAlgorithm,
+    /// Parameters which are valid, but weaker than current recommendations. Users with these
+    /// hashes can still log in.
+    pub warnings: Vec<String>,
+}
+
+/// Detects the algorithm of a password hash and checks that it's well-formed and its
+/// parameters are in range. Recognizes:
+///
+/// - bcrypt (`$2a$`, `$2b$`, `$2x$` and `$2y$`)
+/// - argon2id, argon2i and argon2d in PHC format (`$argon2id$v=19$m=...,t=...,p=...$salt$hash`)
+/// - scrypt in PHC format (`$scrypt$ln=...,r=...,p=...$salt$hash`)
+/// - PBKDF2 in PHC or passlib format (`$pbkdf2-sha256$i=...$salt$hash`) and Django format
+///   (`pbkdf2_sha256$iterations$salt$hash`)
+/// - salted SHA as LDAP `{SSHA}`, `{SSHA256}` and `{SSHA512}`, or `sha1$salt$hex`
+pub fn validate_password_hash(hash: &str) -> Result<PasswordHashInfo, InvalidPasswordHashError> {
+    let hash = hash.trim();
+    if let Some(rest) = hash.strip_prefix("{SSHA512}") {
+        return validate_ldap_salted_sha(PasswordHashAlgorithm::SaltedSha512, rest, 64);
+    }
+    if let Some(rest) = hash.strip_prefix("{SSHA256}") {
+        return validate_ldap_salted_sha(PasswordHashAlgorithm::SaltedSha256, rest, 32);
+    }
+    if let Some(rest) = hash.strip_prefix("{SSHA}") {
+        return validate_ldap_salted_sha(PasswordHashAlgorithm::SaltedSha1, rest, 20);
+    }
+
+    if let Some(rest) = hash.strip_prefix('$') {
+        let (id, rest) = rest.split_once('$').unwrap_or((rest, ""));
+        return match id {
+            "2a" | "2b" | "2x" | "2y" => validate_bcrypt(rest),
+            "argon2id" => validate_argon2(PasswordHashAlgorithm::Argon2id, rest),
+            "argon2i" => validate_argon2(PasswordHashAlgorithm::Argon2i, rest),
+            "argon2d" => validate_argon2(PasswordHashAlgorithm::Argon2d, rest),
+            "scrypt" => validate_scrypt(rest),
+            "pbkdf2" | "pbkdf2-sha1" => {
+                validate_phc_pbkdf2(PasswordHashAlgorithm::Pbkdf2Sha1, rest)
+            }
+            "pbkdf2-sha256" => validate_phc_pbkdf2(PasswordHashAlgorithm::Pbkdf2Sha256, rest),
+            "pbkdf2-sha512" => validate_phc_pbkdf2(PasswordHashAlgorithm::Pbkdf2Sha512, rest),
+            _ => Err(InvalidPasswordHashError::UnrecognizedFormat),
+        };
+    }
+
+    let (id, rest) = hash
+        .split_once('$')
+        .ok_or(InvalidPasswordHashError::UnrecognizedFormat)?;
+    match id {
+        "pbkdf2_sha1" => validate_django_pbkdf2(PasswordHashAlgorithm::Pbkdf2Sha1, rest),
+        "pbkdf2_sha256" => validate_django_pbkdf2(PasswordHashAlgorithm::Pbkdf2Sha256, rest),
+        "sha1" => validate_hex_salted_sha(PasswordHashAlgorithm::SaltedSha1, rest),
+        "sha256" => validate_hex_salted_sha(PasswordHashAlgorithm::SaltedSha256, rest),
+        "sha512" => validate_hex_salted_sha(PasswordHashAlgorithm::SaltedSha512, rest),
+        _ => Err(InvalidPasswordHashError::UnrecognizedFormat),
+    }
+}
+
+fn malformed(
+    algorithm: PasswordHashAlgorithm,
+    reason: impl Into<String>,
+) -> InvalidPasswordHashError {
+    InvalidPasswordHashError::Malformed {
+        algorithm,
+        reason: reason.into(),
+    }
+}
+
+fn validate_bcrypt(rest: &str) -> Result<PasswordHashInfo, InvalidPasswordHashError> {
+    let algorithm = PasswordHashAlgorithm::Bcrypt;
+    let (cost, salt_and_hash) = rest
+        .split_once('$')
+        .ok_or_else(|| malformed(algorithm, "missing cost"))?;
+    let cost: u32 = match cost.len() {
+        2 => cost.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| malformed(algorithm, "cost must be two digits"))?;
+    if !(4..=31).contains(&cost) {
+        return Err(malformed(algorithm, "cost must be between 4 and 31"));
+    }
+    if salt_and_hash.len() != 53
+        || !salt_and_hash
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '/')
+    {
+        return Err(malformed(
+            algorithm,
+            "expected 22 characters of salt and 31 of hash",
+        ));
+    }
+
+    let mut warnings = vec![];
+    if cost < 10 {
+        warnings.push(format!(
+            "cost {} is below the recommended minimum of 10",
+            cost
+        ));
+    }
+    Ok(PasswordHashInfo {
+        algorithm,
+        warnings,
+    })
+}
+
+/// The `$`-separated sections following the algorithm ID in a PHC string
+struct PhcSections<'a> {
+    version: Option<&'a str>,
+    params: Vec<(&'a str, &'a str)>,
+    salt: &'a str,
+    hash: &'a str,
+}
+
+impl<'a> PhcSections<'a> {
+    fn parse(
+        algorithm: PasswordHashAlgorithm,
+        rest: &'a str,
+    ) -> Result<Self, InvalidPasswordHashError> {
+        let mut sections: Vec<&str> = rest.split('$').collect();
+        let version = if sections.first().is_some_and(|s| s.starts_with("v=")) {
+            Some(&sections.remove(0)[2..])
+        } else {
+            None
+        };
+        if sections.len() != 3 {
+            return Err(malformed(
+                algorithm,
+                "expected parameters, a salt and a hash separated by $",
+            ));
+        }
+
+        let mut params = vec![];
+        for param in sections[0].split(',') {
+            let (name, value) = param.split_once('=').unwrap_or(("", param));
+            params.push((name, value));
+        }
+        Ok(PhcSections {
+            version,
+            params,
+            salt: sections[1],
+            hash: sections[2],
+        })
+    }
+
+    fn param(
+        &self,
+        algorithm: PasswordHashAlgorithm,
+        name: &str,
+    ) -> Result<u64, InvalidPasswordHashError> {
+        let value = self
+            .params
+            .iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| malformed(algorithm, format!("missing parameter {}", name)))?;
+        value
+            .parse()
+            .map_err(|_| malformed(algorithm, format!("parameter {} must be a number", name)))
+    }
+}
+
+fn validate_argon2(
+    algorithm: PasswordHashAlgorithm,
+    rest: &str,
+) -> Result<PasswordHashInfo, InvalidPasswordHashError> {
+    let phc = PhcSections::parse(algorithm, rest)?;
+    match phc.version {
+        None | Some("16") | Some("19") => {}
+        Some(_) => return Err(malformed(algorithm, "version must be 16 or 19")),
+    }
+
+    let memory = phc.param(algorithm, "m")?;
+    let iterations = phc.param(algorithm, "t")?;
+    let parallelism = phc.param(algorithm, "p")?;
+    if !(1..=0x00ff_ffff).contains(&parallelism) {
+        return Err(malformed(algorithm, "p must be between 1 and 16777215"));
+    }
+    if iterations < 1 {
+        return Err(malformed(algorithm, "t must be at least 1"));
+    }
+    if memory < 8 * parallelism {
+        return Err(malformed(algorithm, "m must be at least 8 times p"));
+    }
+    check_salt_and_hash(algorithm, phc.salt, phc.hash, &['+', '/'], 8, 4)?;
+
+    let mut warnings = vec![];
+    if algorithm == PasswordHashAlgorithm::Argon2d {
+        warnings.push("argon2d is vulnerable to side-channel attacks, prefer argon2id".to_string());
+    }
+    if memory < 19456 {
+        warnings.push(format!(
+            "memory cost m={} KiB is below the recommended minimum of 19456 KiB",
+            memory
+        ));
+    }
+    Ok(PasswordHashInfo {
+        algorithm,
+        warnings,
+    })
+}
+
+fn validate_scrypt(rest: &str) -> Result<PasswordHashInfo, InvalidPasswordHashError> {
+    let algorithm = PasswordHashAlgorithm::Scrypt;
+    let phc = PhcSections::parse(algorithm, rest)?;
+    let log_n = phc.param(algorithm, "ln")?;
+    let block_size = phc.param(algorithm, "r")?;
+    let parallelism = phc.param(algorithm, "p")?;
+    if !(1..=63).contains(&log_n) {
+        return Err(malformed(algorithm, "ln must be between 1 and 63"));
+    }
+    if block_size < 1 || parallelism < 1 {
+        return Err(malformed(algorithm, "r and p must be at least 1"));
+    }
+    if !matches!(block_size.checked_mul(parallelism), Some(product) if product < 1 << 30) {
+        return Err(malformed(algorithm, "r * p must be less than 2^30"));
+    }
+    check_salt_and_hash(algorithm, phc.salt, phc.hash, &['+', '/'], 8, 16)?;
+
+    let mut warnings = vec![];
+    if log_n < 15 {
+        warnings.push(format!(
+            "ln={} is below the recommended minimum of 15 (N=32768)",
+            log_n
+        ));
+    }
+    Ok(PasswordHashInfo {
+        algorithm,
+        warnings,
+    })
+}
+
+fn validate_phc_pbkdf2(
+    algorithm: PasswordHashAlgorithm,
+    rest: &str,
+) -> Result<PasswordHashInfo, InvalidPasswordHashError> {
+    let phc = PhcSections::parse(algorithm, rest)?;
+    // passlib writes the iteration count on its own, and PHC as `i=<iterations>`
+    let iterations = match phc.params.as_slice() {
+        [("", iterations)] => iterations
+            .parse()
+            .map_err(|_| malformed(algorithm, "iterations must be a number"))?,
+        _ => phc.param(algorithm, "i")?,
+    };
+    // passlib uses `.` in place of `+`
+    check_salt_and_hash(algorithm, phc.salt, phc.hash, &['+', '.', '/'], 8, 16)?;
+    pbkdf2_info(algorithm, iterations)
+}
+
+fn validate_django_pbkdf2(
+    algorithm: PasswordHashAlgorithm,
+    rest: &str,
+) -> Result<PasswordHashInfo, InvalidPasswordHashError> {
+    let sections: Vec<&str> = rest.split('$').collect();
+    let (iterations, salt, hash) = match sections.as_slice() {
+        [iterations, salt, hash] => (iterations, salt, hash),
+        _ => {
+            return Err(malformed(
+                algorithm,
+                "expected iterations, a salt and a hash separated by $",
+            ))
+        }
+    };
+    let iterations: u64 = iterations
+        .parse()
+        .map_err(|_| malformed(algorithm, "iterations must be a number"))?;
+    if salt.is_empty() {
+        return Err(malformed(algorithm, "missing salt"));
+    }
+    let expected_len = match algorithm {
+        PasswordHashAlgorithm::Pbkdf2Sha1 => 20,
+        _ => 32,
+    };
+    if base64_len(hash, &['+', '/']) != Some(expected_len) {
+        return Err(malformed(
+            algorithm,
+            format!("hash must be {} base64 encoded bytes", expected_len),
+        ));
+    }
+    pbkdf2_info(algorithm, iterations)
+}
+
+fn pbkdf2_info(
+    algorithm: PasswordHashAlgorithm,
+    iterations: u64,
+) -> Result<PasswordHashInfo, InvalidPasswordHashError> {
+    if iterations < 1 {
+        return Err(malformed(algorithm, "iterations must be at least 1"));
+    }
+
+    let recommended = match algorithm {
+        PasswordHashAlgorithm::Pbkdf2Sha1 => 1_300_000,
+        PasswordHashAlgorithm::Pbkdf2Sha512 => 210_000,
+        _ => 600_000,
+    };
+    let mut warnings = vec![];
+    if iterations < recommended {
+        warnings.push(format!(
+            "{} iterations is below the recommended minimum of {}",
+            iterations, recommended
+        ));
+    }
+    Ok(PasswordHashInfo {
+        algorithm,
+        warnings,
+    })
+}
+
+fn validate_ldap_salted_sha(
+    algorithm: PasswordHashAlgorithm,
+    encoded: &str,
+    digest_len: usize,
+) -> Result<PasswordHashInfo, InvalidPasswordHashError> {
+    let decoded_len = base64_len(encoded, &['+', '/'])
+        .ok_or_else(|| malformed(algorithm, "must be base64 encoded"))?;
+    if decoded_len <= digest_len {
+        return Err(malformed(algorithm, "missing salt"));
+    }
+    Ok(salted_sha_info(algorithm))
+}
+
+fn validate_hex_salted_sha(
+    algorithm: PasswordHashAlgorithm,
+    rest: &str,
+) -> Result<PasswordHashInfo, InvalidPasswordHashError> {
+    let (salt, hash) = rest
+        .split_once('$')
+        .ok_or_else(|| malformed(algorithm, "expected a salt and a hash separated by $"))?;
+    if salt.is_empty() {
+        return Err(malformed(algorithm, "missing salt"));
+    }
+    let expected_len = match algorithm {
+        PasswordHashAlgorithm::SaltedSha1 => 40,
+        PasswordHashAlgorithm::SaltedSha256 => 64,
+        _ => 128,
+    };
+    if hash.len() != expected_len || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(malformed(
+            algorithm,
+            format!("hash must be {} hex characters", expected_len),
+        ));
+    }
+    Ok(salted_sha_info(algorithm))
+}
+
+fn salted_sha_info(algorithm: PasswordHashAlgorithm) -> PasswordHashInfo {
+    PasswordHashInfo {
+        algorithm,
+        warnings: vec![format!(
+            "{} is fast to brute force, consider asking users to reset their password",
+            algorithm
+        )],
+    }
+}
+
+fn check_salt_and_hash(
+    algorithm: PasswordHashAlgorithm,
+    salt: &str,
+    hash: &str,
+    extra_chars: &[char],
+    min_salt_len: usize,
+    min_hash_len: usize,
+) -> Result<(), InvalidPasswordHashError> {
+    match base64_len(salt, extra_chars) {
+        Some(len) if len >= min_salt_len => {}
+        Some(_) => {
+            return Err(malformed(
+                algorithm,
+                format!("salt must be at least {} bytes", min_salt_len),
+            ))
+        }
+        None => return Err(malformed(algorithm, "salt must be base64 encoded")),
+    }
+    match base64_len(hash, extra_chars) {
+        Some(len) if len >= min_hash_len => Ok(()),
+        Some(_) => Err(malformed(
+            algorithm,
+            format!("hash must be at least {} bytes", min_hash_len),
+        )),
+        None => Err(malformed(algorithm, "hash must be base64 encoded")),
+    }
+}
+
+/// The number of bytes `encoded` decodes to, if it's valid base64 using alphanumerics and
+/// `extra_chars`, with or without padding
+fn base64_len(encoded: &str, extra_chars: &[char]) -> Option<usize> {
+    let unpadded = encoded.trim_end_matches('=');
+    if unpadded.is_empty() || encoded.len() - unpadded.len() > 2 {
+        return None;
+    }
+    if !unpadded
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || extra_chars.contains(&c))
+    {
+        return None;
+    }
+    match unpadded.len() % 4 {
+        1 => None,
+        remainder => Some(unpadded.len() / 4 * 3 + remainder.saturating_sub(1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::propelauth::errors::InvalidPasswordHashError;
+    use crate::propelauth::password_hash::{validate_password_hash, PasswordHashAlgorithm};
+
+    fn algorithm(hash: &str) -> PasswordHashAlgorithm {
+        validate_password_hash(hash)
+            .unwrap_or_else(|err| panic!("{} should be valid: {}", hash, err))
+            .algorithm
+    }
+
+    fn reason(hash: &str) -> String {
+        match validate_password_hash(hash) {
+            Err(InvalidPasswordHashError::Malformed { reason, .. }) => reason,
+            other => panic!("{} should be malformed, got {:?}", hash, other),
+        }
+    }
+
+    #[test]
+    fn recognizes_supported_formats() {
+        let sha256 = "LXEWQrcmsEQBYnyp+6wy9chTD7GQPMTbAiWHF5IaSIE";
+        assert_eq!(
+            PasswordHashAlgorithm::Bcrypt,
+            algorithm("$2b$12$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW")
+        );
+        assert_eq!(
+            PasswordHashAlgorithm::Argon2id,
+            algorithm(
+                "$argon2id$v=19$m=65536,t=3,p=4$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG"
+            )
+        );
+        assert_eq!(
+            PasswordHashAlgorithm::Argon2i,
+            algorithm("$argon2i$m=65536,t=2,p=1$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG")
+        );
+        assert_eq!(
+            PasswordHashAlgorithm::Scrypt,
+            algorithm(&format!(
+                "$scrypt$ln=16,r=8,p=1$aM15713r3Xsvxbi31lqr1Q${}",
+                sha256
+            ))
+        );
+        assert_eq!(
+            PasswordHashAlgorithm::Pbkdf2Sha256,
+            algorithm(&format!(
+                "$pbkdf2-sha256$i=600000,l=32$c29tZXNhbHQ${}",
+                sha256
+            ))
+        );
+        assert_eq!(
+            PasswordHashAlgorithm::Pbkdf2Sha256,
+            algorithm("$pbkdf2-sha256$29000$N2YMIWQsBWBMae09x1jrPQ$1t8iyB2A.WF/Z5JZv.lfCIhXXN33N23OSgQYThBYRfk")
+        );
+        assert_eq!(
+            PasswordHashAlgorithm::Pbkdf2Sha256,
+            algorithm(&format!("pbkdf2_sha256$260000$somesalt${}=", sha256))
+        );
+        assert_eq!(
+            PasswordHashAlgorithm::SaltedSha1,
+            algorithm("{SSHA}cyy1DSsp8htHWuMRIU8YoBan9yBzYWx0")
+        );
+        assert_eq!(
+            PasswordHashAlgorithm::SaltedSha1,
+            algorithm("sha1$salt$ba6a7a32fddcf049146a14ff31d9c2f5b22d0ed3")
+        );
+    }
+
+    #[test]
+    fn reports_weak_parameters_as_warnings() {
+        let info =
+            validate_password_hash("$2a$08$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW")
+                .unwrap();
+        assert_eq!(
+            vec!["cost 8 is below the recommended minimum of 10"],
+            info.warnings
+        );
+
+        let info = validate_password_hash(
+            "$argon2id$v=19$m=65536,t=3,p=4$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG",
+        )
+        .unwrap();
+        assert!(info.warnings.is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_hashes() {
+        assert_eq!(
+            Err(InvalidPasswordHashError::UnrecognizedFormat),
+            validate_password_hash("hunter2")
+        );
+        assert_eq!(
+            Err(InvalidPasswordHashError::UnrecognizedFormat),
+            validate_password_hash("$md5$abc")
+        );
+        assert_eq!(
+            "cost must be between 4 and 31",
+            reason("$2b$32$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW")
+        );
+        assert_eq!(
+            "expected 22 characters of salt and 31 of hash",
+            reason("$2b$12$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi")
+        );
+        assert_eq!(
+            "m must be at least 8 times p",
+            reason("$argon2id$v=19$m=16,t=3,p=4$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG")
+        );
+        assert_eq!(
+            "missing parameter t",
+            reason("$argon2id$v=19$m=65536,p=4$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG")
+        );
+        assert_eq!(
+            "salt must be at least 8 bytes",
+            reason("$argon2id$v=19$m=65536,t=3,p=4$c2FsdA$RdescudvJCsgt3ub+b+dWRWJTmaaJObG")
+        );
+        assert_eq!(
+            "hash must be 32 base64 encoded bytes",
+            reason("pbkdf2_sha256$260000$somesalt$tooshort")
+        );
+        assert_eq!(
+            "r * p must be less than 2^30",
+            reason("$scrypt$ln=15,r=4294967296,p=4294967296$aM15713r3Xsvxbi31lqr1Q$aM15713r3Xsvxbi31lqr1Q")
+        );
+        assert_eq!("missing salt", reason("{SSHA}cyy1DSsp8htHWuMRIU8YoBan9yA="));
+    }
+
+    #[cfg(all(feature = "testing", feature = "__reqwest"))]
+    #[test]
+    fn strict_mode_rejects_malformed_hashes_before_migrating() {
+        use crate::models::{BadMigrateUserRequest, MigrateUserRequest};
+        use crate::propelauth::errors::MigrateUserError;
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth().with_strict_password_hashes();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let migrate = |password_hash: &str| MigrateUserRequest {
+                email: "user@example.com".to_string(),
+                existing_password_hash: Some(password_hash.to_string()),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                Err(MigrateUserError::BadRequest(BadMigrateUserRequest {
+                    existing_password_hash: Some(vec![
+                        "Unrecognized password hash format".to_string()
+                    ]),
+                    ..Default::default()
+                })),
+                auth.user().migrate_user(migrate("hunter2")).await
+            );
+            assert!(fake.users().is_empty());
+
+            auth.user()
+                .migrate_user(migrate(
+                    "$2b$12$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW",
+                ))
+                .await
+                .unwrap();
+            assert_eq!(1, fake.users().len());
+        });
+    }
+}
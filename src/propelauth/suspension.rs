@@ -0,0 +1,391 @@
+//! Reversible suspension of a user. Suspending disables the user, logs them out and expires
+//! their API keys, and remembers what was changed in the user's metadata so that
+//! reinstating them can put it back.
+
+use chrono::Utc;
+use futures_util::stream::TryStreamExt;
+use serde_json::{Map, Value};
+
+use crate::apis::api_key_service_api::{ApiKeyError, ApiKeyQueryParams, UpdateApiKeyParams};
+use crate::apis::user_service_api::FetchUserByIdParams;
+use crate::models::UserMetadata;
+use crate::propelauth::api_key::ApiKeyService;
+use crate::propelauth::errors::{ErrorsWithNotFound, ModifyMetadataError, SuspensionError};
+use crate::propelauth::ids::UserId;
+use crate::propelauth::pagination::PaginationOptions;
+use crate::propelauth::user::UserService;
+
+/// The user metadata key that holds a suspended user's [`SuspensionRecord`]
+pub const SUSPENSION_METADATA_KEY: &str = "propelauth_suspension";
+
+/// What [`UserService::suspend_user`] changed, as stored in the user's metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuspensionRecord {
+    pub suspended_at: i64,
+    pub reason: Option<String>,
+    /// Whether the user was enabled before being suspended
+    pub was_enabled: bool,
+    /// The API keys that were active when the user was suspended
+    pub api_keys: Vec<SuspendedApiKey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuspendedApiKey {
+    pub api_key_id: String,
+    /// When the key was set to expire before it was suspended, if ever
+    pub expires_at_seconds: Option<i64>,
+}
+
+impl SuspensionRecord {
+    /// The suspension stored in the user's metadata, if they're suspended
+    pub fn from_user(user: &UserMetadata) -> Option<SuspensionRecord> {
+        let record = user.metadata.as_ref()?.get(SUSPENSION_METADATA_KEY)?;
+        serde_json::from_value(record.clone()).ok()
+    }
+}
+
+impl<'a> UserService<'a> {
+    /// Disables the user, logs them out of every session, and expires each of their active API
+    /// keys, recording the keys' previous expirations in their metadata under
+    /// [`SUSPENSION_METADATA_KEY`].
+    ///
+    /// Suspending an already suspended user keeps the original record, adding any keys created
+    /// since, and applies every step again. This makes it safe to retry after a failure.
+    pub async fn suspend_user(
+        &self,
+        user_id: UserId,
+        reason: Option<String>,
+    ) -> Result<SuspensionRecord, SuspensionError> {
+        let user = self.fetch_user_for_suspension(user_id).await?;
+        let now = Utc::now().timestamp();
+        let mut record = SuspensionRecord::from_user(&user).unwrap_or(SuspensionRecord {
+            suspended_at: now,
+            reason,
+            was_enabled: user.enabled,
+            api_keys: vec![],
+        });
+
+        let api_keys = ApiKeyService {
+            config: self.config,
+        };
+        let params = ApiKeyQueryParams {
+            user_id: Some(user_id.to_string()),
+            ..Default::default()
+        };
+        let active: Vec<SuspendedApiKey> = api_keys
+            .fetch_current_api_keys_stream(params, PaginationOptions::default())
+            .try_filter_map(|api_key| {
+                let is_active = !matches!(
+                    api_key.expires_at_seconds,
+                    Some(expires_at) if expires_at <= now
+                );
+                let suspended = SuspendedApiKey {
+                    api_key_id: api_key.api_key_id,
+                    expires_at_seconds: api_key.expires_at_seconds,
+                };
+                std::future::ready(Ok(is_active.then_some(suspended)))
+            })
+            .try_collect()
+            .await
+            .map_err(suspension_api_key_error)?;
+        for api_key in active {
+            if !record
+                .api_keys
+                .iter()
+                .any(|suspended| suspended.api_key_id == api_key.api_key_id)
+            {
+                record.api_keys.push(api_key);
+            }
+        }
+
+        // The record is saved before anything changes, so a partial suspension can be undone
+        let record_value = serde_json::to_value(&record).ok();
+        self.set_suspension_metadata(user_id, record_value).await?;
+
+        for api_key in &record.api_keys {
+            let params = UpdateApiKeyParams {
+                expires_at_seconds: Some(now),
+                ..Default::default()
+            };
+            update_suspended_api_key(&api_keys, &api_key.api_key_id, params).await?;
+        }
+        self.logout_all_user_sessions_by_id(user_id)
+            .await
+            .map_err(suspension_error)?;
+        self.disable_user_by_id(user_id)
+            .await
+            .map_err(suspension_error)?;
+        Ok(record)
+    }
+
+    /// Undoes [`suspend_user`](Self::suspend_user): restores each suspended API key's previous
+    /// expiration, re-enables the user if they were enabled before, and then removes the
+    /// record from their metadata. Keys that were deleted in the meantime are skipped.
+    pub async fn reinstate_user(
+        &self,
+        user_id: UserId,
+    ) -> Result<SuspensionRecord, SuspensionError> {
+        let user = self.fetch_user_for_suspension(user_id).await?;
+        let record = SuspensionRecord::from_user(&user).ok_or(SuspensionError::NotSuspended)?;
+
+        let api_keys = ApiKeyService {
+            config: self.config,
+        };
+        for api_key in &record.api_keys {
+            let params = match api_key.expires_at_seconds {
+                Some(expires_at_seconds) => UpdateApiKeyParams {
+                    expires_at_seconds: Some(expires_at_seconds),
+                    ..Default::default()
+                },
+                None => UpdateApiKeyParams {
+                    set_to_never_expire: Some(true),
+                    ..Default::default()
+                },
+            };
+            update_suspended_api_key(&api_keys, &api_key.api_key_id, params).await?;
+        }
+        if record.was_enabled {
+            self.enable_user_by_id(user_id)
+                .await
+                .map_err(suspension_error)?;
+        }
+        self.set_suspension_metadata(user_id, None).await?;
+        Ok(record)
+    }
+
+    async fn fetch_user_for_suspension(
+        &self,
+        user_id: UserId,
+    ) -> Result<UserMetadata, SuspensionError> {
+        self.fetch_user_by_id(FetchUserByIdParams {
+            user_id: user_id.to_string(),
+            include_orgs: None,
+        })
+        .await
+        .map_err(suspension_error)
+    }
+
+    /// Stores `record` under [`SUSPENSION_METADATA_KEY`], or removes it when there is none,
+    /// keeping the rest of the user's metadata as it is
+    async fn set_suspension_metadata(
+        &self,
+        user_id: UserId,
+        record: Option<Value>,
+    ) -> Result<(), SuspensionError> {
+        self.modify_user_metadata(user_id, |metadata: &mut Map<String, Value>| {
+            match &record {
+                Some(record) => {
+                    metadata.insert(SUSPENSION_METADATA_KEY.to_string(), record.clone())
+                }
+                None => metadata.remove(SUSPENSION_METADATA_KEY),
+            };
+        })
+        .await
+        .map(|_| ())
+        .map_err(|err| match err {
+            ModifyMetadataError::InvalidApiKey => SuspensionError::InvalidApiKey,
+            ModifyMetadataError::PropelAuthRateLimit => SuspensionError::PropelAuthRateLimit,
+            ModifyMetadataError::NotFound => SuspensionError::NotFound,
+            _ => SuspensionError::UnexpectedException,
+        })
+    }
+}
+
+/// Keys that were deleted in the meantime are skipped
+async fn update_suspended_api_key(
+    api_keys: &ApiKeyService<'_>,
+    api_key_id: &str,
+    params: UpdateApiKeyParams,
+) -> Result<(), SuspensionError> {
+    let result = match api_key_id.parse() {
+        Ok(api_key_id) => api_keys.update_api_key_by_id(api_key_id, params).await,
+        Err(_) => Err(ApiKeyError::NotFound),
+    };
+    match result {
+        Ok(()) | Err(ApiKeyError::NotFound) => Ok(()),
+        Err(err) => Err(suspension_api_key_error(err)),
+    }
+}
+
+fn suspension_error(err: ErrorsWithNotFound) -> SuspensionError {
+    match err {
+        ErrorsWithNotFound::InvalidApiKey => SuspensionError::InvalidApiKey,
+        ErrorsWithNotFound::PropelAuthRateLimit => SuspensionError::PropelAuthRateLimit,
+        ErrorsWithNotFound::NotFound => SuspensionError::NotFound,
+        ErrorsWithNotFound::UnexpectedException => SuspensionError::UnexpectedException,
+    }
+}
+
+fn suspension_api_key_error(err: ApiKeyError) -> SuspensionError {
+    match err {
+        ApiKeyError::InvalidIntegrationAPIKey => SuspensionError::InvalidApiKey,
+        ApiKeyError::PropelAuthRateLimit => SuspensionError::PropelAuthRateLimit,
+        _ => SuspensionError::UnexpectedException,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use crate::models::UserMetadata;
+    use crate::propelauth::suspension::{
+        SuspendedApiKey, SuspensionRecord, SUSPENSION_METADATA_KEY,
+    };
+
+    #[test]
+    fn reads_the_record_from_metadata() {
+        let user = |metadata| UserMetadata {
+            metadata,
+            ..Default::default()
+        };
+        assert_eq!(None, SuspensionRecord::from_user(&user(None)));
+        assert_eq!(
+            None,
+            SuspensionRecord::from_user(&user(Some(HashMap::from([(
+                SUSPENSION_METADATA_KEY.to_string(),
+                json!("garbage")
+            )]))))
+        );
+
+        let record = json!({
+            "suspended_at": 1_700_000_000,
+            "reason": "spam",
+            "was_enabled": true,
+            "api_keys": [{"api_key_id": "abc", "expires_at_seconds": null}],
+        });
+        assert_eq!(
+            Some(SuspensionRecord {
+                suspended_at: 1_700_000_000,
+                reason: Some("spam".to_string()),
+                was_enabled: true,
+                api_keys: vec![SuspendedApiKey {
+                    api_key_id: "abc".to_string(),
+                    expires_at_seconds: None,
+                }],
+            }),
+            SuspensionRecord::from_user(&user(Some(HashMap::from([(
+                SUSPENSION_METADATA_KEY.to_string(),
+                record
+            )]))))
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn suspends_and_reinstates_a_user() {
+        use crate::apis::api_key_service_api::{CreateApiKeyParams, ValidateApiKeyParams};
+        use crate::apis::user_service_api::{FetchUserByIdParams, UpdateUserMetadataParams};
+        use crate::models::{CreateUserRequest, UpdateMetadataRequest};
+        use crate::propelauth::errors::SuspensionError;
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            auth.user()
+                .update_user_metadata(UpdateUserMetadataParams {
+                    user_id: user_id.clone(),
+                    update_metadata_request: UpdateMetadataRequest {
+                        metadata: Some(HashMap::from([("plan".to_string(), json!("pro"))])),
+                        ..Default::default()
+                    },
+                })
+                .await
+                .unwrap();
+            let create_key = |expires_at_seconds| CreateApiKeyParams {
+                user_id: Some(user_id.to_string()),
+                expires_at_seconds,
+                ..Default::default()
+            };
+            let forever = auth
+                .api_key()
+                .create_api_key(create_key(None))
+                .await
+                .unwrap();
+            let expiring = auth
+                .api_key()
+                .create_api_key(create_key(Some(4_102_444_800)))
+                .await
+                .unwrap();
+            let is_valid = |token: String| {
+                let api_keys = auth.api_key();
+                async move {
+                    api_keys
+                        .validate_api_key(ValidateApiKeyParams {
+                            api_key_token: token,
+                        })
+                        .await
+                        .is_ok()
+                }
+            };
+
+            let record = auth
+                .user()
+                .suspend_user(user_id.parse().unwrap(), Some("spam".to_string()))
+                .await
+                .unwrap();
+            assert!(record.was_enabled);
+            assert_eq!(2, record.api_keys.len());
+            assert!(!is_valid(forever.api_key_token.clone()).await);
+            assert!(!is_valid(expiring.api_key_token.clone()).await);
+            let user = auth
+                .user()
+                .fetch_user_by_id(FetchUserByIdParams {
+                    user_id: user_id.clone(),
+                    include_orgs: None,
+                })
+                .await
+                .unwrap();
+            assert!(!user.enabled);
+            assert_eq!(Some(record.clone()), SuspensionRecord::from_user(&user));
+            assert_eq!(Some(&json!("pro")), user.metadata.unwrap().get("plan"));
+
+            // Suspending again keeps the original snapshot
+            let again = auth
+                .user()
+                .suspend_user(user_id.parse().unwrap(), None)
+                .await
+                .unwrap();
+            assert_eq!(record, again);
+
+            auth.user()
+                .reinstate_user(user_id.parse().unwrap())
+                .await
+                .unwrap();
+            assert!(is_valid(forever.api_key_token).await);
+            assert!(is_valid(expiring.api_key_token).await);
+            let user = fake
+                .users()
+                .into_iter()
+                .find(|user| user.user_id == user_id)
+                .unwrap();
+            assert!(user.enabled);
+            assert_eq!(
+                Some(HashMap::from([("plan".to_string(), json!("pro"))])),
+                user.metadata
+            );
+
+            assert!(matches!(
+                auth.user().reinstate_user(user_id.parse().unwrap()).await,
+                Err(SuspensionError::NotSuspended)
+            ));
+        });
+    }
+}
@@ -61,34 +61,34 @@ fn default_login_method() -> LoginMethodForAccessToken {
     }
 }
 
-impl Into<User> for DecodedUserFromToken {
-    fn into(self) -> User {
+impl From<DecodedUserFromToken> for User {
+    fn from(val: DecodedUserFromToken) -> Self {
         let mut active_org_id: Option<String> = None;
         let mut org_id_to_org_member_info = HashMap::<String, OrgMemberInfo>::new();
-        if let Some(org_member_info) = &self.org_member_info {
+        if let Some(org_member_info) = &val.org_member_info {
             active_org_id = Some(org_member_info.org_id.clone());
             org_id_to_org_member_info
                 .insert(org_member_info.org_id.clone(), org_member_info.clone());
         } else {
-            if let Some(org_id_to_org_member_info_from_token) = self.org_id_to_org_member_info {
+            if let Some(org_id_to_org_member_info_from_token) = val.org_id_to_org_member_info {
                 for (org_id, org_member_info) in org_id_to_org_member_info_from_token {
                     org_id_to_org_member_info.insert(org_id, org_member_info);
                 }
             }
         }
         User {
-            user_id: self.user_id,
-            email: self.email,
-            first_name: self.first_name,
-            last_name: self.last_name,
-            username: self.username,
+            user_id: val.user_id,
+            email: val.email,
+            first_name: val.first_name,
+            last_name: val.last_name,
+            username: val.username,
             org_id_to_org_member_info,
-            legacy_user_id: self.legacy_user_id,
-            impersonator_user_id: self.impersonator_user_id,
-            properties: self.properties,
-            metadata: self.metadata,
+            legacy_user_id: val.legacy_user_id,
+            impersonator_user_id: val.impersonator_user_id,
+            properties: val.properties,
+            metadata: val.metadata,
             active_org_id,
-            login_method: self.login_method.into(),
+            login_method: val.login_method.into(),
         }
     }
 }
@@ -149,6 +149,11 @@ impl TokenService<'_> {
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::redundant_static_lifetimes,
+    clippy::useless_vec,
+    mismatched_lifetime_syntaxes
+)]
 mod tests {
     use std::collections::HashMap;
     use std::time::SystemTime;
@@ -5,9 +5,12 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::propelauth::errors::DetailedForbiddenError;
+use crate::propelauth::ids::OrgId;
 use crate::propelauth::options::{RequiredOrg, UserRequirementsInOrg};
 
 #[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct LoginMethodForAccessToken {
     pub login_method: String,
     #[serde(default)]
@@ -17,6 +20,8 @@ pub struct LoginMethodForAccessToken {
 }
 
 #[derive(Debug, Serialize, Deserialize, Hash, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum SocialLoginType {
     Google,
@@ -48,6 +53,8 @@ impl std::str::FromStr for SocialLoginType {
 }
 
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum IdentityProvider {
     Google,
     Rippling,
@@ -78,6 +85,7 @@ impl std::str::FromStr for IdentityProvider {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum LoginMethod {
     Password,
     MagicLink,
@@ -90,24 +98,24 @@ pub enum LoginMethod {
     Unknown,
 }
 
-impl Into<LoginMethod> for LoginMethodForAccessToken {
-    fn into(self) -> LoginMethod {
-        match self.login_method.as_str() {
+impl From<LoginMethodForAccessToken> for LoginMethod {
+    fn from(val: LoginMethodForAccessToken) -> Self {
+        match val.login_method.as_str() {
             "password" => LoginMethod::Password,
             "magic_link" => LoginMethod::MagicLink,
             "social_sso" => LoginMethod::SocialSso(
-                self.provider
+                val.provider
                     .expect("provider is required for social_sso login method")
                     .parse::<SocialLoginType>()
                     .expect("invalid social login type for social_sso login method"),
             ),
             "email_confirmation_link" => LoginMethod::EmailConfirmationLink,
             "saml_sso" => LoginMethod::SamlSso(
-                self.provider
+                val.provider
                     .expect("provider is required for saml_sso login method")
                     .parse::<IdentityProvider>()
                     .expect("invalid identity provider for saml_sso login method"),
-                self.org_id
+                val.org_id
                     .expect("org_id is required for saml_sso login method"),
             ),
             "impersonation" => LoginMethod::Impersonation,
@@ -117,7 +125,71 @@ impl Into<LoginMethod> for LoginMethodForAccessToken {
     }
 }
 
+// The derived utoipa schema describes `SamlSso` as an array of arbitrary objects, so it's spelled
+// out here to match how serde (and the schemars derive) represent it.
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for LoginMethod {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::schema::{
+            AllOfBuilder, ArrayBuilder, ArrayItems, ObjectBuilder, OneOfBuilder, Schema, Type,
+        };
+        use utoipa::openapi::Ref;
+        use utoipa::ToSchema;
+
+        let unit_variants = ObjectBuilder::new()
+            .schema_type(Type::String)
+            .enum_values(Some([
+                "Password",
+                "MagicLink",
+                "EmailConfirmationLink",
+                "Impersonation",
+                "TokenGeneratedFromBackendApi",
+                "Unknown",
+            ]));
+        let social_sso = ObjectBuilder::new()
+            .property("SocialSso", Ref::from_schema_name(SocialLoginType::name()))
+            .required("SocialSso");
+        let saml_sso = ObjectBuilder::new()
+            .property(
+                "SamlSso",
+                ArrayBuilder::new()
+                    .prefix_items::<_, Schema>([
+                        AllOfBuilder::new()
+                            .item(Ref::from_schema_name(IdentityProvider::name()))
+                            .into(),
+                        ObjectBuilder::new().schema_type(Type::String).into(),
+                    ])
+                    .items(ArrayItems::False)
+                    .min_items(Some(2))
+                    .max_items(Some(2)),
+            )
+            .required("SamlSso");
+        OneOfBuilder::new()
+            .item(unit_variants)
+            .item(social_sso)
+            .item(saml_sso)
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for LoginMethod {
+    fn schemas(
+        schemas: &mut Vec<(
+            String,
+            utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>,
+        )>,
+    ) {
+        use utoipa::PartialSchema;
+
+        schemas.push((SocialLoginType::name().into(), SocialLoginType::schema()));
+        schemas.push((IdentityProvider::name().into(), IdentityProvider::schema()));
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct User {
     pub user_id: String,
 
@@ -208,6 +280,10 @@ impl User {
         }
     }
 
+    pub fn get_org_by_id(&self, org_id: OrgId) -> Option<&OrgMemberInfo> {
+        self.org_id_to_org_member_info.get(&org_id.to_string())
+    }
+
     pub fn get_active_org(&self) -> Option<&OrgMemberInfo> {
         match &self.active_org_id {
             Some(org_id) => self.get_org(RequiredOrg::OrgId(org_id)),
@@ -237,6 +313,8 @@ impl User {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct OrgMemberInfo {
     pub org_id: String,
     pub org_name: String,
@@ -250,6 +328,8 @@ pub struct OrgMemberInfo {
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum OrgRoleStructure {
     #[default]
     #[serde(rename = "single_role_in_hierarchy")]
@@ -301,7 +381,98 @@ impl OrgMemberInfo {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct UserAndOrgMemberInfo {
     pub user: User,
     pub org_member_info: OrgMemberInfo,
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn json_schemas_match_the_serde_representation() {
+        use serde_json::json;
+
+        use crate::models::FetchOrgResponse;
+        use crate::propelauth::token_models::{LoginMethod, OrgRoleStructure};
+
+        let schema = serde_json::to_value(schemars::schema_for!(OrgRoleStructure)).unwrap();
+        assert_eq!(
+            json!(["single_role_in_hierarchy", "multi_role"]),
+            schema["enum"]
+        );
+
+        let schema = serde_json::to_value(schemars::schema_for!(LoginMethod)).unwrap();
+        assert_eq!(json!("Password"), schema["oneOf"][0]["enum"][0]);
+        assert_eq!(
+            json!({ "$ref": "#/$defs/SocialLoginType" }),
+            schema["oneOf"][1]["properties"]["SocialSso"]
+        );
+        assert_eq!(
+            json!([{ "$ref": "#/$defs/IdentityProvider" }, { "type": "string" }]),
+            schema["oneOf"][2]["properties"]["SamlSso"]["prefixItems"]
+        );
+
+        // The org's metadata is flattened into a map, and left out when it's empty
+        let schema = serde_json::to_value(schemars::schema_for!(FetchOrgResponse)).unwrap();
+        assert_eq!(
+            json!({ "type": "object", "additionalProperties": true }),
+            schema["$defs"]["OrgMetadata"]
+        );
+        assert!(!schema["required"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("metadata")));
+    }
+
+    #[cfg(feature = "utoipa")]
+    #[test]
+    fn openapi_schemas_match_the_serde_representation() {
+        use serde_json::json;
+        use utoipa::{PartialSchema, ToSchema};
+
+        use crate::models::fetch_org_response::OrgMetadata;
+        use crate::propelauth::token_models::{LoginMethod, OrgRoleStructure, User};
+
+        let schema = serde_json::to_value(OrgRoleStructure::schema()).unwrap();
+        assert_eq!(
+            json!(["single_role_in_hierarchy", "multi_role"]),
+            schema["enum"]
+        );
+
+        let schema = serde_json::to_value(LoginMethod::schema()).unwrap();
+        assert_eq!(
+            json!([
+                { "allOf": [{ "$ref": "#/components/schemas/IdentityProvider" }] },
+                { "type": "string" }
+            ]),
+            schema["oneOf"][2]["properties"]["SamlSso"]["prefixItems"]
+        );
+
+        let schema = serde_json::to_value(OrgMetadata::schema()).unwrap();
+        assert_eq!(
+            json!({ "type": "object", "additionalProperties": {} }),
+            schema
+        );
+
+        // Referenced schemas are collected so they end up in the OpenAPI components
+        let mut schemas = vec![];
+        User::schemas(&mut schemas);
+        let names: Vec<_> = schemas.iter().map(|(name, _)| name.as_str()).collect();
+        for name in [
+            "LoginMethod",
+            "OrgMemberInfo",
+            "SocialLoginType",
+            "IdentityProvider",
+        ] {
+            assert!(
+                names.contains(&name),
+                "{} is missing from {:?}",
+                name,
+                names
+            );
+        }
+    }
+}
@@ -0,0 +1,692 @@
+//! Async traits over the backend API services, so application code can depend on an
+//! abstraction instead of [`PropelAuth`](crate::propelauth::auth::PropelAuth) directly.
+//!
+//! They are implemented by the borrowed services (`auth.user()`, `auth.org()`, ...) and by the
+//! owned [`PropelAuthClient`](crate::propelauth::client::PropelAuthClient), which can be
+//! stored in app state as e.g. `Arc<dyn UserApi>`.
+//! With the `testing` feature enabled, [mockall](https://docs.rs/mockall) mocks are generated
+//! for each trait (`MockUserApi`, `MockOrgApi`, `MockApiKeyApi` and `MockAccessTokenApi`).
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::apis::access_token_service_api::{CreateAccessTokenParams, CreateAccessTokenV2Params};
+use crate::apis::api_key_service_api::{
+    ApiKeyError, ApiKeyQueryParams, CreateApiKeyParams, UpdateApiKeyParams, ValidateApiKeyParams,
+};
+use crate::apis::org_service_api::{
+    AllowOrgToEnableSamlParams, CreateSamlConnectionLinkParams, DisallowSamlParams,
+    FetchOrgsByQueryParams, FetchPendingInvitesParams, FetchUsersInOrgParams,
+};
+use crate::apis::user_service_api::{
+    FetchUserByEmailParams, FetchUserByIdParams, FetchUserByUsernameParams,
+    FetchUsersByEmailsParams, FetchUsersByIdsParams, FetchUsersByQueryParams,
+    FetchUsersByUsernamesParams, UpdateUserEmailParams, UpdateUserMetadataParams,
+    UpdateUserPasswordParams,
+};
+use crate::models::validate_api_key_response::{
+    ValidateOrgApiKeyResponse, ValidatePersonalApiKeyResponse,
+};
+use crate::models::{
+    AddUserToOrgRequest, ChangeUserRoleInOrgRequest, CreateAccessTokenResponse,
+    CreateApiKeyResponse, CreateMagicLinkRequest, CreateOrgRequest, CreateOrgResponse,
+    CreateSamlConnectionLinkResponse, CreateUserRequest, CreatedUserResponse, FetchApiKeyResponse,
+    FetchApiKeysPagedResponse, FetchOrgResponse, FetchOrgsResponse, FetchSamlSpMetadataResponse,
+    InviteUserToOrgRequest, MagicLink, MigrateUserPasswordRequest, MigrateUserRequest,
+    RemoveUserFromOrgRequest, RevokePendingOrgInviteRequest, UpdateOrgRequest, UserMetadata,
+    UserPagedResponse, ValidateApiKeyResponse,
+};
+use crate::propelauth::access_token::AccessTokenService;
+use crate::propelauth::api_key::ApiKeyService;
+use crate::propelauth::errors::{
+    BatchFetchError, ClearPasswordError, CreateAccessTokenError, CreateMagicLinkError,
+    CreateOrgError, CreateUserError, ErrorsWithNotFound, FetchByQueryError, FetchOrgsByQueryError,
+    FetchUsersInOrgError, InviteUserToOrgError, MigrateUserError, MigrateUserPasswordError,
+    OrgMissingOrRoleError, ResendEmailConfirmationError, SubscribeOrgToRoleMappingError,
+    UpdateOrgError, UpdatePasswordError, UpdateUserEmailError, UpdateUserMetadataError,
+};
+use crate::propelauth::org::OrgService;
+use crate::propelauth::user::UserService;
+
+/// API requests related to users
+#[cfg_attr(feature = "testing", mockall::automock)]
+#[cfg_attr(feature = "testing", allow(clippy::result_large_err))]
+#[async_trait]
+pub trait UserApi: Send + Sync {
+    async fn fetch_user_by_email(
+        &self,
+        params: FetchUserByEmailParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound>;
+    async fn fetch_user_by_id(
+        &self,
+        params: FetchUserByIdParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound>;
+    async fn fetch_user_by_username(
+        &self,
+        params: FetchUserByUsernameParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound>;
+    async fn fetch_users_by_ids(
+        &self,
+        params: FetchUsersByIdsParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError>;
+    async fn fetch_users_by_emails(
+        &self,
+        params: FetchUsersByEmailsParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError>;
+    async fn fetch_users_by_usernames(
+        &self,
+        params: FetchUsersByUsernamesParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError>;
+    async fn fetch_users_by_query(
+        &self,
+        params: FetchUsersByQueryParams,
+    ) -> Result<UserPagedResponse, FetchByQueryError>;
+    async fn create_user(
+        &self,
+        create_user_request: CreateUserRequest,
+    ) -> Result<CreatedUserResponse, CreateUserError>;
+    async fn delete_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound>;
+    async fn disable_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound>;
+    async fn logout_all_user_sessions(&self, user_id: String) -> Result<(), ErrorsWithNotFound>;
+    async fn enable_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound>;
+    async fn update_user_metadata(
+        &self,
+        params: UpdateUserMetadataParams,
+    ) -> Result<(), UpdateUserMetadataError>;
+    async fn update_user_email(
+        &self,
+        params: UpdateUserEmailParams,
+    ) -> Result<(), UpdateUserEmailError>;
+    async fn update_user_password(
+        &self,
+        params: UpdateUserPasswordParams,
+    ) -> Result<(), UpdatePasswordError>;
+    async fn clear_user_password(&self, user_id: String) -> Result<(), ClearPasswordError>;
+    async fn disable_user_2fa(&self, user_id: String) -> Result<(), ErrorsWithNotFound>;
+    async fn resend_email_confirmation(
+        &self,
+        user_id: String,
+    ) -> Result<(), ResendEmailConfirmationError>;
+    async fn migrate_user(
+        &self,
+        migrate_user_request: MigrateUserRequest,
+    ) -> Result<CreatedUserResponse, MigrateUserError>;
+    async fn migrate_user_password(
+        &self,
+        migrate_user_password_request: MigrateUserPasswordRequest,
+    ) -> Result<(), MigrateUserPasswordError>;
+    async fn create_magic_link(
+        &self,
+        create_magic_link_request: CreateMagicLinkRequest,
+    ) -> Result<MagicLink, CreateMagicLinkError>;
+    async fn enable_user_can_create_orgs(&self, user_id: String) -> Result<(), ErrorsWithNotFound>;
+    async fn disable_user_can_create_orgs(&self, user_id: String)
+        -> Result<(), ErrorsWithNotFound>;
+}
+
+/// API requests related to organizations
+#[cfg_attr(feature = "testing", mockall::automock)]
+#[async_trait]
+pub trait OrgApi: Send + Sync {
+    async fn fetch_org(&self, org_id: String) -> Result<FetchOrgResponse, ErrorsWithNotFound>;
+    async fn fetch_orgs_by_query(
+        &self,
+        params: FetchOrgsByQueryParams,
+    ) -> Result<FetchOrgsResponse, FetchOrgsByQueryError>;
+    async fn fetch_users_in_org(
+        &self,
+        params: FetchUsersInOrgParams,
+    ) -> Result<UserPagedResponse, FetchUsersInOrgError>;
+    async fn fetch_custom_role_mappings(
+        &self,
+    ) -> Result<crate::models::FetchCustomRoleMappingsResponse, ErrorsWithNotFound>;
+    async fn fetch_pending_invites(
+        &self,
+        params: FetchPendingInvitesParams,
+    ) -> Result<crate::models::FetchPendingInvitesResponse, ErrorsWithNotFound>;
+    async fn add_user_to_org(
+        &self,
+        add_user_to_org_request: AddUserToOrgRequest,
+    ) -> Result<(), OrgMissingOrRoleError>;
+    async fn change_user_role_in_org(
+        &self,
+        change_user_role_in_org_request: ChangeUserRoleInOrgRequest,
+    ) -> Result<(), OrgMissingOrRoleError>;
+    async fn remove_user_from_org(
+        &self,
+        remove_user_from_org_request: RemoveUserFromOrgRequest,
+    ) -> Result<(), ErrorsWithNotFound>;
+    async fn create_org(
+        &self,
+        create_org_request: CreateOrgRequest,
+    ) -> Result<CreateOrgResponse, CreateOrgError>;
+    async fn update_org(
+        &self,
+        org_id: String,
+        update_org_request: UpdateOrgRequest,
+    ) -> Result<(), UpdateOrgError>;
+    async fn subscribe_org_to_role_mapping(
+        &self,
+        org_id: String,
+        custom_role_mapping_name: String,
+    ) -> Result<(), SubscribeOrgToRoleMappingError>;
+    async fn allow_org_to_enable_saml(
+        &self,
+        params: AllowOrgToEnableSamlParams,
+    ) -> Result<(), ErrorsWithNotFound>;
+    async fn disallow_saml(&self, params: DisallowSamlParams) -> Result<(), ErrorsWithNotFound>;
+    async fn create_saml_connection_link(
+        &self,
+        params: CreateSamlConnectionLinkParams,
+    ) -> Result<CreateSamlConnectionLinkResponse, ErrorsWithNotFound>;
+    async fn fetch_saml_sp_metadata(
+        &self,
+        org_id: String,
+    ) -> Result<FetchSamlSpMetadataResponse, ErrorsWithNotFound>;
+    async fn set_saml_idp_metadata(
+        &self,
+        request: crate::models::SetSamlIdpMetadataRequest,
+    ) -> Result<(), ErrorsWithNotFound>;
+    async fn saml_go_live(&self, org_id: String) -> Result<(), ErrorsWithNotFound>;
+    async fn delete_saml_connection(&self, org_id: String) -> Result<(), ErrorsWithNotFound>;
+    async fn delete_org(&self, org_id: String) -> Result<(), ErrorsWithNotFound>;
+    async fn invite_user_to_org(
+        &self,
+        invite_user_to_org_request: InviteUserToOrgRequest,
+    ) -> Result<(), InviteUserToOrgError>;
+    async fn revoke_pending_org_invite(
+        &self,
+        revoke_pending_org_invite_request: RevokePendingOrgInviteRequest,
+    ) -> Result<(), ErrorsWithNotFound>;
+}
+
+/// API requests related to end user API keys
+#[cfg_attr(feature = "testing", mockall::automock)]
+#[async_trait]
+pub trait ApiKeyApi: Send + Sync {
+    async fn fetch_current_api_keys(
+        &self,
+        params: ApiKeyQueryParams,
+    ) -> Result<FetchApiKeysPagedResponse, ApiKeyError>;
+    async fn fetch_archived_api_keys(
+        &self,
+        params: ApiKeyQueryParams,
+    ) -> Result<FetchApiKeysPagedResponse, ApiKeyError>;
+    async fn fetch_api_key(&self, api_key_id: String) -> Result<FetchApiKeyResponse, ApiKeyError>;
+    async fn create_api_key(
+        &self,
+        params: CreateApiKeyParams,
+    ) -> Result<CreateApiKeyResponse, ApiKeyError>;
+    async fn update_api_key(
+        &self,
+        api_key_id: String,
+        params: UpdateApiKeyParams,
+    ) -> Result<(), ApiKeyError>;
+    async fn delete_api_key(&self, api_key_id: String) -> Result<(), ApiKeyError>;
+    async fn validate_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidateApiKeyResponse, ApiKeyError>;
+    async fn validate_personal_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidatePersonalApiKeyResponse, ApiKeyError>;
+    async fn validate_org_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidateOrgApiKeyResponse, ApiKeyError>;
+}
+
+/// API requests related to access tokens
+#[cfg_attr(feature = "testing", mockall::automock)]
+#[async_trait]
+pub trait AccessTokenApi: Send + Sync {
+    async fn create_access_token(
+        &self,
+        params: CreateAccessTokenParams,
+    ) -> Result<CreateAccessTokenResponse, CreateAccessTokenError>;
+    async fn create_access_token_v2(
+        &self,
+        params: CreateAccessTokenV2Params,
+    ) -> Result<CreateAccessTokenResponse, CreateAccessTokenError>;
+}
+
+#[async_trait]
+impl UserApi for UserService<'_> {
+    async fn fetch_user_by_email(
+        &self,
+        params: FetchUserByEmailParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        UserService::fetch_user_by_email(self, params).await
+    }
+
+    async fn fetch_user_by_id(
+        &self,
+        params: FetchUserByIdParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        UserService::fetch_user_by_id(self, params).await
+    }
+
+    async fn fetch_user_by_username(
+        &self,
+        params: FetchUserByUsernameParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        UserService::fetch_user_by_username(self, params).await
+    }
+
+    async fn fetch_users_by_ids(
+        &self,
+        params: FetchUsersByIdsParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
+        UserService::fetch_users_by_ids(self, params).await
+    }
+
+    async fn fetch_users_by_emails(
+        &self,
+        params: FetchUsersByEmailsParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
+        UserService::fetch_users_by_emails(self, params).await
+    }
+
+    async fn fetch_users_by_usernames(
+        &self,
+        params: FetchUsersByUsernamesParams,
+    ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
+        UserService::fetch_users_by_usernames(self, params).await
+    }
+
+    async fn fetch_users_by_query(
+        &self,
+        params: FetchUsersByQueryParams,
+    ) -> Result<UserPagedResponse, FetchByQueryError> {
+        UserService::fetch_users_by_query(self, params).await
+    }
+
+    async fn create_user(
+        &self,
+        create_user_request: CreateUserRequest,
+    ) -> Result<CreatedUserResponse, CreateUserError> {
+        UserService::create_user(self, create_user_request).await
+    }
+
+    async fn delete_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.delete_user_by_raw_id(user_id).await
+    }
+
+    async fn disable_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.disable_user_by_raw_id(user_id).await
+    }
+
+    async fn logout_all_user_sessions(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.logout_all_user_sessions_by_raw_id(user_id).await
+    }
+
+    async fn enable_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.enable_user_by_raw_id(user_id).await
+    }
+
+    async fn update_user_metadata(
+        &self,
+        params: UpdateUserMetadataParams,
+    ) -> Result<(), UpdateUserMetadataError> {
+        UserService::update_user_metadata(self, params).await
+    }
+
+    async fn update_user_email(
+        &self,
+        params: UpdateUserEmailParams,
+    ) -> Result<(), UpdateUserEmailError> {
+        UserService::update_user_email(self, params).await
+    }
+
+    async fn update_user_password(
+        &self,
+        params: UpdateUserPasswordParams,
+    ) -> Result<(), UpdatePasswordError> {
+        UserService::update_user_password(self, params).await
+    }
+
+    async fn clear_user_password(&self, user_id: String) -> Result<(), ClearPasswordError> {
+        self.clear_user_password_by_raw_id(user_id).await
+    }
+
+    async fn disable_user_2fa(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.disable_user_2fa_by_raw_id(user_id).await
+    }
+
+    async fn resend_email_confirmation(
+        &self,
+        user_id: String,
+    ) -> Result<(), ResendEmailConfirmationError> {
+        self.resend_email_confirmation_by_raw_id(user_id).await
+    }
+
+    async fn migrate_user(
+        &self,
+        migrate_user_request: MigrateUserRequest,
+    ) -> Result<CreatedUserResponse, MigrateUserError> {
+        UserService::migrate_user(self, migrate_user_request).await
+    }
+
+    async fn migrate_user_password(
+        &self,
+        migrate_user_password_request: MigrateUserPasswordRequest,
+    ) -> Result<(), MigrateUserPasswordError> {
+        UserService::migrate_user_password(self, migrate_user_password_request).await
+    }
+
+    async fn create_magic_link(
+        &self,
+        create_magic_link_request: CreateMagicLinkRequest,
+    ) -> Result<MagicLink, CreateMagicLinkError> {
+        UserService::create_magic_link(self, create_magic_link_request).await
+    }
+
+    async fn enable_user_can_create_orgs(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.enable_user_can_create_orgs_by_raw_id(user_id).await
+    }
+
+    async fn disable_user_can_create_orgs(
+        &self,
+        user_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.disable_user_can_create_orgs_by_raw_id(user_id).await
+    }
+}
+
+#[async_trait]
+impl OrgApi for OrgService<'_> {
+    async fn fetch_org(&self, org_id: String) -> Result<FetchOrgResponse, ErrorsWithNotFound> {
+        self.fetch_org_by_raw_id(org_id).await
+    }
+
+    async fn fetch_orgs_by_query(
+        &self,
+        params: FetchOrgsByQueryParams,
+    ) -> Result<FetchOrgsResponse, FetchOrgsByQueryError> {
+        OrgService::fetch_orgs_by_query(self, params).await
+    }
+
+    async fn fetch_users_in_org(
+        &self,
+        params: FetchUsersInOrgParams,
+    ) -> Result<UserPagedResponse, FetchUsersInOrgError> {
+        OrgService::fetch_users_in_org(self, params).await
+    }
+
+    async fn fetch_custom_role_mappings(
+        &self,
+    ) -> Result<crate::models::FetchCustomRoleMappingsResponse, ErrorsWithNotFound> {
+        OrgService::fetch_custom_role_mappings(self).await
+    }
+
+    async fn fetch_pending_invites(
+        &self,
+        params: FetchPendingInvitesParams,
+    ) -> Result<crate::models::FetchPendingInvitesResponse, ErrorsWithNotFound> {
+        OrgService::fetch_pending_invites(self, params).await
+    }
+
+    async fn add_user_to_org(
+        &self,
+        add_user_to_org_request: AddUserToOrgRequest,
+    ) -> Result<(), OrgMissingOrRoleError> {
+        OrgService::add_user_to_org(self, add_user_to_org_request).await
+    }
+
+    async fn change_user_role_in_org(
+        &self,
+        change_user_role_in_org_request: ChangeUserRoleInOrgRequest,
+    ) -> Result<(), OrgMissingOrRoleError> {
+        OrgService::change_user_role_in_org(self, change_user_role_in_org_request).await
+    }
+
+    async fn remove_user_from_org(
+        &self,
+        remove_user_from_org_request: RemoveUserFromOrgRequest,
+    ) -> Result<(), ErrorsWithNotFound> {
+        OrgService::remove_user_from_org(self, remove_user_from_org_request).await
+    }
+
+    async fn create_org(
+        &self,
+        create_org_request: CreateOrgRequest,
+    ) -> Result<CreateOrgResponse, CreateOrgError> {
+        OrgService::create_org(self, create_org_request).await
+    }
+
+    async fn update_org(
+        &self,
+        org_id: String,
+        update_org_request: UpdateOrgRequest,
+    ) -> Result<(), UpdateOrgError> {
+        self.update_org_by_raw_id(org_id, update_org_request).await
+    }
+
+    async fn subscribe_org_to_role_mapping(
+        &self,
+        org_id: String,
+        custom_role_mapping_name: String,
+    ) -> Result<(), SubscribeOrgToRoleMappingError> {
+        OrgService::subscribe_org_to_role_mapping(self, org_id, custom_role_mapping_name).await
+    }
+
+    async fn allow_org_to_enable_saml(
+        &self,
+        params: AllowOrgToEnableSamlParams,
+    ) -> Result<(), ErrorsWithNotFound> {
+        OrgService::allow_org_to_enable_saml(self, params).await
+    }
+
+    async fn disallow_saml(&self, params: DisallowSamlParams) -> Result<(), ErrorsWithNotFound> {
+        OrgService::disallow_saml(self, params).await
+    }
+
+    async fn create_saml_connection_link(
+        &self,
+        params: CreateSamlConnectionLinkParams,
+    ) -> Result<CreateSamlConnectionLinkResponse, ErrorsWithNotFound> {
+        OrgService::create_saml_connection_link(self, params).await
+    }
+
+    async fn fetch_saml_sp_metadata(
+        &self,
+        org_id: String,
+    ) -> Result<FetchSamlSpMetadataResponse, ErrorsWithNotFound> {
+        OrgService::fetch_saml_sp_metadata(self, org_id).await
+    }
+
+    async fn set_saml_idp_metadata(
+        &self,
+        request: crate::models::SetSamlIdpMetadataRequest,
+    ) -> Result<(), ErrorsWithNotFound> {
+        OrgService::set_saml_idp_metadata(self, request).await
+    }
+
+    async fn saml_go_live(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.saml_go_live_by_raw_id(org_id).await
+    }
+
+    async fn delete_saml_connection(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.delete_saml_connection_by_raw_id(org_id).await
+    }
+
+    async fn delete_org(&self, org_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.delete_org_by_raw_id(org_id).await
+    }
+
+    async fn invite_user_to_org(
+        &self,
+        invite_user_to_org_request: InviteUserToOrgRequest,
+    ) -> Result<(), InviteUserToOrgError> {
+        OrgService::invite_user_to_org(self, invite_user_to_org_request).await
+    }
+
+    async fn revoke_pending_org_invite(
+        &self,
+        revoke_pending_org_invite_request: RevokePendingOrgInviteRequest,
+    ) -> Result<(), ErrorsWithNotFound> {
+        OrgService::revoke_pending_org_invite(self, revoke_pending_org_invite_request).await
+    }
+}
+
+#[async_trait]
+impl ApiKeyApi for ApiKeyService<'_> {
+    async fn fetch_current_api_keys(
+        &self,
+        params: ApiKeyQueryParams,
+    ) -> Result<FetchApiKeysPagedResponse, ApiKeyError> {
+        ApiKeyService::fetch_current_api_keys(self, params).await
+    }
+
+    async fn fetch_archived_api_keys(
+        &self,
+        params: ApiKeyQueryParams,
+    ) -> Result<FetchApiKeysPagedResponse, ApiKeyError> {
+        ApiKeyService::fetch_archived_api_keys(self, params).await
+    }
+
+    async fn fetch_api_key(&self, api_key_id: String) -> Result<FetchApiKeyResponse, ApiKeyError> {
+        self.fetch_api_key_by_raw_id(api_key_id).await
+    }
+
+    async fn create_api_key(
+        &self,
+        params: CreateApiKeyParams,
+    ) -> Result<CreateApiKeyResponse, ApiKeyError> {
+        ApiKeyService::create_api_key(self, params).await
+    }
+
+    async fn update_api_key(
+        &self,
+        api_key_id: String,
+        params: UpdateApiKeyParams,
+    ) -> Result<(), ApiKeyError> {
+        self.update_api_key_by_raw_id(api_key_id, params).await
+    }
+
+    async fn delete_api_key(&self, api_key_id: String) -> Result<(), ApiKeyError> {
+        self.delete_api_key_by_raw_id(api_key_id).await
+    }
+
+    async fn validate_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidateApiKeyResponse, ApiKeyError> {
+        ApiKeyService::validate_api_key(self, params).await
+    }
+
+    async fn validate_personal_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidatePersonalApiKeyResponse, ApiKeyError> {
+        ApiKeyService::validate_personal_api_key(self, params).await
+    }
+
+    async fn validate_org_api_key(
+        &self,
+        params: ValidateApiKeyParams,
+    ) -> Result<ValidateOrgApiKeyResponse, ApiKeyError> {
+        ApiKeyService::validate_org_api_key(self, params).await
+    }
+}
+
+#[async_trait]
+impl AccessTokenApi for AccessTokenService<'_> {
+    async fn create_access_token(
+        &self,
+        params: CreateAccessTokenParams,
+    ) -> Result<CreateAccessTokenResponse, CreateAccessTokenError> {
+        AccessTokenService::create_access_token(self, params).await
+    }
+
+    async fn create_access_token_v2(
+        &self,
+        params: CreateAccessTokenV2Params,
+    ) -> Result<CreateAccessTokenResponse, CreateAccessTokenError> {
+        AccessTokenService::create_access_token_v2(self, params).await
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::apis::user_service_api::FetchUserByIdParams;
+    use crate::models::{CreateUserRequest, UserMetadata};
+    use crate::propelauth::errors::ErrorsWithNotFound;
+    use crate::propelauth::traits::UserApi;
+    use crate::testing::{FakeBackend, MockUserApi};
+
+    async fn fetch_email(users: &dyn UserApi, user_id: &str) -> Option<String> {
+        users
+            .fetch_user_by_id(FetchUserByIdParams {
+                user_id: user_id.to_string(),
+                include_orgs: None,
+            })
+            .await
+            .ok()
+            .map(|user| user.email)
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn mock_can_stand_in_for_the_service() {
+        let mut users = MockUserApi::new();
+        users
+            .expect_fetch_user_by_id()
+            .withf(|params| params.user_id == "known")
+            .returning(|params| {
+                Ok(UserMetadata {
+                    user_id: params.user_id,
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+            });
+        users
+            .expect_fetch_user_by_id()
+            .returning(|_| Err(ErrorsWithNotFound::NotFound));
+
+        runtime().block_on(async {
+            assert_eq!(
+                Some("user@example.com".to_string()),
+                fetch_email(&users, "known").await
+            );
+            assert_eq!(None, fetch_email(&users, "unknown").await);
+        });
+    }
+
+    #[test]
+    fn services_and_client_implement_the_traits() {
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+        let client: Arc<dyn UserApi> = Arc::new(auth.client());
+
+        runtime().block_on(async {
+            let user_id = client
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+
+            assert_eq!(
+                Some("user@example.com".to_string()),
+                fetch_email(client.as_ref(), &user_id).await
+            );
+            assert_eq!(
+                Some("user@example.com".to_string()),
+                fetch_email(&auth.user(), &user_id).await
+            );
+        });
+    }
+}
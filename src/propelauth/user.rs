@@ -4,55 +4,85 @@ use crate::apis::user_service_api::{
     DisableUserParams, EnableUserParams, FetchUserByEmailParams, FetchUserByIdParams,
     FetchUserByUsernameParams, FetchUsersByEmailsError, FetchUsersByEmailsParams,
     FetchUsersByIdsError, FetchUsersByIdsParams, FetchUsersByQueryError, FetchUsersByQueryParams,
-    FetchUsersByUsernamesError, FetchUsersByUsernamesParams, MigrateUserParams, MigrateUserPasswordParams,
-    UpdateUserEmailParams, UpdateUserMetadataParams, UpdateUserPasswordParams,
+    FetchUsersByUsernamesError, FetchUsersByUsernamesParams, MigrateUserParams,
+    MigrateUserPasswordParams, UpdateUserEmailParams, UpdateUserMetadataParams,
+    UpdateUserPasswordParams,
 };
 use crate::models::{
-    CreateMagicLinkRequest, CreateUserRequest, CreatedUserResponse, MagicLink, MigrateUserRequest, MigrateUserPasswordRequest,
-    UserMetadata, UserPagedResponse,
+    BadMigrateUserPasswordRequest, BadMigrateUserRequest, CreateMagicLinkRequest,
+    CreateUserRequest, CreatedUserResponse, EmailsQuery, MagicLink, MigrateUserPasswordRequest,
+    MigrateUserRequest, UserIdsQuery, UserMetadata, UserPagedResponse, UsernamesQuery,
 };
+use crate::propelauth::batch::{fetch_in_chunks, BatchFetchResult, BatchOptions};
+use crate::propelauth::cache::{invalidating, read_cached, write_cached, CacheKey, CachedValue};
+use crate::propelauth::coalesce::{coalesced, coalescing_key};
 use crate::propelauth::errors::{
     BatchFetchError, ClearPasswordError, CreateMagicLinkError, CreateUserError, ErrorsWithNotFound,
-    FetchByQueryError, MigrateUserError, MigrateUserPasswordError, ResendEmailConfirmationError, UpdatePasswordError,
-    UpdateUserEmailError, UpdateUserMetadataError,
+    FetchByQueryError, InvalidPasswordHashError, MigrateUserError, MigrateUserPasswordError,
+    ResendEmailConfirmationError, UpdatePasswordError, UpdateUserEmailError,
+    UpdateUserMetadataError,
 };
 use crate::propelauth::helpers::{is_valid_id, map_autogenerated_error};
+use crate::propelauth::ids::UserId;
+use crate::propelauth::pagination::{paginate, Page, PaginationOptions};
+use crate::propelauth::password_hash::validate_password_hash;
+use futures_util::stream::Stream;
 use std::collections::HashMap;
 
 pub struct UserService<'a> {
     pub(crate) config: &'a Configuration,
 }
 
-impl UserService<'_> {
+impl<'a> UserService<'a> {
     pub async fn fetch_user_by_email(
         &self,
         params: FetchUserByEmailParams,
     ) -> Result<UserMetadata, ErrorsWithNotFound> {
-        crate::apis::user_service_api::fetch_user_by_email(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })
+        let key = coalescing_key(self.config, "fetch_user_by_email", &params);
+        coalesced(self.config, key, async move {
+            crate::apis::user_service_api::fetch_user_by_email(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })
+        })
+        .await
     }
 
     pub async fn fetch_user_by_id(
         &self,
         params: FetchUserByIdParams,
+    ) -> Result<UserMetadata, ErrorsWithNotFound> {
+        let cache_key = CacheKey::user(&params.user_id, params.include_orgs.unwrap_or(false));
+        if let Some(CachedValue::User(user)) = read_cached(self.config, &cache_key).await {
+            return Ok(user);
+        }
+
+        let key = coalescing_key(self.config, "fetch_user_by_id", &params);
+        let user = coalesced(self.config, key, self.fetch_user_by_id_uncached(params)).await?;
+        write_cached(self.config, cache_key, CachedValue::User(user.clone())).await;
+        Ok(user)
+    }
+
+    /// Always asks PropelAuth, bypassing both the cache and any in-flight request
+    pub(crate) async fn fetch_user_by_id_uncached(
+        &self,
+        params: FetchUserByIdParams,
     ) -> Result<UserMetadata, ErrorsWithNotFound> {
         if !is_valid_id(&params.user_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        crate::apis::user_service_api::fetch_user_by_id(&self.config, params)
+        crate::apis::user_service_api::fetch_user_by_id(self.config, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -72,142 +102,265 @@ impl UserService<'_> {
         &self,
         params: FetchUserByUsernameParams,
     ) -> Result<UserMetadata, ErrorsWithNotFound> {
-        crate::apis::user_service_api::fetch_user_by_username(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })
+        let key = coalescing_key(self.config, "fetch_user_by_username", &params);
+        coalesced(self.config, key, async move {
+            crate::apis::user_service_api::fetch_user_by_username(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })
+        })
+        .await
     }
 
     pub async fn fetch_users_by_ids(
         &self,
         params: FetchUsersByIdsParams,
     ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
-        let users = crate::apis::user_service_api::fetch_users_by_ids(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    BatchFetchError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (_, Some(FetchUsersByIdsError::Status400(bad_request))) => {
-                            match bad_request.query {
-                                None => {
-                                    BatchFetchError::BadRequest("Request is invalid".to_string())
+        let key = coalescing_key(self.config, "fetch_users_by_ids", &params);
+        coalesced(self.config, key, async move {
+            let users = crate::apis::user_service_api::fetch_users_by_ids(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        BatchFetchError::UnexpectedException,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (_, Some(FetchUsersByIdsError::Status400(bad_request))) => {
+                                match bad_request.query {
+                                    None => BatchFetchError::BadRequest(
+                                        "Request is invalid".to_string(),
+                                    ),
+                                    Some(bad_query_reasons) => BatchFetchError::BadRequest(
+                                        bad_query_reasons.join(", ").to_string(),
+                                    ),
                                 }
-                                Some(bad_query_reasons) => BatchFetchError::BadRequest(
-                                    bad_query_reasons.join(", ").to_string(),
-                                ),
                             }
-                        }
-                        (401, _) => BatchFetchError::InvalidApiKey,
-                        (429, _) => BatchFetchError::PropelAuthRateLimit,
-                        _ => BatchFetchError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(users
-            .into_iter()
-            .map(|user| (user.user_id.clone(), user))
-            .collect())
+                            (401, _) => BatchFetchError::InvalidApiKey,
+                            (429, _) => BatchFetchError::PropelAuthRateLimit,
+                            _ => BatchFetchError::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(users
+                .into_iter()
+                .map(|user| (user.user_id.clone(), user))
+                .collect())
+        })
+        .await
     }
 
     pub async fn fetch_users_by_emails(
         &self,
         params: FetchUsersByEmailsParams,
     ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
-        let users = crate::apis::user_service_api::fetch_users_by_emails(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    BatchFetchError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (_, Some(FetchUsersByEmailsError::Status400(bad_request))) => {
-                            match bad_request.query {
-                                None => {
-                                    BatchFetchError::BadRequest("Request is invalid".to_string())
+        let key = coalescing_key(self.config, "fetch_users_by_emails", &params);
+        coalesced(self.config, key, async move {
+            let users = crate::apis::user_service_api::fetch_users_by_emails(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        BatchFetchError::UnexpectedException,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (_, Some(FetchUsersByEmailsError::Status400(bad_request))) => {
+                                match bad_request.query {
+                                    None => BatchFetchError::BadRequest(
+                                        "Request is invalid".to_string(),
+                                    ),
+                                    Some(bad_query_reasons) => BatchFetchError::BadRequest(
+                                        bad_query_reasons.join(", ").to_string(),
+                                    ),
                                 }
-                                Some(bad_query_reasons) => BatchFetchError::BadRequest(
-                                    bad_query_reasons.join(", ").to_string(),
-                                ),
                             }
-                        }
-                        (401, _) => BatchFetchError::InvalidApiKey,
-                        (429, _) => BatchFetchError::PropelAuthRateLimit,
-                        _ => BatchFetchError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(users
-            .into_iter()
-            .map(|user| (user.email.clone(), user))
-            .collect())
+                            (401, _) => BatchFetchError::InvalidApiKey,
+                            (429, _) => BatchFetchError::PropelAuthRateLimit,
+                            _ => BatchFetchError::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(users
+                .into_iter()
+                .map(|user| (user.email.clone(), user))
+                .collect())
+        })
+        .await
     }
 
     pub async fn fetch_users_by_usernames(
         &self,
         params: FetchUsersByUsernamesParams,
     ) -> Result<HashMap<String, UserMetadata>, BatchFetchError> {
-        let users = crate::apis::user_service_api::fetch_users_by_usernames(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    BatchFetchError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (_, Some(FetchUsersByUsernamesError::Status400(bad_request))) => {
-                            match bad_request.query {
-                                None => {
-                                    BatchFetchError::BadRequest("Request is invalid".to_string())
+        let key = coalescing_key(self.config, "fetch_users_by_usernames", &params);
+        coalesced(self.config, key, async move {
+            let users =
+                crate::apis::user_service_api::fetch_users_by_usernames(self.config, params)
+                    .await
+                    .map_err(|err| {
+                        map_autogenerated_error(
+                            err,
+                            BatchFetchError::UnexpectedException,
+                            |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                                (_, Some(FetchUsersByUsernamesError::Status400(bad_request))) => {
+                                    match bad_request.query {
+                                        None => BatchFetchError::BadRequest(
+                                            "Request is invalid".to_string(),
+                                        ),
+                                        Some(bad_query_reasons) => BatchFetchError::BadRequest(
+                                            bad_query_reasons.join(", ").to_string(),
+                                        ),
+                                    }
                                 }
-                                Some(bad_query_reasons) => BatchFetchError::BadRequest(
-                                    bad_query_reasons.join(", ").to_string(),
-                                ),
-                            }
-                        }
-                        (401, _) => BatchFetchError::InvalidApiKey,
-                        (429, _) => BatchFetchError::PropelAuthRateLimit,
-                        _ => BatchFetchError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(users
-            .into_iter()
-            .filter(|user| user.username.is_some())
-            .map(|user| (user.username.clone().unwrap(), user))
-            .collect())
+                                (401, _) => BatchFetchError::InvalidApiKey,
+                                (429, _) => BatchFetchError::PropelAuthRateLimit,
+                                _ => BatchFetchError::UnexpectedException,
+                            },
+                        )
+                    })?;
+            Ok(users
+                .into_iter()
+                .filter(|user| user.username.is_some())
+                .map(|user| (user.username.clone().unwrap(), user))
+                .collect())
+        })
+        .await
+    }
+
+    /// Like [`fetch_users_by_ids`](Self::fetch_users_by_ids), but for any number of IDs. They are
+    /// de-duplicated case-insensitively and fetched in server-sized chunks, and IDs without a
+    /// user (including malformed ones) are reported in `not_found`. Found users are keyed by
+    /// their lower-case ID, which is how PropelAuth returns them.
+    pub async fn fetch_users_by_ids_chunked<I: IntoIterator<Item = String>>(
+        &self,
+        user_ids: I,
+        options: BatchOptions,
+    ) -> Result<BatchFetchResult, BatchFetchError> {
+        let include_orgs = options.include_orgs;
+        fetch_in_chunks(
+            user_ids,
+            &options,
+            str::to_ascii_lowercase,
+            |user_ids| async move {
+                let user_ids: Vec<String> = user_ids
+                    .iter()
+                    .filter(|id| is_valid_id(id))
+                    .map(|id| id.to_ascii_lowercase())
+                    .collect();
+                if user_ids.is_empty() {
+                    return Ok(HashMap::new());
+                }
+                self.fetch_users_by_ids(FetchUsersByIdsParams {
+                    user_ids_query: UserIdsQuery { user_ids },
+                    include_orgs,
+                })
+                .await
+            },
+        )
+        .await
+    }
+
+    /// Like [`fetch_users_by_emails`](Self::fetch_users_by_emails), but for any number of emails.
+    /// Emails are compared case-insensitively when de-duplicating and reporting `not_found`.
+    pub async fn fetch_users_by_emails_chunked<I: IntoIterator<Item = String>>(
+        &self,
+        emails: I,
+        options: BatchOptions,
+    ) -> Result<BatchFetchResult, BatchFetchError> {
+        let include_orgs = options.include_orgs;
+        fetch_in_chunks(emails, &options, str::to_lowercase, |emails| async move {
+            self.fetch_users_by_emails(FetchUsersByEmailsParams {
+                emails_query: EmailsQuery { emails },
+                include_orgs,
+            })
+            .await
+        })
+        .await
+    }
+
+    /// Like [`fetch_users_by_usernames`](Self::fetch_users_by_usernames), but for any number of
+    /// usernames
+    pub async fn fetch_users_by_usernames_chunked<I: IntoIterator<Item = String>>(
+        &self,
+        usernames: I,
+        options: BatchOptions,
+    ) -> Result<BatchFetchResult, BatchFetchError> {
+        let include_orgs = options.include_orgs;
+        fetch_in_chunks(
+            usernames,
+            &options,
+            str::to_string,
+            |usernames| async move {
+                self.fetch_users_by_usernames(FetchUsersByUsernamesParams {
+                    usernames_query: UsernamesQuery { usernames },
+                    include_orgs,
+                })
+                .await
+            },
+        )
+        .await
     }
 
     pub async fn fetch_users_by_query(
         &self,
         params: FetchUsersByQueryParams,
     ) -> Result<UserPagedResponse, FetchByQueryError> {
-        crate::apis::user_service_api::fetch_users_by_query(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    FetchByQueryError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (_, Some(FetchUsersByQueryError::Status400(bad_request))) => {
-                            FetchByQueryError::BadRequest(bad_request)
-                        }
-                        (401, _) => FetchByQueryError::InvalidApiKey,
-                        (429, _) => FetchByQueryError::PropelAuthRateLimit,
-                        _ => FetchByQueryError::UnexpectedException,
-                    },
-                )
-            })
+        let key = coalescing_key(self.config, "fetch_users_by_query", &params);
+        coalesced(self.config, key, async move {
+            crate::apis::user_service_api::fetch_users_by_query(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        FetchByQueryError::UnexpectedException,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (_, Some(FetchUsersByQueryError::Status400(bad_request))) => {
+                                FetchByQueryError::BadRequest(bad_request)
+                            }
+                            (401, _) => FetchByQueryError::InvalidApiKey,
+                            (429, _) => FetchByQueryError::PropelAuthRateLimit,
+                            _ => FetchByQueryError::UnexpectedException,
+                        },
+                    )
+                })
+        })
+        .await
+    }
+
+    /// Lazily walks every page of [`fetch_users_by_query`](Self::fetch_users_by_query), one user at a time
+    pub fn fetch_users_by_query_stream(
+        &self,
+        params: FetchUsersByQueryParams,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<UserMetadata, FetchByQueryError>> + 'a {
+        let config = self.config;
+        paginate(
+            params.page_number.unwrap_or(0),
+            options.page_size.or(params.page_size),
+            options.prefetch,
+            move |page_number, page_size| {
+                let params = FetchUsersByQueryParams {
+                    page_number: Some(page_number),
+                    page_size,
+                    ..params.clone()
+                };
+                async move {
+                    let response = UserService { config }.fetch_users_by_query(params).await?;
+                    Ok(Page {
+                        items: response.users,
+                        has_more_results: response.has_more_results,
+                    })
+                }
+            },
+        )
     }
 
     pub async fn create_user(
@@ -217,7 +370,7 @@ impl UserService<'_> {
         let params = CreateUserParams {
             create_user_request,
         };
-        crate::apis::user_service_api::create_user(&self.config, params)
+        crate::apis::user_service_api::create_user(self.config, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -238,61 +391,112 @@ impl UserService<'_> {
             })
     }
 
+    #[deprecated(note = "use `delete_user_by_id`, which takes a `UserId`")]
     pub async fn delete_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.delete_user_by_raw_id(user_id).await
+    }
+
+    pub async fn delete_user_by_id(&self, user_id: UserId) -> Result<(), ErrorsWithNotFound> {
+        self.delete_user_by_raw_id(user_id.to_string()).await
+    }
+
+    /// Sends `user_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn delete_user_by_raw_id(
+        &self,
+        user_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
         if !is_valid_id(&user_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        let params = DeleteUserParams { user_id };
-        crate::apis::user_service_api::delete_user(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        let mut cache_keys = CacheKey::for_user(&user_id);
+        cache_keys.extend(self.legacy_cache_keys(&user_id, None).await);
+        invalidating(self.config, cache_keys, async move {
+            let params = DeleteUserParams { user_id };
+            crate::apis::user_service_api::delete_user(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
+    #[deprecated(note = "use `disable_user_by_id`, which takes a `UserId`")]
     pub async fn disable_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.disable_user_by_raw_id(user_id).await
+    }
+
+    pub async fn disable_user_by_id(&self, user_id: UserId) -> Result<(), ErrorsWithNotFound> {
+        self.disable_user_by_raw_id(user_id.to_string()).await
+    }
+
+    /// Sends `user_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn disable_user_by_raw_id(
+        &self,
+        user_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
         if !is_valid_id(&user_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        let params = DisableUserParams { user_id };
-        crate::apis::user_service_api::disable_user(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(self.config, CacheKey::for_user(&user_id), async move {
+            let params = DisableUserParams { user_id };
+            crate::apis::user_service_api::disable_user(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
+    #[deprecated(note = "use `logout_all_user_sessions_by_id`, which takes a `UserId`")]
     pub async fn logout_all_user_sessions(
         &self,
         user_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.logout_all_user_sessions_by_raw_id(user_id).await
+    }
+
+    pub async fn logout_all_user_sessions_by_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.logout_all_user_sessions_by_raw_id(user_id.to_string())
+            .await
+    }
+
+    /// Sends `user_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn logout_all_user_sessions_by_raw_id(
+        &self,
+        user_id: String,
     ) -> Result<(), ErrorsWithNotFound> {
         if !is_valid_id(&user_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        crate::apis::user_service_api::logout_all_user_sessions(&self.config, user_id)
+        crate::apis::user_service_api::logout_all_user_sessions(self.config, user_id)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -309,180 +513,271 @@ impl UserService<'_> {
         Ok(())
     }
 
+    #[deprecated(note = "use `enable_user_by_id`, which takes a `UserId`")]
     pub async fn enable_user(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.enable_user_by_raw_id(user_id).await
+    }
+
+    pub async fn enable_user_by_id(&self, user_id: UserId) -> Result<(), ErrorsWithNotFound> {
+        self.enable_user_by_raw_id(user_id.to_string()).await
+    }
+
+    /// Sends `user_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn enable_user_by_raw_id(
+        &self,
+        user_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
         if !is_valid_id(&user_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        let params = EnableUserParams { user_id };
-        crate::apis::user_service_api::enable_user(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(self.config, CacheKey::for_user(&user_id), async move {
+            let params = EnableUserParams { user_id };
+            crate::apis::user_service_api::enable_user(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
     pub async fn update_user_metadata(
         &self,
         params: UpdateUserMetadataParams,
     ) -> Result<(), UpdateUserMetadataError> {
-        if !is_valid_id(&params.user_id) {
-            return Err(UpdateUserMetadataError::NotFound);
+        let mut cache_keys = CacheKey::for_user(&params.user_id);
+        if let Some(legacy_user_id) = &params.update_metadata_request.legacy_user_id {
+            let legacy_cache_keys = self
+                .legacy_cache_keys(&params.user_id, Some(legacy_user_id))
+                .await;
+            cache_keys.extend(legacy_cache_keys);
         }
-
-        crate::apis::user_service_api::update_user_metadata(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    UpdateUserMetadataError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (
-                            _,
-                            Some(
-                                crate::apis::user_service_api::UpdateUserMetadataError::Status400(
-                                    bad_request,
+        invalidating(self.config, cache_keys, async move {
+            if !is_valid_id(&params.user_id) {
+                return Err(UpdateUserMetadataError::NotFound);
+            }
+
+            crate::apis::user_service_api::update_user_metadata(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        UpdateUserMetadataError::UnexpectedException,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (
+                                _,
+                                Some(
+                                    crate::apis::user_service_api::UpdateUserMetadataError::Status400(
+                                        bad_request,
+                                    ),
                                 ),
-                            ),
-                        ) => UpdateUserMetadataError::BadRequest(bad_request),
-                        (401, _) => UpdateUserMetadataError::InvalidApiKey,
-                        (429, _) => UpdateUserMetadataError::PropelAuthRateLimit,
-                        (404, _) => UpdateUserMetadataError::NotFound,
-                        _ => UpdateUserMetadataError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+                            ) => UpdateUserMetadataError::BadRequest(bad_request),
+                            (401, _) => UpdateUserMetadataError::InvalidApiKey,
+                            (429, _) => UpdateUserMetadataError::PropelAuthRateLimit,
+                            (404, _) => UpdateUserMetadataError::NotFound,
+                            _ => UpdateUserMetadataError::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
     pub async fn update_user_email(
         &self,
         params: UpdateUserEmailParams,
     ) -> Result<(), UpdateUserEmailError> {
-        if !is_valid_id(&params.user_id) {
-            return Err(UpdateUserEmailError::NotFound);
-        }
-
-        crate::apis::user_service_api::update_user_email(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    UpdateUserEmailError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (
-                            _,
-                            Some(crate::apis::user_service_api::UpdateUserEmailError::Status400(
-                                bad_request,
-                            )),
-                        ) => UpdateUserEmailError::BadRequest(bad_request),
-                        (401, _) => UpdateUserEmailError::InvalidApiKey,
-                        (404, _) => UpdateUserEmailError::NotFound,
-                        (429, _) => UpdateUserEmailError::EmailSentTooRecently,
-                        _ => UpdateUserEmailError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(
+            self.config,
+            CacheKey::for_user(&params.user_id),
+            async move {
+                if !is_valid_id(&params.user_id) {
+                    return Err(UpdateUserEmailError::NotFound);
+                }
+
+                crate::apis::user_service_api::update_user_email(self.config, params)
+                    .await
+                    .map_err(|err| {
+                        map_autogenerated_error(
+                            err,
+                            UpdateUserEmailError::UnexpectedException,
+                            |status_code, err_entity| {
+                                match (status_code.as_u16(), err_entity) {
+                            (
+                                _,
+                                Some(crate::apis::user_service_api::UpdateUserEmailError::Status400(
+                                    bad_request,
+                                )),
+                            ) => UpdateUserEmailError::BadRequest(bad_request),
+                            (401, _) => UpdateUserEmailError::InvalidApiKey,
+                            (404, _) => UpdateUserEmailError::NotFound,
+                            (429, _) => UpdateUserEmailError::EmailSentTooRecently,
+                            _ => UpdateUserEmailError::UnexpectedException,
+                        }
+                            },
+                        )
+                    })?;
+                Ok(())
+            },
+        )
+        .await
     }
 
     pub async fn update_user_password(
         &self,
         params: UpdateUserPasswordParams,
     ) -> Result<(), UpdatePasswordError> {
-        if !is_valid_id(&params.user_id) {
-            return Err(UpdatePasswordError::NotFound);
-        }
-
-        crate::apis::user_service_api::update_user_password(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    UpdatePasswordError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (
-                            _,
-                            Some(
-                                crate::apis::user_service_api::UpdateUserPasswordError::Status400(
-                                    bad_request,
+        invalidating(self.config, CacheKey::for_user(&params.user_id), async move {
+            if !is_valid_id(&params.user_id) {
+                return Err(UpdatePasswordError::NotFound);
+            }
+
+            crate::apis::user_service_api::update_user_password(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        UpdatePasswordError::UnexpectedException,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (
+                                _,
+                                Some(
+                                    crate::apis::user_service_api::UpdateUserPasswordError::Status400(
+                                        bad_request,
+                                    ),
                                 ),
-                            ),
-                        ) => UpdatePasswordError::BadRequest(bad_request),
-                        (401, _) => UpdatePasswordError::InvalidApiKey,
-                        (429, _) => UpdatePasswordError::PropelAuthRateLimit,
-                        (404, _) => UpdatePasswordError::NotFound,
-                        _ => UpdatePasswordError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+                            ) => UpdatePasswordError::BadRequest(bad_request),
+                            (401, _) => UpdatePasswordError::InvalidApiKey,
+                            (429, _) => UpdatePasswordError::PropelAuthRateLimit,
+                            (404, _) => UpdatePasswordError::NotFound,
+                            _ => UpdatePasswordError::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
+    #[deprecated(note = "use `clear_user_password_by_id`, which takes a `UserId`")]
     pub async fn clear_user_password(&self, user_id: String) -> Result<(), ClearPasswordError> {
+        self.clear_user_password_by_raw_id(user_id).await
+    }
+
+    pub async fn clear_user_password_by_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<(), ClearPasswordError> {
+        self.clear_user_password_by_raw_id(user_id.to_string())
+            .await
+    }
+
+    /// Sends `user_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn clear_user_password_by_raw_id(
+        &self,
+        user_id: String,
+    ) -> Result<(), ClearPasswordError> {
         if !is_valid_id(&user_id) {
             return Err(ClearPasswordError::NotFound);
         }
 
-        crate::apis::user_service_api::clear_user_password(&self.config, user_id)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ClearPasswordError::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ClearPasswordError::InvalidApiKey,
-                        429 => ClearPasswordError::PropelAuthRateLimit,
-                        404 => ClearPasswordError::NotFound,
-                        _ => ClearPasswordError::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(self.config, CacheKey::for_user(&user_id), async move {
+            crate::apis::user_service_api::clear_user_password(self.config, user_id)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ClearPasswordError::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ClearPasswordError::InvalidApiKey,
+                            429 => ClearPasswordError::PropelAuthRateLimit,
+                            404 => ClearPasswordError::NotFound,
+                            _ => ClearPasswordError::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
+    #[deprecated(note = "use `disable_user_2fa_by_id`, which takes a `UserId`")]
     pub async fn disable_user_2fa(&self, user_id: String) -> Result<(), ErrorsWithNotFound> {
+        self.disable_user_2fa_by_raw_id(user_id).await
+    }
+
+    pub async fn disable_user_2fa_by_id(&self, user_id: UserId) -> Result<(), ErrorsWithNotFound> {
+        self.disable_user_2fa_by_raw_id(user_id.to_string()).await
+    }
+
+    /// Sends `user_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn disable_user_2fa_by_raw_id(
+        &self,
+        user_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
         if !is_valid_id(&user_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        let params = DisableUser2faParams { user_id };
-        crate::apis::user_service_api::disable_user2fa(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(self.config, CacheKey::for_user(&user_id), async move {
+            let params = DisableUser2faParams { user_id };
+            crate::apis::user_service_api::disable_user2fa(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
+    #[deprecated(note = "use `resend_email_confirmation_by_id`, which takes a `UserId`")]
     pub async fn resend_email_confirmation(
         &self,
         user_id: String,
+    ) -> Result<(), ResendEmailConfirmationError> {
+        self.resend_email_confirmation_by_raw_id(user_id).await
+    }
+
+    pub async fn resend_email_confirmation_by_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<(), ResendEmailConfirmationError> {
+        self.resend_email_confirmation_by_raw_id(user_id.to_string())
+            .await
+    }
+
+    /// Sends `user_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn resend_email_confirmation_by_raw_id(
+        &self,
+        user_id: String,
     ) -> Result<(), ResendEmailConfirmationError> {
         if !is_valid_id(&user_id) {
             return Err(ResendEmailConfirmationError::NotFound);
         }
 
-        crate::apis::user_service_api::resend_email_confirmation(&self.config, user_id)
+        crate::apis::user_service_api::resend_email_confirmation(self.config, user_id)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -519,10 +814,19 @@ impl UserService<'_> {
         &self,
         migrate_user_request: MigrateUserRequest,
     ) -> Result<CreatedUserResponse, MigrateUserError> {
+        if let Some(password_hash) = &migrate_user_request.existing_password_hash {
+            if let Err(err) = self.check_password_hash(password_hash) {
+                return Err(MigrateUserError::BadRequest(BadMigrateUserRequest {
+                    existing_password_hash: Some(vec![err.to_string()]),
+                    ..Default::default()
+                }));
+            }
+        }
+
         let params = MigrateUserParams {
             migrate_user_request,
         };
-        crate::apis::user_service_api::migrate_user(&self.config, params)
+        crate::apis::user_service_api::migrate_user(self.config, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -547,29 +851,43 @@ impl UserService<'_> {
         &self,
         migrate_user_password_request: MigrateUserPasswordRequest,
     ) -> Result<(), MigrateUserPasswordError> {
-        let params = MigrateUserPasswordParams {
-            migrate_user_password_request,
-        };
-        crate::apis::user_service_api::migrate_user_password(&self.config, params)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    MigrateUserPasswordError::UnexpectedException,
-                    |status_code, err_entity| match (status_code.as_u16(), err_entity) {
-                        (
-                            _,
-                            Some(crate::apis::user_service_api::MigrateUserPasswordError::Status400(
-                                bad_request,
-                            )),
-                        ) => MigrateUserPasswordError::BadRequest(bad_request),
-                        (401, _) => MigrateUserPasswordError::InvalidApiKey,
-                        (429, _) => MigrateUserPasswordError::PropelAuthRateLimit,
-                        _ => MigrateUserPasswordError::UnexpectedException,
-                    },
-                )
-            })?;
+        if let Err(err) = self.check_password_hash(&migrate_user_password_request.password_hash) {
+            return Err(MigrateUserPasswordError::BadRequest(
+                BadMigrateUserPasswordRequest {
+                    password_hash: Some(vec![err.to_string()]),
+                    ..Default::default()
+                },
+            ));
+        }
+
+        invalidating(self.config, CacheKey::for_user(&migrate_user_password_request.user_id), async move {
+            let params = MigrateUserPasswordParams {
+                migrate_user_password_request,
+            };
+            crate::apis::user_service_api::migrate_user_password(self.config, params)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        MigrateUserPasswordError::UnexpectedException,
+                        |status_code, err_entity| match (status_code.as_u16(), err_entity) {
+                            (
+                                _,
+                                Some(
+                                    crate::apis::user_service_api::MigrateUserPasswordError::Status400(
+                                        bad_request,
+                                    ),
+                                ),
+                            ) => MigrateUserPasswordError::BadRequest(bad_request),
+                            (401, _) => MigrateUserPasswordError::InvalidApiKey,
+                            (429, _) => MigrateUserPasswordError::PropelAuthRateLimit,
+                            _ => MigrateUserPasswordError::UnexpectedException,
+                        },
+                    )
+                })?;
             Ok(())
+        })
+        .await
     }
 
     pub async fn create_magic_link(
@@ -579,7 +897,7 @@ impl UserService<'_> {
         let params = CreateMagicLinkParams {
             create_magic_link_request,
         };
-        crate::apis::user_service_api::create_magic_link(&self.config, params)
+        crate::apis::user_service_api::create_magic_link(self.config, params)
             .await
             .map_err(|err| {
                 map_autogenerated_error(
@@ -600,53 +918,101 @@ impl UserService<'_> {
             })
     }
 
+    #[deprecated(note = "use `enable_user_can_create_orgs_by_id`, which takes a `UserId`")]
     pub async fn enable_user_can_create_orgs(
         &self,
         user_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.enable_user_can_create_orgs_by_raw_id(user_id).await
+    }
+
+    pub async fn enable_user_can_create_orgs_by_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.enable_user_can_create_orgs_by_raw_id(user_id.to_string())
+            .await
+    }
+
+    /// Sends `user_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn enable_user_can_create_orgs_by_raw_id(
+        &self,
+        user_id: String,
     ) -> Result<(), ErrorsWithNotFound> {
         if !is_valid_id(&user_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        crate::apis::user_service_api::enable_user_can_create_orgs(&self.config, user_id)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
-        Ok(())
+        invalidating(self.config, CacheKey::for_user(&user_id), async move {
+            crate::apis::user_service_api::enable_user_can_create_orgs(self.config, user_id)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
     }
 
+    #[deprecated(note = "use `disable_user_can_create_orgs_by_id`, which takes a `UserId`")]
     pub async fn disable_user_can_create_orgs(
         &self,
         user_id: String,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.disable_user_can_create_orgs_by_raw_id(user_id).await
+    }
+
+    pub async fn disable_user_can_create_orgs_by_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<(), ErrorsWithNotFound> {
+        self.disable_user_can_create_orgs_by_raw_id(user_id.to_string())
+            .await
+    }
+
+    /// Sends `user_id` exactly as given, which the deprecated `String` methods rely on
+    pub(crate) async fn disable_user_can_create_orgs_by_raw_id(
+        &self,
+        user_id: String,
     ) -> Result<(), ErrorsWithNotFound> {
         if !is_valid_id(&user_id) {
             return Err(ErrorsWithNotFound::NotFound);
         }
 
-        crate::apis::user_service_api::disable_user_can_create_orgs(&self.config, user_id)
-            .await
-            .map_err(|err| {
-                map_autogenerated_error(
-                    err,
-                    ErrorsWithNotFound::UnexpectedException,
-                    |status_code, _| match status_code.as_u16() {
-                        401 => ErrorsWithNotFound::InvalidApiKey,
-                        429 => ErrorsWithNotFound::PropelAuthRateLimit,
-                        404 => ErrorsWithNotFound::NotFound,
-                        _ => ErrorsWithNotFound::UnexpectedException,
-                    },
-                )
-            })?;
+        invalidating(self.config, CacheKey::for_user(&user_id), async move {
+            crate::apis::user_service_api::disable_user_can_create_orgs(self.config, user_id)
+                .await
+                .map_err(|err| {
+                    map_autogenerated_error(
+                        err,
+                        ErrorsWithNotFound::UnexpectedException,
+                        |status_code, _| match status_code.as_u16() {
+                            401 => ErrorsWithNotFound::InvalidApiKey,
+                            429 => ErrorsWithNotFound::PropelAuthRateLimit,
+                            404 => ErrorsWithNotFound::NotFound,
+                            _ => ErrorsWithNotFound::UnexpectedException,
+                        },
+                    )
+                })?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Only validates when strict password hashes are enabled
+    fn check_password_hash(&self, password_hash: &str) -> Result<(), InvalidPasswordHashError> {
+        if self.config.strict_password_hashes {
+            validate_password_hash(password_hash)?;
+        }
         Ok(())
     }
 }
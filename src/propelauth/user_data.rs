@@ -0,0 +1,384 @@
+//! Everything PropelAuth knows about a single user, gathered into one document for data
+//! subject access requests.
+
+use std::collections::HashMap;
+use std::future::ready;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures_util::stream::TryStreamExt;
+use serde_json::Value;
+
+use crate::apis::api_key_service_api::ApiKeyQueryParams;
+use crate::apis::org_service_api::FetchPendingInvitesParams;
+use crate::apis::user_service_api::FetchUserByIdParams;
+use crate::models::fetch_pending_invites::PendingInviteResponse;
+use crate::models::{FetchApiKeyResponse, UserInOrg, UserMetadata};
+use crate::propelauth::api_key::ApiKeyService;
+use crate::propelauth::errors::ExportUserDataError;
+use crate::propelauth::ids::UserId;
+use crate::propelauth::org::OrgService;
+use crate::propelauth::pagination::PaginationOptions;
+use crate::propelauth::user::UserService;
+
+/// A user's data as returned by [`UserService::export_user_data`]. All timestamps are RFC 3339
+/// strings in UTC, except ones too far out to be dates, which are kept as the Unix seconds
+/// PropelAuth returned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserDataExport {
+    pub exported_at: String,
+    pub profile: UserDataProfile,
+    pub orgs: Vec<UserDataOrg>,
+    /// API keys that belong to the user and no org
+    pub personal_api_keys: Vec<UserDataApiKey>,
+    /// API keys that belong to an org and were created for the user
+    pub org_api_keys: Vec<UserDataApiKey>,
+    /// Invites the user sent that haven't been accepted yet
+    pub sent_invites: Vec<UserDataInvite>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserDataProfile {
+    pub user_id: String,
+    pub email: String,
+    pub email_confirmed: bool,
+    pub has_password: bool,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub picture_url: Option<String>,
+    pub locked: bool,
+    pub enabled: bool,
+    pub mfa_enabled: bool,
+    pub created_at: String,
+    pub last_active_at: String,
+    pub legacy_user_id: Option<String>,
+    pub metadata: HashMap<String, Value>,
+    pub properties: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserDataOrg {
+    pub org_id: String,
+    pub org_name: String,
+    pub role: String,
+    pub additional_roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserDataApiKey {
+    pub api_key_id: String,
+    pub org_id: Option<String>,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    pub archived: bool,
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserDataInvite {
+    pub invitee_email: String,
+    pub org_id: String,
+    pub org_name: String,
+    pub role_in_org: String,
+    pub additional_roles_in_org: Vec<String>,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+fn rfc3339(seconds: i64, timestamp: Option<DateTime<Utc>>) -> String {
+    match timestamp {
+        Some(timestamp) => timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+        None => seconds.to_string(),
+    }
+}
+
+impl From<UserMetadata> for UserDataProfile {
+    fn from(user: UserMetadata) -> Self {
+        let created_at = rfc3339(user.created_at, user.created_at_utc());
+        let last_active_at = rfc3339(user.last_active_at, user.last_active_at_utc());
+        UserDataProfile {
+            user_id: user.user_id,
+            email: user.email,
+            email_confirmed: user.email_confirmed,
+            has_password: user.has_password,
+            username: user.username,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            picture_url: user.picture_url,
+            locked: user.locked,
+            enabled: user.enabled,
+            mfa_enabled: user.mfa_enabled,
+            created_at,
+            last_active_at,
+            legacy_user_id: user.legacy_user_id,
+            metadata: user.metadata.unwrap_or_default(),
+            properties: user.properties.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<UserInOrg> for UserDataOrg {
+    fn from(org: UserInOrg) -> Self {
+        UserDataOrg {
+            org_id: org.org_id,
+            org_name: org.org_name,
+            role: org.user_role,
+            additional_roles: org.additional_roles,
+            permissions: org.user_permissions,
+        }
+    }
+}
+
+impl UserDataApiKey {
+    fn new(api_key: FetchApiKeyResponse, archived: bool) -> Self {
+        let created_at = rfc3339(api_key.created_at, api_key.created_at_utc());
+        let expires_at = api_key
+            .expires_at_seconds
+            .map(|seconds| rfc3339(seconds, api_key.expires_at_utc()));
+        UserDataApiKey {
+            api_key_id: api_key.api_key_id,
+            org_id: api_key.org_id,
+            created_at,
+            expires_at,
+            archived,
+            metadata: api_key.metadata,
+        }
+    }
+}
+
+impl From<PendingInviteResponse> for UserDataInvite {
+    fn from(invite: PendingInviteResponse) -> Self {
+        let created_at = rfc3339(invite.created_at, invite.created_at_utc());
+        let expires_at = rfc3339(invite.expires_at, invite.expires_at_utc());
+        UserDataInvite {
+            invitee_email: invite.invitee_email,
+            org_id: invite.org_id,
+            org_name: invite.org_name,
+            role_in_org: invite.role_in_org,
+            additional_roles_in_org: invite.additional_roles_in_org,
+            created_at,
+            expires_at,
+        }
+    }
+}
+
+impl<'a> UserService<'a> {
+    /// Gathers the user's profile, orgs, current and archived API keys, and the pending invites
+    /// they sent into a single document.
+    ///
+    /// Finding the user's invites means reading every pending invite in the project, so this
+    /// can take a while for projects with many outstanding invites.
+    pub async fn export_user_data(
+        &self,
+        user_id: UserId,
+    ) -> Result<UserDataExport, ExportUserDataError> {
+        let user_id = user_id.to_string();
+        let mut user = self
+            .fetch_user_by_id(FetchUserByIdParams {
+                user_id: user_id.clone(),
+                include_orgs: Some(true),
+            })
+            .await
+            .map_err(ExportUserDataError::User)?;
+
+        let api_keys = ApiKeyService {
+            config: self.config,
+        };
+        let params = ApiKeyQueryParams {
+            user_id: Some(user_id.clone()),
+            ..Default::default()
+        };
+        let current: Vec<_> = api_keys
+            .fetch_current_api_keys_stream(params.clone(), PaginationOptions::default())
+            .map_ok(|api_key| UserDataApiKey::new(api_key, false))
+            .try_collect()
+            .await
+            .map_err(ExportUserDataError::ApiKeys)?;
+        let archived: Vec<_> = api_keys
+            .fetch_archived_api_keys_stream(params, PaginationOptions::default())
+            .map_ok(|api_key| UserDataApiKey::new(api_key, true))
+            .try_collect()
+            .await
+            .map_err(ExportUserDataError::ApiKeys)?;
+        let (org_api_keys, personal_api_keys) = current
+            .into_iter()
+            .chain(archived)
+            .partition(|api_key| api_key.org_id.is_some());
+
+        let sent_invites = OrgService {
+            config: self.config,
+        }
+        .fetch_pending_invites_stream(
+            FetchPendingInvitesParams::default(),
+            PaginationOptions::default(),
+        )
+        .try_filter(|invite| {
+            let sent_by_user = invite.inviter_user_id.as_deref() == Some(user_id.as_str());
+            ready(sent_by_user)
+        })
+        .map_ok(UserDataInvite::from)
+        .try_collect()
+        .await
+        .map_err(ExportUserDataError::Invites)?;
+
+        let mut orgs: Vec<UserDataOrg> = user
+            .org_id_to_org_info
+            .take()
+            .unwrap_or_default()
+            .into_values()
+            .map(UserDataOrg::from)
+            .collect();
+        orgs.sort_by(|a, b| a.org_id.cmp(&b.org_id));
+
+        Ok(UserDataExport {
+            exported_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            profile: user.into(),
+            orgs,
+            personal_api_keys,
+            org_api_keys,
+            sent_invites,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::UserMetadata;
+    use crate::propelauth::user_data::{rfc3339, UserDataProfile};
+
+    #[test]
+    fn timestamps_are_rfc3339_in_utc() {
+        let user = UserMetadata {
+            created_at: 1_709_209_805,
+            ..Default::default()
+        };
+        assert_eq!(
+            "1970-01-01T00:00:00Z",
+            rfc3339(user.last_active_at, user.last_active_at_utc())
+        );
+        assert_eq!(
+            "2024-02-29T12:30:05Z",
+            rfc3339(user.created_at, user.created_at_utc())
+        );
+    }
+
+    #[test]
+    fn out_of_range_timestamps_keep_their_seconds() {
+        let user = UserMetadata {
+            created_at: i64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(None, user.created_at_utc());
+        assert_eq!(i64::MAX.to_string(), UserDataProfile::from(user).created_at);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn gathers_profile_orgs_and_api_keys() {
+        use std::collections::HashMap;
+
+        use serde_json::json;
+
+        use crate::apis::api_key_service_api::CreateApiKeyParams;
+        use crate::models::{AddUserToOrgRequest, CreateOrgRequest, CreateUserRequest};
+        use crate::propelauth::errors::{ErrorsWithNotFound, ExportUserDataError};
+        use crate::testing::FakeBackend;
+
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    properties: Some(HashMap::from([("plan".to_string(), json!("pro"))])),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            let org_id = auth
+                .org()
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id;
+            auth.org()
+                .add_user_to_org(AddUserToOrgRequest {
+                    user_id: user_id.clone(),
+                    org_id: org_id.clone(),
+                    role: "Admin".to_string(),
+                    additional_roles: vec![],
+                })
+                .await
+                .unwrap();
+            let personal_key = auth
+                .api_key()
+                .create_api_key(CreateApiKeyParams {
+                    user_id: Some(user_id.clone()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .api_key_id;
+            let org_key = auth
+                .api_key()
+                .create_api_key(CreateApiKeyParams {
+                    user_id: Some(user_id.clone()),
+                    org_id: Some(org_id.clone()),
+                    expires_at_seconds: Some(1_893_456_000),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .api_key_id;
+            auth.api_key()
+                .delete_api_key_by_id(personal_key.parse().unwrap())
+                .await
+                .unwrap();
+
+            let export = auth
+                .user()
+                .export_user_data(user_id.parse().unwrap())
+                .await
+                .unwrap();
+            assert_eq!("user@example.com", export.profile.email);
+            assert_eq!(Some(&json!("pro")), export.profile.properties.get("plan"));
+            assert!(export.profile.created_at.ends_with('Z'));
+            assert_eq!(1, export.orgs.len());
+            assert_eq!("Admin", export.orgs[0].role);
+
+            assert_eq!(1, export.personal_api_keys.len());
+            assert_eq!(personal_key, export.personal_api_keys[0].api_key_id);
+            assert!(export.personal_api_keys[0].archived);
+            assert_eq!(1, export.org_api_keys.len());
+            assert_eq!(org_key, export.org_api_keys[0].api_key_id);
+            assert_eq!(
+                Some("2030-01-01T00:00:00Z"),
+                export.org_api_keys[0].expires_at.as_deref()
+            );
+            assert!(export.sent_invites.is_empty());
+
+            let document = serde_json::to_value(&export).unwrap();
+            assert_eq!(json!(org_id), document["org_api_keys"][0]["org_id"]);
+
+            let missing = auth
+                .user()
+                .export_user_data("8a4c2b5e-0d0b-4f7e-9c52-3b8f5e0f6a11".parse().unwrap())
+                .await;
+            assert!(matches!(
+                missing,
+                Err(ExportUserDataError::User(ErrorsWithNotFound::NotFound))
+            ));
+        });
+    }
+}
@@ -0,0 +1,1793 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+use chrono::{Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use url::form_urlencoded;
+use uuid::Uuid;
+
+use crate::models::{
+    AddUserToOrgRequest, ChangeUserRoleInOrgRequest, CreateAccessTokenV2Request,
+    CreateMagicLinkRequest, CreateOrgRequest, CreateUserRequest, EmailsQuery, FetchApiKeyResponse,
+    FetchOrgBasicResponse, FetchOrgResponse, InviteUserToOrgRequest, MigrateUserPasswordRequest,
+    MigrateUserRequest, RemoveUserFromOrgRequest, ResendEmailConfirmationRequest,
+    RevokePendingOrgInviteRequest, UpdateEmailRequest, UpdateMetadataRequest,
+    UpdatePasswordRequest, UserIdsQuery, UserInOrg, UserMetadata, UsernamesQuery,
+};
+use crate::propelauth::auth::PropelAuth;
+use crate::propelauth::options::AuthOptionsWithTokenVerification;
+use crate::propelauth::token_models::{LoginMethod, OrgRoleStructure, User};
+use crate::testing::test_auth::TEST_AUTH_URL;
+use crate::testing::{OrgMemberInfoBuilder, TestAuth, TokenOptions};
+
+const API_PREFIX: &str = "/api/backend/v1";
+const DEFAULT_PAGE_SIZE: usize = 10;
+const MAX_PAGE_SIZE: usize = 100;
+const MAX_BATCH_SIZE: usize = 100;
+
+/// An in-process stand-in for the PropelAuth backend API.
+///
+/// It serves the same endpoints the services in this crate call, keeps users, orgs,
+/// memberships, invites and API keys in memory, and answers with the same status codes and
+/// `Bad*Request` bodies as the real API. Access tokens it creates are signed by its own
+/// [`TestAuth`], so they verify against [`auth()`](Self::auth).
+///
+/// The server runs on a background thread and shuts down when the `FakeBackend` is dropped.
+pub struct FakeBackend {
+    addr: SocketAddr,
+    base_url: String,
+    api_key: String,
+    test_auth: TestAuth,
+    state: Arc<Mutex<State>>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl FakeBackend {
+    /// Starts a fake backend on a random local port.
+    ///
+    /// Panics if the server can't be started.
+    pub fn start() -> FakeBackend {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Unable to start the fake backend");
+        let addr = listener
+            .local_addr()
+            .expect("Unable to start the fake backend");
+
+        let fake_backend = FakeBackend {
+            addr,
+            base_url: format!("http://{}", addr),
+            api_key: Uuid::new_v4().simple().to_string(),
+            test_auth: TestAuth::with_auth_url(TEST_AUTH_URL),
+            state: Arc::new(Mutex::new(State::default())),
+            shutdown: Arc::new(AtomicBool::new(false)),
+        };
+
+        let server = Arc::new(Server {
+            api_key: fake_backend.api_key.clone(),
+            test_auth: fake_backend.test_auth.clone(),
+            state: fake_backend.state.clone(),
+        });
+        let shutdown = fake_backend.shutdown.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if shutdown.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let server = server.clone();
+                    thread::spawn(move || server.handle_connection(stream));
+                }
+            }
+        });
+
+        fake_backend
+    }
+
+    /// A `PropelAuth` which sends all backend API requests to this fake and trusts the access
+    /// tokens it (or [`test_auth()`](Self::test_auth)) mints.
+    pub fn auth(&self) -> PropelAuth {
+        let mut auth = PropelAuth::init(AuthOptionsWithTokenVerification {
+            auth_url: TEST_AUTH_URL.to_string(),
+            api_key: self.api_key.clone(),
+            manual_token_verification_metadata: self.test_auth.token_verification_metadata(),
+        })
+        .expect("Unable to initialize the fake backend's PropelAuth");
+        auth.config.base_path = self.base_url.clone();
+        auth
+    }
+
+    /// The URL to use as the backend API base path
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// The only API key the fake accepts. Any other key gets a 401.
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Mints access tokens which [`auth()`](Self::auth) will accept
+    pub fn test_auth(&self) -> &TestAuth {
+        &self.test_auth
+    }
+
+    /// Makes the next `count` requests fail with a 429, as if the API rate limit was hit
+    pub fn rate_limit_next_requests(&self, count: usize) {
+        self.state().requests_to_rate_limit = count;
+    }
+
+    /// All users currently stored, in creation order
+    pub fn users(&self) -> Vec<UserMetadata> {
+        let state = self.state();
+        let mut users: Vec<&FakeUser> = state.users.values().collect();
+        users.sort_by_key(|user| user.sequence);
+        users
+            .into_iter()
+            .map(|user| state.user_metadata(user, true))
+            .collect()
+    }
+
+    /// Directly edits a stored user, for fields the API can't set such as `last_active_at`,
+    /// `created_at`, `locked` or `mfa_enabled`. Returns false if the user doesn't exist.
+    pub fn modify_user<F: FnOnce(&mut UserMetadata)>(&self, user_id: &str, f: F) -> bool {
+        match self.state().users.get_mut(user_id) {
+            Some(user) => {
+                f(&mut user.metadata);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for FakeBackend {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake up the accept loop so it notices the shutdown
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+impl std::fmt::Debug for FakeBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FakeBackend")
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Default)]
+struct State {
+    users: HashMap<String, FakeUser>,
+    orgs: HashMap<String, FakeOrg>,
+    invites: Vec<FakeInvite>,
+    api_keys: HashMap<String, FakeApiKey>,
+    requests_to_rate_limit: usize,
+    next_sequence: u64,
+}
+
+struct FakeUser {
+    sequence: u64,
+    metadata: UserMetadata,
+}
+
+struct FakeOrg {
+    sequence: u64,
+    org: FetchOrgResponse,
+    members: HashMap<String, Membership>,
+}
+
+struct Membership {
+    role: String,
+    additional_roles: Vec<String>,
+}
+
+struct FakeInvite {
+    invitee_email: String,
+    org_id: String,
+    role: String,
+    additional_roles: Vec<String>,
+    created_at: i64,
+    expires_at: i64,
+}
+
+struct FakeApiKey {
+    sequence: u64,
+    api_key: FetchApiKeyResponse,
+    token: String,
+    archived: bool,
+}
+
+impl State {
+    fn next_sequence(&mut self) -> u64 {
+        self.next_sequence += 1;
+        self.next_sequence
+    }
+
+    fn user_by_email(&self, email: &str) -> Option<&FakeUser> {
+        self.users
+            .values()
+            .find(|user| user.metadata.email.eq_ignore_ascii_case(email))
+    }
+
+    fn user_by_username(&self, username: &str) -> Option<&FakeUser> {
+        self.users
+            .values()
+            .find(|user| user.metadata.username.as_deref() == Some(username))
+    }
+
+    fn user_metadata(&self, user: &FakeUser, include_orgs: bool) -> UserMetadata {
+        let mut metadata = user.metadata.clone();
+        if include_orgs {
+            metadata.org_id_to_org_info = Some(
+                self.orgs
+                    .values()
+                    .filter_map(|org| {
+                        org.members.get(&metadata.user_id).map(|membership| {
+                            (org.org.org_id.clone(), user_in_org(org, membership))
+                        })
+                    })
+                    .collect(),
+            );
+        }
+        metadata
+    }
+
+    fn insert_user(&mut self, metadata: UserMetadata) -> String {
+        let user_id = metadata.user_id.clone();
+        let sequence = self.next_sequence();
+        self.users
+            .insert(user_id.clone(), FakeUser { sequence, metadata });
+        user_id
+    }
+
+    fn remove_user(&mut self, user_id: &str) {
+        self.users.remove(user_id);
+        for org in self.orgs.values_mut() {
+            org.members.remove(user_id);
+        }
+        for api_key in self.api_keys.values_mut() {
+            if api_key.api_key.user_id.as_deref() == Some(user_id) {
+                api_key.archived = true;
+            }
+        }
+    }
+}
+
+fn user_in_org(org: &FakeOrg, membership: &Membership) -> UserInOrg {
+    UserInOrg {
+        org_id: org.org.org_id.clone(),
+        org_name: org.org.name.clone(),
+        org_metadata: org.org.metadata.metadata.clone(),
+        org_role_structure: org_role_structure(membership),
+        user_role: membership.role.clone(),
+        inherited_user_roles_plus_current_role: vec![membership.role.clone()],
+        user_permissions: vec![],
+        additional_roles: membership.additional_roles.clone(),
+    }
+}
+
+fn org_role_structure(membership: &Membership) -> OrgRoleStructure {
+    if membership.additional_roles.is_empty() {
+        OrgRoleStructure::SingleRoleInHierarchy
+    } else {
+        OrgRoleStructure::MultiRole
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn json<T: DeserializeOwned>(&self) -> Result<T, Response> {
+        serde_json::from_slice(&self.body).map_err(|_| Response::empty(400))
+    }
+
+    fn query_bool(&self, name: &str) -> bool {
+        self.query.get(name).map(|v| v == "true").unwrap_or(false)
+    }
+}
+
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn ok<T: Serialize>(body: &T) -> Response {
+        Response {
+            status: 200,
+            body: serde_json::to_string(body).unwrap_or_default(),
+        }
+    }
+
+    fn success() -> Response {
+        Response::ok(&json!({}))
+    }
+
+    // The services map 401/404/429 on the status code alone, and an object body would be
+    // mistaken for a `Bad*Request`, so those are sent without a body
+    fn empty(status: u16) -> Response {
+        Response {
+            status,
+            body: String::new(),
+        }
+    }
+
+    fn not_found() -> Response {
+        Response::empty(404)
+    }
+
+    fn bad_request(field: &str, message: &str) -> Response {
+        Response {
+            status: 400,
+            body: json!({ field: [message] }).to_string(),
+        }
+    }
+}
+
+type Handled = Result<Response, Response>;
+
+struct Server {
+    api_key: String,
+    test_auth: TestAuth,
+    state: Arc<Mutex<State>>,
+}
+
+impl Server {
+    fn handle_connection(&self, mut stream: TcpStream) {
+        let response = match read_request(&stream) {
+            Some(request) => self.handle(request),
+            None => return,
+        };
+        let reason = match response.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            429 => "Too Many Requests",
+            _ => "Unknown",
+        };
+        let _ = write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.status,
+            reason,
+            response.body.len(),
+            response.body
+        );
+        let _ = stream.flush();
+    }
+
+    fn handle(&self, request: Request) -> Response {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.requests_to_rate_limit > 0 {
+            state.requests_to_rate_limit -= 1;
+            return Response::empty(429);
+        }
+        if request.authorization.as_deref() != Some(&format!("Bearer {}", self.api_key)) {
+            return Response::empty(401);
+        }
+        let path = match request.path.strip_prefix(API_PREFIX) {
+            Some(path) => path.trim_end_matches('/').to_string(),
+            None => return Response::not_found(),
+        };
+        let segments: Vec<&str> = path.split('/').skip(1).collect();
+
+        self.route(&mut state, &request, &segments)
+            .unwrap_or_else(|response| response)
+    }
+
+    fn route(&self, state: &mut State, request: &Request, segments: &[&str]) -> Handled {
+        match (request.method.as_str(), segments) {
+            ("GET", ["token_verification_metadata"]) => {
+                Ok(Response::ok(&self.test_auth.token_verification_metadata()))
+            }
+
+            ("POST", ["user"]) => create_user(state, request),
+            ("GET", ["user", "email"]) => fetch_user_by(state, request, |user, query| {
+                query
+                    .get("email")
+                    .map(|email| user.metadata.email.eq_ignore_ascii_case(email))
+                    .unwrap_or(false)
+            }),
+            ("GET", ["user", "username"]) => fetch_user_by(state, request, |user, query| {
+                user.metadata.username.as_ref() == query.get("username")
+            }),
+            ("GET", ["user", "query"]) => fetch_users_by_query(state, request),
+            ("POST", ["user", "user_ids"]) => {
+                let query: UserIdsQuery = request.json()?;
+                fetch_users_in_batch(state, request, query.user_ids, |state, id| {
+                    state.users.get(id)
+                })
+            }
+            ("POST", ["user", "emails"]) => {
+                let query: EmailsQuery = request.json()?;
+                fetch_users_in_batch(state, request, query.emails, State::user_by_email)
+            }
+            ("POST", ["user", "usernames"]) => {
+                let query: UsernamesQuery = request.json()?;
+                fetch_users_in_batch(state, request, query.usernames, State::user_by_username)
+            }
+            ("GET", ["user", "org", org_id]) => fetch_users_in_org(state, request, org_id),
+            ("GET", ["user", user_id]) => {
+                fetch_user_by(state, request, |user, _| user.metadata.user_id == *user_id)
+            }
+            ("PUT", ["user", user_id]) => update_user_metadata(state, request, user_id),
+            ("DELETE", ["user", user_id]) => {
+                find_user(state, user_id)?;
+                state.remove_user(user_id);
+                Ok(Response::success())
+            }
+            ("PUT", ["user", user_id, "email"]) => update_user_email(state, request, user_id),
+            ("PUT", ["user", user_id, "password"]) => {
+                let update: UpdatePasswordRequest = request.json()?;
+                if update.password.len() < 8 {
+                    return Err(Response::bad_request(
+                        "password",
+                        "Password must be at least 8 characters",
+                    ));
+                }
+                modify_user(state, user_id, |user| user.has_password = true)
+            }
+            ("PUT", ["user", user_id, "clear_password"]) => {
+                modify_user(state, user_id, |user| user.has_password = false)
+            }
+            ("POST", ["user", user_id, "disable"]) => {
+                modify_user(state, user_id, |user| user.enabled = false)
+            }
+            ("POST", ["user", user_id, "enable"]) => {
+                modify_user(state, user_id, |user| user.enabled = true)
+            }
+            ("POST", ["user", user_id, "disable_2fa"]) => {
+                modify_user(state, user_id, |user| user.mfa_enabled = false)
+            }
+            ("POST", ["user", user_id, "logout_all_sessions"])
+            | ("POST", ["user", user_id, "can_create_orgs", "enable"])
+            | ("POST", ["user", user_id, "can_create_orgs", "disable"]) => {
+                modify_user(state, user_id, |_| {})
+            }
+            ("POST", ["resend_email_confirmation"]) => {
+                let resend: ResendEmailConfirmationRequest = request.json()?;
+                let user = find_user(state, &resend.user_id)?;
+                if user.metadata.email_confirmed {
+                    Ok(Response {
+                        status: 400,
+                        body: json!({ "user_facing_error": "Email is already confirmed" })
+                            .to_string(),
+                    })
+                } else {
+                    Ok(Response::success())
+                }
+            }
+            ("POST", ["magic_link"]) => create_magic_link(state, request),
+            ("POST", ["migrate_user"]) => migrate_user(state, request),
+            ("POST", ["migrate_user", "password"]) => {
+                let migrate: MigrateUserPasswordRequest = request.json()?;
+                if migrate.password_hash.is_empty() {
+                    return Err(Response::bad_request(
+                        "password_hash",
+                        "This field is required",
+                    ));
+                }
+                match state.users.get_mut(&migrate.user_id) {
+                    Some(user) => {
+                        user.metadata.has_password = true;
+                        Ok(Response::success())
+                    }
+                    None => Err(Response::bad_request("user_id", "User not found")),
+                }
+            }
+
+            ("POST", ["org"]) => create_org(state, request),
+            ("GET", ["org", "query"]) => fetch_orgs_by_query(state, request),
+            ("POST", ["org", "add_user"]) => {
+                let add: AddUserToOrgRequest = request.json()?;
+                set_membership(
+                    state,
+                    &add.org_id,
+                    &add.user_id,
+                    add.role,
+                    add.additional_roles,
+                )
+            }
+            ("POST", ["org", "change_role"]) => {
+                let change: ChangeUserRoleInOrgRequest = request.json()?;
+                find_membership(state, &change.org_id, &change.user_id)?;
+                set_membership(
+                    state,
+                    &change.org_id,
+                    &change.user_id,
+                    change.role,
+                    change.additional_roles,
+                )
+            }
+            ("POST", ["org", "remove_user"]) => {
+                let remove: RemoveUserFromOrgRequest = request.json()?;
+                find_membership(state, &remove.org_id, &remove.user_id)?;
+                if let Some(org) = state.orgs.get_mut(&remove.org_id) {
+                    org.members.remove(&remove.user_id);
+                }
+                Ok(Response::success())
+            }
+            ("GET", ["org", org_id]) => Ok(Response::ok(&find_org(state, org_id)?.org)),
+            ("PUT", ["org", org_id]) => update_org(state, request, org_id),
+            ("DELETE", ["org", org_id]) => {
+                find_org(state, org_id)?;
+                state.orgs.remove(*org_id);
+                state.invites.retain(|invite| invite.org_id != *org_id);
+                Ok(Response::success())
+            }
+            ("POST", ["org", org_id, "allow_saml"]) => {
+                modify_org(state, org_id, |org| org.can_setup_saml = true)
+            }
+            ("POST", ["org", org_id, "disallow_saml"]) => {
+                modify_org(state, org_id, |org| org.can_setup_saml = false)
+            }
+            ("POST", ["org", org_id, "create_saml_connection_link"]) => {
+                find_org(state, org_id)?;
+                Ok(Response::ok(&json!({
+                    "url": format!("{}/saml/setup/{}/{}", TEST_AUTH_URL, org_id, Uuid::new_v4().simple()),
+                })))
+            }
+            ("GET", ["saml_sp_metadata", org_id]) => {
+                find_org(state, org_id)?;
+                Ok(Response::ok(&json!({
+                    "entity_id": format!("{}/saml/{}/metadata", TEST_AUTH_URL, org_id),
+                    "acs_url": format!("{}/saml/{}/acs", TEST_AUTH_URL, org_id),
+                    "logout_url": format!("{}/saml/{}/logout", TEST_AUTH_URL, org_id),
+                })))
+            }
+            ("POST", ["saml_idp_metadata"]) => {
+                let set_idp: Value = request.json()?;
+                let org_id = set_idp["org_id"].as_str().unwrap_or_default().to_string();
+                modify_org(state, &org_id, |org| {
+                    org.is_saml_configured = true;
+                    org.is_saml_in_test_mode = true;
+                })
+            }
+            ("POST", ["saml_idp_metadata", "go_live", org_id]) => {
+                modify_org(state, org_id, |org| org.is_saml_in_test_mode = false)
+            }
+            ("DELETE", ["saml_idp_metadata", org_id]) => modify_org(state, org_id, |org| {
+                org.is_saml_configured = false;
+                org.is_saml_in_test_mode = false;
+            }),
+            ("GET", ["custom_role_mappings"]) => {
+                let mut num_orgs_subscribed: HashMap<&String, i32> = HashMap::new();
+                for org in state.orgs.values() {
+                    if let Some(name) = &org.org.custom_role_mapping_name {
+                        *num_orgs_subscribed.entry(name).or_default() += 1;
+                    }
+                }
+                Ok(Response::ok(&json!({
+                    "custom_role_mappings": num_orgs_subscribed
+                        .into_iter()
+                        .map(|(name, count)| json!({
+                            "custom_role_mapping_name": name,
+                            "num_orgs_subscribed": count,
+                        }))
+                        .collect::<Vec<_>>(),
+                })))
+            }
+            ("POST", ["invite_user"]) => invite_user_to_org(state, request),
+            ("GET", ["pending_org_invites"]) => fetch_pending_invites(state, request),
+            ("DELETE", ["pending_org_invites"]) => {
+                let revoke: RevokePendingOrgInviteRequest = request.json()?;
+                let num_invites = state.invites.len();
+                state.invites.retain(|invite| {
+                    invite.org_id != revoke.org_id
+                        || !invite
+                            .invitee_email
+                            .eq_ignore_ascii_case(&revoke.invitee_email)
+                });
+                if state.invites.len() == num_invites {
+                    Err(Response::not_found())
+                } else {
+                    Ok(Response::success())
+                }
+            }
+
+            ("GET", ["end_user_api_keys"]) => fetch_api_keys(state, request, false),
+            ("GET", ["end_user_api_keys", "archived"]) => fetch_api_keys(state, request, true),
+            ("POST", ["end_user_api_keys"]) => create_api_key(state, request),
+            ("POST", ["end_user_api_keys", "validate"]) => validate_api_key(state, request),
+            ("GET", ["end_user_api_keys", api_key_id]) => {
+                Ok(Response::ok(&find_api_key(state, api_key_id)?.api_key))
+            }
+            ("PATCH", ["end_user_api_keys", api_key_id]) => {
+                update_api_key(state, request, api_key_id)
+            }
+            ("DELETE", ["end_user_api_keys", api_key_id]) => {
+                find_api_key(state, api_key_id)?;
+                if let Some(api_key) = state.api_keys.get_mut(*api_key_id) {
+                    api_key.archived = true;
+                }
+                Ok(Response::success())
+            }
+
+            ("POST", ["access_token"]) => self.create_access_token(state, request),
+
+            _ => Err(Response::not_found()),
+        }
+    }
+
+    fn create_access_token(&self, state: &State, request: &Request) -> Handled {
+        let create: CreateAccessTokenV2Request = request.json()?;
+        let user = find_user(state, &create.user_id)?;
+        let metadata = state.user_metadata(user, true);
+
+        let org_id_to_org_member_info = metadata
+            .org_id_to_org_info
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(org_id, org)| {
+                let org_member_info = match org.org_role_structure {
+                    OrgRoleStructure::SingleRoleInHierarchy => {
+                        OrgMemberInfoBuilder::single_role_in_hierarchy(
+                            &org.org_id,
+                            &org.org_name,
+                            &org.user_role,
+                        )
+                    }
+                    OrgRoleStructure::MultiRole => OrgMemberInfoBuilder::multi_role(
+                        &org.org_id,
+                        &org.org_name,
+                        std::iter::once(org.user_role).chain(org.additional_roles),
+                    ),
+                }
+                .org_metadata(org.org_metadata)
+                .build();
+                (org_id, org_member_info)
+            })
+            .collect::<HashMap<_, _>>();
+
+        if let Some(active_org_id) = &create.active_org_id {
+            if !org_id_to_org_member_info.contains_key(active_org_id) {
+                return Err(Response::bad_request(
+                    "active_org_id",
+                    "User is not a member of this organization",
+                ));
+            }
+        }
+
+        let user = User {
+            user_id: metadata.user_id,
+            org_id_to_org_member_info,
+            active_org_id: create.active_org_id,
+            email: metadata.email,
+            first_name: metadata.first_name,
+            last_name: metadata.last_name,
+            username: metadata.username,
+            properties: metadata.properties,
+            legacy_user_id: metadata.legacy_user_id,
+            login_method: LoginMethod::TokenGeneratedFromBackendApi,
+            ..Default::default()
+        };
+        let access_token = self.test_auth.mint_token_with_options(
+            &user,
+            TokenOptions {
+                expires_in: Duration::minutes(create.duration_in_minutes as i64),
+                ..Default::default()
+            },
+        );
+        Ok(Response::ok(&json!({ "access_token": access_token })))
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse().ok()?;
+            } else if name.eq_ignore_ascii_case("authorization") {
+                authorization = Some(value.to_string());
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    Some(Request {
+        method,
+        path: path.to_string(),
+        query: form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect(),
+        authorization,
+        body,
+    })
+}
+
+fn find_user<'a>(state: &'a State, user_id: &str) -> Result<&'a FakeUser, Response> {
+    state.users.get(user_id).ok_or_else(Response::not_found)
+}
+
+fn find_org<'a>(state: &'a State, org_id: &str) -> Result<&'a FakeOrg, Response> {
+    state.orgs.get(org_id).ok_or_else(Response::not_found)
+}
+
+fn find_membership<'a>(
+    state: &'a State,
+    org_id: &str,
+    user_id: &str,
+) -> Result<&'a Membership, Response> {
+    find_user(state, user_id)?;
+    find_org(state, org_id)?
+        .members
+        .get(user_id)
+        .ok_or_else(Response::not_found)
+}
+
+fn find_api_key<'a>(state: &'a State, api_key_id: &str) -> Result<&'a FakeApiKey, Response> {
+    state
+        .api_keys
+        .get(api_key_id)
+        .filter(|api_key| !api_key.archived)
+        .ok_or_else(Response::not_found)
+}
+
+fn modify_user<F: FnOnce(&mut UserMetadata)>(state: &mut State, user_id: &str, f: F) -> Handled {
+    let user = state
+        .users
+        .get_mut(user_id)
+        .ok_or_else(Response::not_found)?;
+    f(&mut user.metadata);
+    Ok(Response::success())
+}
+
+fn modify_org<F: FnOnce(&mut FetchOrgResponse)>(state: &mut State, org_id: &str, f: F) -> Handled {
+    let org = state.orgs.get_mut(org_id).ok_or_else(Response::not_found)?;
+    f(&mut org.org);
+    Ok(Response::success())
+}
+
+fn paginate<T>(
+    request: &Request,
+    items: Vec<T>,
+) -> Result<(Vec<T>, usize, usize, usize, bool), Response> {
+    let page_size = match request.query.get("page_size") {
+        Some(page_size) => match page_size.parse::<usize>() {
+            Ok(page_size) if (1..=MAX_PAGE_SIZE).contains(&page_size) => page_size,
+            _ => {
+                return Err(Response::bad_request(
+                    "page_size",
+                    "Ensure this value is between 1 and 100",
+                ))
+            }
+        },
+        None => DEFAULT_PAGE_SIZE,
+    };
+    let page_number = match request.query.get("page_number") {
+        Some(page_number) => page_number.parse::<usize>().map_err(|_| {
+            Response::bad_request(
+                "page_number",
+                "Ensure this value is greater than or equal to 0",
+            )
+        })?,
+        None => 0,
+    };
+
+    let total = items.len();
+    let start = page_number.saturating_mul(page_size);
+    let page = items.into_iter().skip(start).take(page_size).collect();
+    let has_more_results = start.saturating_add(page_size) < total;
+    Ok((page, total, page_number, page_size, has_more_results))
+}
+
+fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => !local.is_empty() && domain.contains('.'),
+        None => false,
+    }
+}
+
+fn new_user_metadata(email: String, email_confirmed: bool, has_password: bool) -> UserMetadata {
+    let now = Utc::now().timestamp();
+    UserMetadata::new(
+        Uuid::new_v4().to_string(),
+        email,
+        email_confirmed,
+        has_password,
+        false,
+        true,
+        false,
+        now,
+        now,
+    )
+}
+
+fn create_user(state: &mut State, request: &Request) -> Handled {
+    let create: CreateUserRequest = request.json()?;
+    if !is_valid_email(&create.email) {
+        return Err(Response::bad_request("email", "Email is not valid"));
+    }
+    if state.user_by_email(&create.email).is_some() {
+        return Err(Response::bad_request("email", "Email already in use"));
+    }
+    if let Some(username) = &create.username {
+        if state.user_by_username(username).is_some() {
+            return Err(Response::bad_request("username", "Username already in use"));
+        }
+    }
+    if let Some(password) = &create.password {
+        if password.len() < 8 {
+            return Err(Response::bad_request(
+                "password",
+                "Password must be at least 8 characters",
+            ));
+        }
+    }
+
+    let mut metadata = new_user_metadata(
+        create.email,
+        create.email_confirmed,
+        create.password.is_some(),
+    );
+    metadata.username = create.username;
+    metadata.first_name = create.first_name;
+    metadata.last_name = create.last_name;
+    metadata.properties = create.properties;
+
+    let user_id = state.insert_user(metadata);
+    Ok(Response::ok(&json!({ "user_id": user_id })))
+}
+
+fn migrate_user(state: &mut State, request: &Request) -> Handled {
+    let migrate: MigrateUserRequest = request.json()?;
+    if !is_valid_email(&migrate.email) {
+        return Err(Response::bad_request("email", "Email is not valid"));
+    }
+    if state.user_by_email(&migrate.email).is_some() {
+        return Err(Response::bad_request("email", "Email already in use"));
+    }
+    if let Some(username) = &migrate.username {
+        if state.user_by_username(username).is_some() {
+            return Err(Response::bad_request("username", "Username already in use"));
+        }
+    }
+
+    let mut metadata = new_user_metadata(
+        migrate.email,
+        migrate.email_confirmed,
+        migrate.existing_password_hash.is_some(),
+    );
+    metadata.enabled = migrate.enabled.unwrap_or(true);
+    metadata.mfa_enabled = migrate.existing_mfa_base32_encoded_secret.is_some();
+    metadata.legacy_user_id = migrate.existing_user_id;
+    metadata.username = migrate.username;
+    metadata.first_name = migrate.first_name;
+    metadata.last_name = migrate.last_name;
+    metadata.picture_url = migrate.picture_url;
+    metadata.properties = migrate.properties;
+
+    let user_id = state.insert_user(metadata);
+    Ok(Response::ok(&json!({ "user_id": user_id })))
+}
+
+fn fetch_user_by<F>(state: &State, request: &Request, predicate: F) -> Handled
+where
+    F: Fn(&FakeUser, &HashMap<String, String>) -> bool,
+{
+    let user = state
+        .users
+        .values()
+        .find(|user| predicate(user, &request.query))
+        .ok_or_else(Response::not_found)?;
+    Ok(Response::ok(
+        &state.user_metadata(user, request.query_bool("include_orgs")),
+    ))
+}
+
+fn fetch_users_in_batch<F>(
+    state: &State,
+    request: &Request,
+    keys: Vec<String>,
+    lookup: F,
+) -> Handled
+where
+    F: for<'a> Fn(&'a State, &str) -> Option<&'a FakeUser>,
+{
+    if keys.len() > MAX_BATCH_SIZE {
+        return Err(Response::bad_request(
+            "query",
+            "Too many values, the maximum is 100",
+        ));
+    }
+    let include_orgs = request.query_bool("include_orgs");
+    let users: Vec<UserMetadata> = keys
+        .iter()
+        .filter_map(|key| lookup(state, key))
+        .map(|user| state.user_metadata(user, include_orgs))
+        .collect();
+    Ok(Response::ok(&users))
+}
+
+fn sort_users(
+    users: &mut [UserMetadata],
+    sequences: &HashMap<String, u64>,
+    order_by: Option<&String>,
+) {
+    let sequence = |user: &UserMetadata| sequences.get(&user.user_id).copied().unwrap_or_default();
+    match order_by.map(String::as_str) {
+        Some("CREATED_AT_DESC") => users.sort_by_key(|user| std::cmp::Reverse(sequence(user))),
+        Some("LAST_ACTIVE_AT_ASC") => {
+            users.sort_by_key(|user| (user.last_active_at, sequence(user)))
+        }
+        Some("LAST_ACTIVE_AT_DESC") => {
+            users.sort_by_key(|user| std::cmp::Reverse((user.last_active_at, sequence(user))))
+        }
+        Some("EMAIL") => users.sort_by(|a, b| a.email.cmp(&b.email)),
+        Some("USERNAME") => users.sort_by(|a, b| a.username.cmp(&b.username)),
+        _ => users.sort_by_key(sequence),
+    }
+}
+
+fn user_page(request: &Request, users: Vec<UserMetadata>) -> Handled {
+    let (users, total, current_page, page_size, has_more_results) = paginate(request, users)?;
+    Ok(Response::ok(&json!({
+        "users": users,
+        "total_users": total,
+        "current_page": current_page,
+        "page_size": page_size,
+        "has_more_results": has_more_results,
+    })))
+}
+
+fn fetch_users_by_query(state: &State, request: &Request) -> Handled {
+    let include_orgs = request.query_bool("include_orgs");
+    let email_or_username = request
+        .query
+        .get("email_or_username")
+        .map(|s| s.to_lowercase());
+    let legacy_user_id = request.query.get("legacy_user_id");
+
+    let mut users: Vec<UserMetadata> = state
+        .users
+        .values()
+        .filter(|user| match &email_or_username {
+            Some(search) => {
+                user.metadata.email.to_lowercase().contains(search)
+                    || user
+                        .metadata
+                        .username
+                        .as_ref()
+                        .map(|username| username.to_lowercase().contains(search))
+                        .unwrap_or(false)
+            }
+            None => true,
+        })
+        .filter(|user| {
+            legacy_user_id.is_none() || user.metadata.legacy_user_id.as_ref() == legacy_user_id
+        })
+        .map(|user| state.user_metadata(user, include_orgs))
+        .collect();
+
+    let sequences = state
+        .users
+        .iter()
+        .map(|(user_id, user)| (user_id.clone(), user.sequence))
+        .collect();
+    sort_users(&mut users, &sequences, request.query.get("order_by"));
+    user_page(request, users)
+}
+
+fn fetch_users_in_org(state: &State, request: &Request, org_id: &str) -> Handled {
+    let org = find_org(state, org_id)?;
+    let include_orgs = request.query_bool("include_orgs");
+    let role = request.query.get("role");
+
+    let mut users: Vec<(u64, UserMetadata)> = org
+        .members
+        .iter()
+        .filter(|(_, membership)| role.is_none() || Some(&membership.role) == role)
+        .filter_map(|(user_id, membership)| {
+            let user = state.users.get(user_id)?;
+            let mut metadata = state.user_metadata(user, include_orgs);
+            metadata.role_in_org = Some(membership.role.clone());
+            metadata.additional_roles_in_org = Some(membership.additional_roles.clone());
+            Some((user.sequence, metadata))
+        })
+        .collect();
+    users.sort_by_key(|(sequence, _)| *sequence);
+    user_page(request, users.into_iter().map(|(_, user)| user).collect())
+}
+
+fn update_user_metadata(state: &mut State, request: &Request, user_id: &str) -> Handled {
+    let update: UpdateMetadataRequest = request.json()?;
+    find_user(state, user_id)?;
+    if let Some(username) = &update.username {
+        if let Some(existing) = state.user_by_username(username) {
+            if existing.metadata.user_id != user_id {
+                return Err(Response::bad_request("username", "Username already in use"));
+            }
+        }
+    }
+
+    modify_user(state, user_id, |user| {
+        if update.username.is_some() {
+            user.username = update.username;
+        }
+        if update.first_name.is_some() {
+            user.first_name = update.first_name;
+        }
+        if update.last_name.is_some() {
+            user.last_name = update.last_name;
+        }
+        if update.picture_url.is_some() {
+            user.picture_url = update.picture_url;
+        }
+        if update.legacy_user_id.is_some() {
+            user.legacy_user_id = update.legacy_user_id;
+        }
+        // Metadata and properties are replaced as a whole, not merged key by key
+        if update.metadata.is_some() {
+            user.metadata = update.metadata;
+        }
+        if update.properties.is_some() {
+            user.properties = update.properties;
+        }
+    })
+}
+
+fn update_user_email(state: &mut State, request: &Request, user_id: &str) -> Handled {
+    let update: UpdateEmailRequest = request.json()?;
+    find_user(state, user_id)?;
+    if !is_valid_email(&update.new_email) {
+        return Err(Response::bad_request("new_email", "Email is not valid"));
+    }
+    if let Some(existing) = state.user_by_email(&update.new_email) {
+        if existing.metadata.user_id != user_id {
+            return Err(Response::bad_request("new_email", "Email already in use"));
+        }
+    }
+
+    modify_user(state, user_id, |user| {
+        user.email = update.new_email;
+        user.email_confirmed = !update.require_email_confirmation;
+    })
+}
+
+fn create_magic_link(state: &mut State, request: &Request) -> Handled {
+    let create: CreateMagicLinkRequest = request.json()?;
+    if !is_valid_email(&create.email) {
+        return Err(Response::bad_request("email", "Email is not valid"));
+    }
+    if let Some(expires_in_hours) = create.expires_in_hours {
+        if expires_in_hours <= 0 {
+            return Err(Response::bad_request(
+                "expires_in_hours",
+                "Ensure this value is greater than 0",
+            ));
+        }
+    }
+    if state.user_by_email(&create.email).is_none()
+        && create.create_new_user_if_one_doesnt_exist.unwrap_or(false)
+    {
+        state.insert_user(new_user_metadata(create.email, false, false));
+    }
+
+    Ok(Response::ok(&json!({
+        "url": format!("{}/magic_link?token={}", TEST_AUTH_URL, Uuid::new_v4().simple()),
+    })))
+}
+
+fn create_org(state: &mut State, request: &Request) -> Handled {
+    let create: CreateOrgRequest = request.json()?;
+    if create.name.trim().is_empty() {
+        return Err(Response::bad_request("name", "This field may not be blank"));
+    }
+
+    let org_id = Uuid::new_v4().to_string();
+    let mut org = FetchOrgResponse::new(
+        org_id.clone(),
+        create.name.clone(),
+        Default::default(),
+        false,
+        url_safe_slug(&create.name),
+        false,
+        false,
+        create.enable_auto_joining_by_domain.unwrap_or(false),
+        create.members_must_have_matching_domain.unwrap_or(false),
+    );
+    org.domain = create.domain;
+    org.max_users = create.max_users;
+    org.custom_role_mapping_name = create.custom_role_mapping_name;
+    org.legacy_org_id = create.legacy_org_id;
+
+    let sequence = state.next_sequence();
+    state.orgs.insert(
+        org_id.clone(),
+        FakeOrg {
+            sequence,
+            org,
+            members: HashMap::new(),
+        },
+    );
+    Ok(Response::ok(
+        &json!({ "org_id": org_id, "name": create.name }),
+    ))
+}
+
+fn url_safe_slug(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn update_org(state: &mut State, request: &Request, org_id: &str) -> Handled {
+    // Both update_org and subscribe_org_to_role_mapping use this endpoint
+    let update: Value = request.json()?;
+    find_org(state, org_id)?;
+    if let Some(name) = update.get("name").and_then(Value::as_str) {
+        if name.trim().is_empty() {
+            return Err(Response::bad_request("name", "This field may not be blank"));
+        }
+    }
+
+    modify_org(state, org_id, |org| {
+        if let Some(name) = update.get("name").and_then(Value::as_str) {
+            org.name = name.to_string();
+            org.url_safe_org_slug = url_safe_slug(name);
+        }
+        if let Some(can_setup_saml) = update.get("can_setup_saml").and_then(Value::as_bool) {
+            org.can_setup_saml = can_setup_saml;
+        }
+        if let Some(max_users) = update.get("max_users").and_then(Value::as_i64) {
+            org.max_users = Some(max_users as i32);
+        }
+        if let Some(Value::Object(metadata)) = update.get("metadata") {
+            org.metadata.metadata = metadata.clone().into_iter().collect();
+        }
+        if let Some(domain) = update.get("domain").and_then(Value::as_str) {
+            org.domain = Some(domain.to_string());
+        }
+        if let Some(autojoin) = update.get("autojoin_by_domain").and_then(Value::as_bool) {
+            org.domain_autojoin = autojoin;
+        }
+        if let Some(restrict) = update.get("restrict_to_domain").and_then(Value::as_bool) {
+            org.domain_restrict = restrict;
+        }
+        if let Some(legacy_org_id) = update.get("legacy_org_id").and_then(Value::as_str) {
+            org.legacy_org_id = Some(legacy_org_id.to_string());
+        }
+        if let Some(Value::Array(extra_domains)) = update.get("extra_domains") {
+            org.extra_domains = extra_domains
+                .iter()
+                .filter_map(|domain| domain.as_str().map(str::to_string))
+                .collect();
+        }
+        if let Some(name) = update
+            .get("custom_role_mapping_name")
+            .and_then(Value::as_str)
+        {
+            org.custom_role_mapping_name = Some(name.to_string());
+        }
+    })
+}
+
+fn fetch_orgs_by_query(state: &State, request: &Request) -> Handled {
+    let name = request.query.get("name").map(|s| s.to_lowercase());
+    let legacy_org_id = request.query.get("legacy_org_id");
+    let domain = request.query.get("domain");
+
+    let mut orgs: Vec<&FakeOrg> = state
+        .orgs
+        .values()
+        .filter(|org| match &name {
+            Some(name) => org.org.name.to_lowercase().contains(name),
+            None => true,
+        })
+        .filter(|org| legacy_org_id.is_none() || org.org.legacy_org_id.as_ref() == legacy_org_id)
+        .filter(|org| domain.is_none() || org.org.domain.as_ref() == domain)
+        .collect();
+    match request.query.get("order_by").map(String::as_str) {
+        Some("CREATED_AT_DESC") => orgs.sort_by_key(|org| std::cmp::Reverse(org.sequence)),
+        Some("NAME") => orgs.sort_by(|a, b| a.org.name.cmp(&b.org.name)),
+        _ => orgs.sort_by_key(|org| org.sequence),
+    }
+
+    let orgs: Vec<FetchOrgBasicResponse> = orgs
+        .into_iter()
+        .map(|org| FetchOrgBasicResponse {
+            org_id: org.org.org_id.clone(),
+            name: org.org.name.clone(),
+            is_saml_configured: org.org.is_saml_configured,
+            metadata: org.org.metadata.clone(),
+            max_users: org.org.max_users,
+            custom_role_mapping_name: org.org.custom_role_mapping_name.clone(),
+            legacy_org_id: org.org.legacy_org_id.clone(),
+        })
+        .collect();
+    let (orgs, total, current_page, page_size, has_more_results) = paginate(request, orgs)?;
+    Ok(Response::ok(&json!({
+        "orgs": orgs,
+        "total_orgs": total,
+        "current_page": current_page,
+        "page_size": page_size,
+        "has_more_results": has_more_results,
+    })))
+}
+
+fn set_membership(
+    state: &mut State,
+    org_id: &str,
+    user_id: &str,
+    role: String,
+    additional_roles: Vec<String>,
+) -> Handled {
+    find_user(state, user_id)?;
+    find_org(state, org_id)?;
+    if role.trim().is_empty() {
+        return Err(Response::bad_request("role", "Unknown role"));
+    }
+
+    if let Some(org) = state.orgs.get_mut(org_id) {
+        org.members.insert(
+            user_id.to_string(),
+            Membership {
+                role,
+                additional_roles,
+            },
+        );
+    }
+    Ok(Response::success())
+}
+
+fn invite_user_to_org(state: &mut State, request: &Request) -> Handled {
+    let invite: InviteUserToOrgRequest = request.json()?;
+    let org = find_org(state, &invite.org_id)?;
+    if !is_valid_email(&invite.email) {
+        return Err(Response::bad_request("email", "Email is not valid"));
+    }
+    if invite.role.trim().is_empty() {
+        return Err(Response::bad_request("role", "Unknown role"));
+    }
+    if let Some(user) = state.user_by_email(&invite.email) {
+        if org.members.contains_key(&user.metadata.user_id) {
+            return Err(Response::bad_request(
+                "email",
+                "User is already a member of this org",
+            ));
+        }
+    }
+
+    let created_at = Utc::now().timestamp();
+    state.invites.retain(|existing| {
+        existing.org_id != invite.org_id
+            || !existing.invitee_email.eq_ignore_ascii_case(&invite.email)
+    });
+    state.invites.push(FakeInvite {
+        invitee_email: invite.email,
+        org_id: invite.org_id,
+        role: invite.role,
+        additional_roles: invite.additional_roles,
+        created_at,
+        expires_at: created_at + Duration::days(7).num_seconds(),
+    });
+    Ok(Response::success())
+}
+
+fn fetch_pending_invites(state: &State, request: &Request) -> Handled {
+    let org_id = request.query.get("org_id");
+    let invites: Vec<Value> = state
+        .invites
+        .iter()
+        .filter(|invite| org_id.is_none() || Some(&invite.org_id) == org_id)
+        .map(|invite| {
+            json!({
+                "invitee_email": invite.invitee_email,
+                "org_id": invite.org_id,
+                "org_name": state.orgs.get(&invite.org_id).map(|org| org.org.name.clone()),
+                "role_in_org": invite.role,
+                "additional_roles_in_org": invite.additional_roles,
+                "created_at": invite.created_at,
+                "expires_at": invite.expires_at,
+            })
+        })
+        .collect();
+    let (invites, total, current_page, page_size, has_more_results) = paginate(request, invites)?;
+    Ok(Response::ok(&json!({
+        "invites": invites,
+        "total_invites": total,
+        "current_page": current_page,
+        "page_size": page_size,
+        "has_more_results": has_more_results,
+    })))
+}
+
+fn fetch_api_keys(state: &State, request: &Request, archived: bool) -> Handled {
+    let user_id = request.query.get("user_id").cloned().or_else(|| {
+        request.query.get("user_email").map(|email| {
+            state
+                .user_by_email(email)
+                .map(|user| user.metadata.user_id.clone())
+                .unwrap_or_default()
+        })
+    });
+    let org_id = request.query.get("org_id");
+
+    let mut api_keys: Vec<&FakeApiKey> = state
+        .api_keys
+        .values()
+        .filter(|api_key| api_key.archived == archived)
+        .filter(|api_key| user_id.is_none() || api_key.api_key.user_id == user_id)
+        .filter(|api_key| org_id.is_none() || api_key.api_key.org_id.as_ref() == org_id)
+        .collect();
+    api_keys.sort_by_key(|api_key| api_key.sequence);
+
+    let api_keys = api_keys
+        .into_iter()
+        .map(|api_key| api_key.api_key.clone())
+        .collect();
+    let (api_keys, total, current_page, page_size, has_more_results) = paginate(request, api_keys)?;
+    Ok(Response::ok(&json!({
+        "api_keys": api_keys,
+        "total_api_keys": total,
+        "current_page": current_page,
+        "page_size": page_size,
+        "has_more_results": has_more_results,
+    })))
+}
+
+fn create_api_key(state: &mut State, request: &Request) -> Handled {
+    let create: Value = request.json()?;
+    let user_id = create["user_id"].as_str().map(str::to_string);
+    let org_id = create["org_id"].as_str().map(str::to_string);
+    if let Some(user_id) = &user_id {
+        find_user(state, user_id)?;
+    }
+    if let Some(org_id) = &org_id {
+        find_org(state, org_id)?;
+    }
+
+    let api_key_id = Uuid::new_v4().simple().to_string();
+    let token = format!("{}{}", api_key_id, Uuid::new_v4().simple());
+    let sequence = state.next_sequence();
+    state.api_keys.insert(
+        api_key_id.clone(),
+        FakeApiKey {
+            sequence,
+            api_key: FetchApiKeyResponse {
+                api_key_id: api_key_id.clone(),
+                created_at: Utc::now().timestamp(),
+                expires_at_seconds: create["expires_at_seconds"].as_i64(),
+                metadata: create.get("metadata").filter(|m| !m.is_null()).cloned(),
+                user_id,
+                org_id,
+            },
+            token: token.clone(),
+            archived: false,
+        },
+    );
+    Ok(Response::ok(&json!({
+        "api_key_id": api_key_id,
+        "api_key_token": token,
+    })))
+}
+
+fn update_api_key(state: &mut State, request: &Request, api_key_id: &str) -> Handled {
+    let update: Value = request.json()?;
+    find_api_key(state, api_key_id)?;
+    if let Some(api_key) = state.api_keys.get_mut(api_key_id) {
+        if let Some(expires_at_seconds) = update["expires_at_seconds"].as_i64() {
+            api_key.api_key.expires_at_seconds = Some(expires_at_seconds);
+        }
+        if update["set_to_never_expire"].as_bool() == Some(true) {
+            api_key.api_key.expires_at_seconds = None;
+        }
+        if let Some(metadata) = update.get("metadata").filter(|m| !m.is_null()) {
+            api_key.api_key.metadata = Some(metadata.clone());
+        }
+    }
+    Ok(Response::success())
+}
+
+fn validate_api_key(state: &State, request: &Request) -> Handled {
+    let validate: Value = request.json()?;
+    let token = validate["api_key_token"].as_str().unwrap_or_default();
+    let now = Utc::now().timestamp();
+    let invalid = || Response {
+        status: 401,
+        body: json!({ "api_key_token": "Invalid API key" }).to_string(),
+    };
+
+    let api_key = state
+        .api_keys
+        .values()
+        .find(|api_key| api_key.token == token && !api_key.archived)
+        .filter(|api_key| {
+            api_key
+                .api_key
+                .expires_at_seconds
+                .map(|expires_at| expires_at > now)
+                .unwrap_or(true)
+        })
+        .ok_or_else(invalid)?;
+
+    let user = match &api_key.api_key.user_id {
+        Some(user_id) => Some(state.users.get(user_id).ok_or_else(invalid)?),
+        None => None,
+    };
+    let org = match &api_key.api_key.org_id {
+        Some(org_id) => Some(state.orgs.get(org_id).ok_or_else(invalid)?),
+        None => None,
+    };
+    let user_in_org = match (user, org) {
+        (Some(user), Some(org)) => org
+            .members
+            .get(&user.metadata.user_id)
+            .map(|membership| user_in_org(org, membership)),
+        _ => None,
+    };
+
+    Ok(Response::ok(&json!({
+        "metadata": api_key.api_key.metadata,
+        "user": user.map(|user| state.user_metadata(user, false)),
+        "org": org.map(|org| json!({
+            "org_id": org.org.org_id,
+            "org_name": org.org.name,
+            "can_setup_saml": org.org.can_setup_saml,
+            "max_users": org.org.max_users,
+            "metadata": org.org.metadata.metadata,
+        })),
+        "user_in_org": user_in_org,
+        "user_id": api_key.api_key.user_id,
+        "org_id": api_key.api_key.org_id,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use crate::apis::access_token_service_api::CreateAccessTokenV2Params;
+    use crate::apis::api_key_service_api::{
+        CreateApiKeyParams, UpdateApiKeyParams, ValidateApiKeyParams,
+    };
+    use crate::apis::org_service_api::FetchUsersInOrgParams;
+    use crate::apis::user_service_api::{
+        FetchUserByIdParams, FetchUsersByQueryParams, UpdateUserMetadataParams,
+    };
+    use crate::models::{
+        AddUserToOrgRequest, BadCreateUserRequest, CreateAccessTokenV2Request, CreateOrgRequest,
+        CreateUserRequest, UpdateMetadataRequest, UpdateOrgRequest,
+    };
+    use crate::propelauth::errors::{CreateUserError, ErrorsWithNotFound, FetchByQueryError};
+    use crate::propelauth::ids::{ApiKeyId, OrgId};
+    use crate::propelauth::options::{RequiredOrg, UserRequirementsInOrg};
+    use crate::testing::FakeBackend;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn users_orgs_and_access_tokens() {
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+
+        runtime().block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    email_confirmed: true,
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            let org_id = auth
+                .org()
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id;
+            auth.org()
+                .add_user_to_org(AddUserToOrgRequest {
+                    user_id: user_id.clone(),
+                    org_id: org_id.clone(),
+                    role: "Admin".to_string(),
+                    additional_roles: vec![],
+                })
+                .await
+                .unwrap();
+
+            let user = auth
+                .user()
+                .fetch_user_by_id(FetchUserByIdParams {
+                    user_id: user_id.clone(),
+                    include_orgs: Some(true),
+                })
+                .await
+                .unwrap();
+            assert_eq!("user@example.com", user.email);
+            assert_eq!("Admin", user.org_id_to_org_info.unwrap()[&org_id].user_role);
+
+            let users_in_org = auth
+                .org()
+                .fetch_users_in_org(FetchUsersInOrgParams {
+                    org_id: org_id.clone(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            assert_eq!(1, users_in_org.total_users);
+            assert_eq!(Some("Admin".to_string()), users_in_org.users[0].role_in_org);
+
+            let access_token = auth
+                .access_token()
+                .create_access_token_v2(CreateAccessTokenV2Params {
+                    create_access_token_request: CreateAccessTokenV2Request {
+                        user_id: user_id.clone(),
+                        duration_in_minutes: 10,
+                        active_org_id: None,
+                    },
+                })
+                .await
+                .unwrap()
+                .access_token;
+            let verified = auth
+                .verify()
+                .validate_authorization_header(&format!("Bearer {}", access_token))
+                .unwrap();
+            assert!(verified
+                .validate_org_membership(
+                    RequiredOrg::OrgId(&org_id),
+                    UserRequirementsInOrg::IsRole("Admin")
+                )
+                .is_ok());
+
+            let created_key = auth
+                .api_key()
+                .create_api_key(CreateApiKeyParams {
+                    user_id: Some(user_id.clone()),
+                    metadata: Some(serde_json::json!({ "team": "infra" })),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            let validated = auth
+                .api_key()
+                .validate_api_key(ValidateApiKeyParams {
+                    api_key_token: created_key.api_key_token,
+                })
+                .await
+                .unwrap();
+            assert_eq!(Some(user_id.clone()), validated.user_id);
+
+            let expires_at = chrono::DateTime::from_timestamp(1_893_456_000, 0).unwrap();
+            let mut params = CreateApiKeyParams {
+                org_id: Some(org_id.clone()),
+                ..Default::default()
+            };
+            params.set_expires_at(Some(expires_at));
+            let api_key_id: ApiKeyId = auth
+                .api_key()
+                .create_api_key(params)
+                .await
+                .unwrap()
+                .api_key_id
+                .parse()
+                .unwrap();
+            let api_key = auth
+                .api_key()
+                .fetch_api_key_by_id(api_key_id)
+                .await
+                .unwrap();
+            assert_eq!(Some(expires_at), api_key.expires_at_utc());
+            assert!(api_key.created_at_utc().unwrap() <= chrono::Utc::now());
+
+            let mut params = UpdateApiKeyParams::default();
+            params.set_expires_at(None);
+            auth.api_key()
+                .update_api_key_by_id(api_key_id, params)
+                .await
+                .unwrap();
+            let api_key = auth
+                .api_key()
+                .fetch_api_key_by_id(api_key_id)
+                .await
+                .unwrap();
+            assert_eq!(None, api_key.expires_at_utc());
+
+            auth.user()
+                .delete_user_by_id(user_id.parse().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(
+                Err(ErrorsWithNotFound::NotFound),
+                auth.user()
+                    .fetch_user_by_id(FetchUserByIdParams {
+                        user_id,
+                        include_orgs: None,
+                    })
+                    .await
+            );
+        });
+    }
+
+    #[test]
+    fn metadata_is_replaced_as_a_whole() {
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+
+        runtime().block_on(async {
+            let user_id = auth
+                .user()
+                .create_user(CreateUserRequest {
+                    email: "user@example.com".to_string(),
+                    properties: Some(HashMap::from([("plan".to_string(), json!("pro"))])),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .user_id;
+            for metadata in [
+                json!({"plan": "pro", "theme": "dark"}),
+                json!({"plan": "free"}),
+            ] {
+                auth.user()
+                    .update_user_metadata(UpdateUserMetadataParams {
+                        user_id: user_id.clone(),
+                        update_metadata_request: UpdateMetadataRequest {
+                            metadata: serde_json::from_value(metadata).unwrap(),
+                            ..Default::default()
+                        },
+                    })
+                    .await
+                    .unwrap();
+            }
+            let user = fake.users().pop().unwrap();
+            assert_eq!(
+                Some(HashMap::from([("plan".to_string(), json!("free"))])),
+                user.metadata
+            );
+            assert_eq!(
+                Some(HashMap::from([("plan".to_string(), json!("pro"))])),
+                user.properties
+            );
+
+            let org_id: OrgId = auth
+                .org()
+                .create_org(CreateOrgRequest {
+                    name: "Acme".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .org_id
+                .parse()
+                .unwrap();
+            for metadata in [json!({"plan": "pro", "seats": 5}), json!({"seats": 10})] {
+                auth.org()
+                    .update_org_by_id(
+                        org_id,
+                        UpdateOrgRequest {
+                            metadata: serde_json::from_value(metadata).unwrap(),
+                            ..Default::default()
+                        },
+                    )
+                    .await
+                    .unwrap();
+            }
+            let org = auth.org().fetch_org_by_id(org_id).await.unwrap();
+            assert_eq!(
+                HashMap::from([("seats".to_string(), json!(10))]),
+                org.metadata.metadata
+            );
+        });
+    }
+
+    #[test]
+    fn errors_match_the_real_api() {
+        let fake = FakeBackend::start();
+        let auth = fake.auth();
+
+        runtime().block_on(async {
+            let create_user = |email: &str| CreateUserRequest {
+                email: email.to_string(),
+                ..Default::default()
+            };
+            auth.user()
+                .create_user(create_user("taken@example.com"))
+                .await
+                .unwrap();
+
+            assert_eq!(
+                Err(CreateUserError::BadRequest(BadCreateUserRequest {
+                    email: Some(vec!["Email already in use".to_string()]),
+                    ..Default::default()
+                })),
+                auth.user()
+                    .create_user(create_user("taken@example.com"))
+                    .await
+            );
+
+            assert!(matches!(
+                auth.user()
+                    .fetch_users_by_query(FetchUsersByQueryParams {
+                        page_size: Some(1000),
+                        ..Default::default()
+                    })
+                    .await,
+                Err(FetchByQueryError::BadRequest(_))
+            ));
+
+            fake.rate_limit_next_requests(1);
+            assert_eq!(
+                Err(CreateUserError::PropelAuthRateLimit),
+                auth.user()
+                    .create_user(create_user("new@example.com"))
+                    .await
+            );
+            assert!(auth
+                .user()
+                .create_user(create_user("new@example.com"))
+                .await
+                .is_ok());
+
+            let mut wrong_key = auth.clone();
+            wrong_key.config.bearer_access_token = Some("wrong".to_string());
+            assert_eq!(
+                Err(CreateUserError::InvalidApiKey),
+                wrong_key
+                    .user()
+                    .create_user(create_user("other@example.com"))
+                    .await
+            );
+        });
+
+        assert_eq!(2, fake.users().len());
+        assert!(fake.modify_user(&fake.users()[0].user_id, |user| user.last_active_at = 0));
+        assert_eq!(0, fake.users()[0].last_active_at);
+    }
+}
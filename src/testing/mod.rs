@@ -0,0 +1,36 @@
+//! Helpers for testing code that depends on this library, e.g. axum or actix handlers that
+//! take a [`User`](crate::propelauth::token_models::User) as an argument.
+//!
+//! Enable the `testing` feature (typically as a dev-dependency) to use it:
+//!
+//! ```rust
+//! let test_auth = TestAuth::new();
+//!
+//! let user = User {
+//!     user_id: "4c2a0a6c-3a5c-4c1d-9a5e-2fbd2b0f8b3e".to_string(),
+//!     email: "user@customer.com".to_string(),
+//!     org_id_to_org_member_info: HashMap::from([(
+//!         "org_id".to_string(),
+//!         OrgMemberInfoBuilder::single_role_in_hierarchy("org_id", "Acme", "Admin")
+//!             .inherited_roles(["Member"])
+//!             .permissions(["can_view_billing"])
+//!             .build(),
+//!     )]),
+//!     ..Default::default()
+//! };
+//!
+//! // Use test_auth.auth() wherever you'd use the PropelAuth from fetch_and_init
+//! let authorization_header = test_auth.authorization_header(&user);
+//! ```
+
+#[cfg(feature = "__reqwest")]
+mod fake_backend;
+mod org_member_info;
+mod test_auth;
+
+#[cfg(feature = "__reqwest")]
+pub use self::fake_backend::FakeBackend;
+pub use self::org_member_info::OrgMemberInfoBuilder;
+pub use self::test_auth::{TestAuth, TokenOptions};
+#[cfg(feature = "__reqwest")]
+pub use crate::propelauth::traits::{MockAccessTokenApi, MockApiKeyApi, MockOrgApi, MockUserApi};
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::propelauth::token_models::{OrgMemberInfo, OrgRoleStructure};
+
+/// Builds an [`OrgMemberInfo`] that looks like the ones found in real access tokens
+#[derive(Debug, Clone)]
+pub struct OrgMemberInfoBuilder {
+    org_member_info: OrgMemberInfo,
+}
+
+impl OrgMemberInfoBuilder {
+    /// A member of an org which uses a single role per user, where roles inherit from the
+    /// roles below them. Use [`inherited_roles`](Self::inherited_roles) to list those.
+    pub fn single_role_in_hierarchy(org_id: &str, org_name: &str, role: &str) -> Self {
+        OrgMemberInfoBuilder {
+            org_member_info: OrgMemberInfo {
+                org_id: org_id.to_string(),
+                org_name: org_name.to_string(),
+                org_metadata: HashMap::new(),
+                url_safe_org_name: org_name.to_string(),
+                org_role_structure: OrgRoleStructure::SingleRoleInHierarchy,
+                user_role: role.to_string(),
+                inherited_user_roles_plus_current_role: vec![role.to_string()],
+                user_permissions: vec![],
+                additional_roles: vec![],
+            },
+        }
+    }
+
+    /// A member of an org where users can have multiple roles. The first role becomes the
+    /// `user_role` and the rest become `additional_roles`.
+    ///
+    /// Panics if `roles` is empty.
+    pub fn multi_role<I, S>(org_id: &str, org_name: &str, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut roles = roles.into_iter().map(Into::into);
+        let user_role = roles.next().expect("multi_role requires at least one role");
+
+        OrgMemberInfoBuilder {
+            org_member_info: OrgMemberInfo {
+                org_id: org_id.to_string(),
+                org_name: org_name.to_string(),
+                org_metadata: HashMap::new(),
+                url_safe_org_name: org_name.to_string(),
+                org_role_structure: OrgRoleStructure::MultiRole,
+                inherited_user_roles_plus_current_role: vec![user_role.clone()],
+                user_role,
+                user_permissions: vec![],
+                additional_roles: roles.collect(),
+            },
+        }
+    }
+
+    /// Roles the user's role inherits from, in addition to the role itself
+    pub fn inherited_roles<I, S>(mut self, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.org_member_info
+            .inherited_user_roles_plus_current_role
+            .extend(roles.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn permissions<I, S>(mut self, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.org_member_info
+            .user_permissions
+            .extend(permissions.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn url_safe_org_name(mut self, url_safe_org_name: &str) -> Self {
+        self.org_member_info.url_safe_org_name = url_safe_org_name.to_string();
+        self
+    }
+
+    pub fn org_metadata(mut self, org_metadata: HashMap<String, Value>) -> Self {
+        self.org_member_info.org_metadata = org_metadata;
+        self
+    }
+
+    pub fn build(self) -> OrgMemberInfo {
+        self.org_member_info
+    }
+}
@@ -0,0 +1,382 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use openssl::rsa::Rsa;
+use serde_json::{json, Map, Value};
+
+use crate::models::AuthTokenVerificationMetadata;
+use crate::propelauth::auth::PropelAuth;
+use crate::propelauth::options::VerifyOnlyAuthOptions;
+use crate::propelauth::token_models::{IdentityProvider, LoginMethod, SocialLoginType, User};
+
+pub(super) const TEST_AUTH_URL: &str = "https://auth.propelauth.test";
+
+/// A [`PropelAuth`] backed by a freshly generated key pair, which can mint access tokens
+/// that it (and only it) will accept.
+#[derive(Clone)]
+pub struct TestAuth {
+    auth: PropelAuth,
+    encoding_key: EncodingKey,
+}
+
+/// Controls how a token is minted by [`TestAuth::mint_token_with_options`]
+#[derive(Debug, Clone)]
+pub struct TokenOptions {
+    /// How long until the token expires. Use a negative duration for an expired token.
+    pub expires_in: Duration,
+    /// Additional claims to add to the token. These override the claims derived from the user.
+    pub extra_claims: HashMap<String, Value>,
+}
+
+impl Default for TokenOptions {
+    fn default() -> Self {
+        TokenOptions {
+            expires_in: Duration::hours(1),
+            extra_claims: HashMap::new(),
+        }
+    }
+}
+
+impl TestAuth {
+    /// Generates a new key pair and a `PropelAuth` which trusts it.
+    ///
+    /// Panics if the key pair can't be generated.
+    pub fn new() -> TestAuth {
+        TestAuth::with_auth_url(TEST_AUTH_URL)
+    }
+
+    /// Same as [`new`](Self::new), but tokens are issued for `auth_url` instead of a
+    /// placeholder. Useful when the code under test checks the issuer.
+    ///
+    /// Panics if `auth_url` is invalid or the key pair can't be generated.
+    pub fn with_auth_url(auth_url: &str) -> TestAuth {
+        let rsa = Rsa::generate(2048).expect("Unable to generate an RSA key");
+        let public_key_pem = String::from_utf8(
+            rsa.public_key_to_pem()
+                .expect("Unable to encode the public key"),
+        )
+        .expect("Public key PEM is not valid UTF-8");
+        let private_key_pem = rsa
+            .private_key_to_pem()
+            .expect("Unable to encode the private key");
+
+        let auth = PropelAuth::init_verify_only(VerifyOnlyAuthOptions {
+            auth_url: auth_url.to_string(),
+            token_verification_metadata: AuthTokenVerificationMetadata { public_key_pem },
+        })
+        .expect("Invalid auth_url");
+
+        TestAuth {
+            auth,
+            encoding_key: EncodingKey::from_rsa_pem(&private_key_pem)
+                .expect("Unable to load the private key"),
+        }
+    }
+
+    /// The `PropelAuth` which accepts tokens minted by this `TestAuth`
+    pub fn auth(&self) -> &PropelAuth {
+        &self.auth
+    }
+
+    /// The public key tokens are signed with, in case you need to configure something else
+    pub fn token_verification_metadata(&self) -> AuthTokenVerificationMetadata {
+        self.auth.verify().token_verification_metadata.clone()
+    }
+
+    /// Mints an access token for `user` which expires in an hour
+    pub fn mint_token(&self, user: &User) -> String {
+        self.mint_token_with_options(user, TokenOptions::default())
+    }
+
+    /// Mints an access token for `user`, formatted as an Authorization header
+    pub fn authorization_header(&self, user: &User) -> String {
+        format!("Bearer {}", self.mint_token(user))
+    }
+
+    /// Mints an access token for `user` with a custom expiry and/or extra claims.
+    ///
+    /// Orgs, the active org, metadata, properties, impersonation and the login method are all
+    /// encoded the same way PropelAuth encodes them.
+    pub fn mint_token_with_options(&self, user: &User, options: TokenOptions) -> String {
+        let iat = Utc::now().timestamp();
+        let exp = iat + options.expires_in.num_seconds();
+
+        let mut claims = Map::new();
+        claims.insert("iat".to_string(), json!(iat));
+        claims.insert("exp".to_string(), json!(exp));
+        claims.insert("iss".to_string(), json!(self.auth.verify().issuer));
+        claims.insert("user_id".to_string(), json!(user.user_id));
+        claims.insert("email".to_string(), json!(user.email));
+        claims.insert("first_name".to_string(), json!(user.first_name));
+        claims.insert("last_name".to_string(), json!(user.last_name));
+        claims.insert("username".to_string(), json!(user.username));
+        claims.insert("legacy_user_id".to_string(), json!(user.legacy_user_id));
+        claims.insert("properties".to_string(), json!(user.properties));
+        claims.insert("metadata".to_string(), json!(user.metadata));
+        claims.insert(
+            "impersonator_user_id".to_string(),
+            json!(user.impersonator_user_id),
+        );
+        claims.insert(
+            "login_method".to_string(),
+            login_method_claim(&user.login_method),
+        );
+
+        // Tokens scoped to an active org only carry that org
+        match user.get_active_org() {
+            Some(org_member_info) => {
+                claims.insert("org_member_info".to_string(), json!(org_member_info));
+            }
+            None => {
+                claims.insert(
+                    "org_id_to_org_member_info".to_string(),
+                    json!(user.org_id_to_org_member_info),
+                );
+            }
+        }
+
+        claims.extend(options.extra_claims);
+
+        encode(&Header::new(Algorithm::RS256), &claims, &self.encoding_key)
+            .expect("Unable to sign the access token")
+    }
+}
+
+impl Default for TestAuth {
+    fn default() -> Self {
+        TestAuth::new()
+    }
+}
+
+impl std::fmt::Debug for TestAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestAuth")
+            .field("auth", &self.auth)
+            .finish_non_exhaustive()
+    }
+}
+
+fn login_method_claim(login_method: &LoginMethod) -> Value {
+    match login_method {
+        LoginMethod::Password => json!({ "login_method": "password" }),
+        LoginMethod::MagicLink => json!({ "login_method": "magic_link" }),
+        LoginMethod::SocialSso(provider) => json!({
+            "login_method": "social_sso",
+            "provider": social_login_type_name(provider),
+        }),
+        LoginMethod::EmailConfirmationLink => json!({ "login_method": "email_confirmation_link" }),
+        LoginMethod::SamlSso(provider, org_id) => json!({
+            "login_method": "saml_sso",
+            "provider": identity_provider_name(provider),
+            "org_id": org_id,
+        }),
+        LoginMethod::Impersonation => json!({ "login_method": "impersonation" }),
+        LoginMethod::TokenGeneratedFromBackendApi => {
+            json!({ "login_method": "generated_from_backend_api" })
+        }
+        LoginMethod::Unknown => json!({ "login_method": "unknown" }),
+    }
+}
+
+fn social_login_type_name(social_login_type: &SocialLoginType) -> &'static str {
+    match social_login_type {
+        SocialLoginType::Google => "Google",
+        SocialLoginType::Github => "GitHub",
+        SocialLoginType::Microsoft => "Microsoft",
+        SocialLoginType::Slack => "Slack",
+        SocialLoginType::Salesforce => "Salesforce",
+        SocialLoginType::Linkedin => "LinkedIn",
+        SocialLoginType::Quickbooks => "QuickBooks Online",
+        SocialLoginType::Xero => "Xero",
+    }
+}
+
+// The names PropelAuth puts in the token, i.e. the inverse of `IdentityProvider::from_str`
+fn identity_provider_name(identity_provider: &IdentityProvider) -> &'static str {
+    match identity_provider {
+        IdentityProvider::Google => "Google",
+        IdentityProvider::Rippling => "Rippling",
+        IdentityProvider::OneLogin => "OneLogin",
+        IdentityProvider::JumpCloud => "JumpCloud",
+        IdentityProvider::Okta => "Okta",
+        IdentityProvider::Azure => "Azure",
+        IdentityProvider::Duo => "Duo",
+        IdentityProvider::Generic => "Generic",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::Duration;
+    use serde_json::json;
+
+    use crate::propelauth::errors::{DetailedAuthError, UnauthorizedError};
+    use crate::propelauth::options::{RequiredOrg, UserRequirementsInOrg};
+    use crate::propelauth::token_models::{IdentityProvider, LoginMethod, SocialLoginType, User};
+    use crate::testing::{OrgMemberInfoBuilder, TestAuth, TokenOptions};
+
+    #[test]
+    fn minted_tokens_round_trip() {
+        let test_auth = TestAuth::new();
+        let user = User {
+            user_id: "bf7b3bc0-739d-45a2-ba60-60655249a5b0".to_string(),
+            email: "easteregg@propelauth.com".to_string(),
+            first_name: Some("Easter".to_string()),
+            org_id_to_org_member_info: HashMap::from([
+                (
+                    "org_id_1".to_string(),
+                    OrgMemberInfoBuilder::single_role_in_hierarchy("org_id_1", "Acme", "Admin")
+                        .inherited_roles(["Member"])
+                        .permissions(["can_view_billing"])
+                        .build(),
+                ),
+                (
+                    "org_id_2".to_string(),
+                    OrgMemberInfoBuilder::multi_role("org_id_2", "Globex", ["Role A", "Role B"])
+                        .build(),
+                ),
+            ]),
+            metadata: HashMap::from([("plan".to_string(), "pro".to_string())]),
+            impersonator_user_id: Some("impersonator".to_string()),
+            login_method: LoginMethod::SocialSso(SocialLoginType::Github),
+            ..Default::default()
+        };
+
+        let verified = test_auth
+            .auth()
+            .verify()
+            .validate_authorization_header(&test_auth.authorization_header(&user))
+            .unwrap();
+        assert_eq!(user, verified);
+        assert!(verified
+            .validate_org_membership(
+                RequiredOrg::OrgName("Acme"),
+                UserRequirementsInOrg::IsAtLeastRole("Member")
+            )
+            .is_ok());
+        assert!(verified
+            .validate_org_membership(
+                RequiredOrg::OrgId("org_id_2"),
+                UserRequirementsInOrg::IsRole("Role B")
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn active_org_and_saml_login_method_round_trip() {
+        let test_auth = TestAuth::new();
+        let user = User {
+            user_id: "bf7b3bc0-739d-45a2-ba60-60655249a5b0".to_string(),
+            active_org_id: Some("org_id_1".to_string()),
+            org_id_to_org_member_info: HashMap::from([(
+                "org_id_1".to_string(),
+                OrgMemberInfoBuilder::single_role_in_hierarchy("org_id_1", "Acme", "Owner").build(),
+            )]),
+            login_method: LoginMethod::SamlSso(IdentityProvider::Okta, "org_id_1".to_string()),
+            ..Default::default()
+        };
+
+        let verified = test_auth
+            .auth()
+            .verify()
+            .validate_authorization_header(&test_auth.authorization_header(&user))
+            .unwrap();
+        assert_eq!(user, verified);
+    }
+
+    #[test]
+    fn every_sso_provider_round_trips() {
+        let test_auth = TestAuth::new();
+        let auth = test_auth.auth();
+        let mut login_methods = vec![];
+        for provider in [
+            SocialLoginType::Google,
+            SocialLoginType::Github,
+            SocialLoginType::Microsoft,
+            SocialLoginType::Slack,
+            SocialLoginType::Salesforce,
+            SocialLoginType::Linkedin,
+            SocialLoginType::Quickbooks,
+            SocialLoginType::Xero,
+        ] {
+            login_methods.push(LoginMethod::SocialSso(provider));
+        }
+        for provider in [
+            IdentityProvider::Google,
+            IdentityProvider::Rippling,
+            IdentityProvider::OneLogin,
+            IdentityProvider::JumpCloud,
+            IdentityProvider::Okta,
+            IdentityProvider::Azure,
+            IdentityProvider::Duo,
+            IdentityProvider::Generic,
+        ] {
+            login_methods.push(LoginMethod::SamlSso(provider, "org_id_1".to_string()));
+        }
+
+        for login_method in login_methods {
+            let user = User {
+                user_id: "bf7b3bc0-739d-45a2-ba60-60655249a5b0".to_string(),
+                email: "easteregg@propelauth.com".to_string(),
+                login_method: login_method.clone(),
+                ..Default::default()
+            };
+            let verified = auth
+                .verify()
+                .validate_authorization_header(&test_auth.authorization_header(&user))
+                .unwrap();
+            assert_eq!(login_method, verified.login_method);
+        }
+    }
+
+    #[test]
+    fn options_control_expiry_and_claims() {
+        let test_auth = TestAuth::new();
+        let user = User {
+            user_id: "bf7b3bc0-739d-45a2-ba60-60655249a5b0".to_string(),
+            ..Default::default()
+        };
+
+        let expired = test_auth.mint_token_with_options(
+            &user,
+            TokenOptions {
+                expires_in: Duration::hours(-1),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            test_auth
+                .auth()
+                .verify()
+                .validate_authorization_header(&format!("Bearer {}", expired))
+                .err(),
+            Some(UnauthorizedError::Unauthorized(
+                DetailedAuthError::CannotVerifyToken
+            ))
+        );
+
+        let with_extra_claims = test_auth.mint_token_with_options(
+            &user,
+            TokenOptions {
+                extra_claims: HashMap::from([("email".to_string(), json!("other@example.com"))]),
+                ..Default::default()
+            },
+        );
+        let verified = test_auth
+            .auth()
+            .verify()
+            .validate_authorization_header(&format!("Bearer {}", with_extra_claims))
+            .unwrap();
+        assert_eq!("other@example.com", verified.email);
+
+        let other_test_auth = TestAuth::new();
+        assert!(other_test_auth
+            .auth()
+            .verify()
+            .validate_authorization_header(&test_auth.authorization_header(&user))
+            .is_err());
+    }
+}